@@ -1,25 +1,52 @@
 
+use std::any::TypeId;
+use std::fmt::Write;
+use std::time::Duration;
+
+use log::LevelFilter;
+
+use crate::langs::lang_type::LangType;
+use crate::parser::arg::{Arg, ArgAction};
 use crate::parser::args_parser::ArgParser;
 
 use crate::config::Config;
-use crate::report::Report;
-use crate::fc::FileCounter;
+use crate::counter::Counter;
+use crate::report::{Discrepancy, LangDelta, Report};
+use crate::fc::{verify_parity, FileCounter};
 use crate::fc::AsyncFileCounter;
+use crate::history::{collect_history, write_history_csv};
+use crate::saver::{CiExporter, FileSaver, JsonExporter, TextExporter};
+use crate::syntax::LineExplanation;
+use crate::utils::chart::ChartDrawer;
+use crate::utils::color::Painter;
+use crate::utils::format::OutputFormat;
+use crate::utils::save::export_report;
+use crate::utils::terminal::terminal_width;
+use crate::consts::COMPACT_TERMINAL_WIDTH;
 
 pub struct Cli{
     arg_parser: ArgParser,
+    painter: Painter,
+    /// 文本输出使用紧凑格式
+    compact: bool,
+    /// 表格中显示待办标记数
+    todos: bool,
+    /// 只输出统计结果，不输出进度与提示信息
+    quiet: bool,
 }
 
 impl Cli {
     pub fn new() -> Self {
         Cli {
             arg_parser: ArgParser::default(),
+            painter: Painter::default(),
+            compact: false,
+            todos: false,
+            quiet: false,
         }
     }
 
     pub fn run(&mut self) -> Result<(), String> {
-        use tokio::runtime::Runtime;
-
         let args = std::env::args().skip(1);
         let matches = self.arg_parser
             .build_matches(args)
@@ -28,28 +55,472 @@ impl Cli {
             .parse_matches(&matches)
             .map_err(|e| e.to_string())?;
 
+        self.painter = Painter::new(config.color.enabled());
+        self.compact = config.compact
+            || terminal_width().is_some_and(|width| width < COMPACT_TERMINAL_WIDTH);
+        self.todos = config.todos;
+        self.quiet = config.quiet;
+        init_logger(config.log_level());
+
         if config.help {
             self.print_help();
             return Ok(());
         }
 
-        if config.enable_async {
+        if config.version {
+            println!("{}", env!("CARGO_PKG_VERSION"));
+            return Ok(());
+        }
+
+        if let Some(file) = &config.explain {
+            return self.run_explain(file, &config);
+        }
+
+        // 模板在扫描前校验，避免扫描完才报错
+        if let Some(template) = &config.template {
+            parse_template(template)?;
+        }
+
+        if let Some(commits) = config.history {
+            return self.run_history(commits, &config);
+        }
+
+        if config.split_by_path {
+            return self.run_split_by_path(&config);
+        }
+
+        if config.verify {
+            return self.run_verify(&config);
+        }
+
+        if let Some(baseline) = &config.baseline {
+            return self.run_baseline(baseline, &config);
+        }
+
+        let report = if config.enable_async {
             // Async mode
-            let rt = Runtime::new()
-                .map_err(|e| format!("Failed to create async runtime: {}", e))?;
-            let report = rt.block_on(self.run_async(config))?;
-            self.print(&report);
+            self.run_async(config.clone())?
+        } else if config.progress && !config.quiet {
+            let report = FileCounter::new(config.clone())
+                .process_with_progress(|done, total| eprint!("\r{}/{} files", done, total));
+            eprintln!();
+            report?
         } else {
             // Sync mode
-            let counter = FileCounter::new(config.clone());
-            let report = counter.process()?;
-            self.print(&report);
+            FileCounter::new(config.clone()).process()?
+        };
+        let report = report
+            .with_group_by(config.group_by)
+            .with_sort(config.sort_by, config.sort_order);
+        let report = Self::display_report(report, &config);
+        self.emit(&report, &config)?;
+        self.draw_chart(&report, &config)?;
+        Ok(())
+    }
+
+    /// 按输出选项变换报告，只影响显示
+    fn display_report(report: Report, config: &Config) -> Report {
+        if config.exclude_comments_from_totals {
+            report.excluding_comments_from_lines()
+        } else {
+            report
+        }
+    }
+
+    /// 指定了 `--chart` 时按 `--chart-type` 绘制图表
+    fn draw_chart(&self, report: &Report, config: &Config) -> Result<(), String> {
+        let Some(path) = &config.chart_output else {
+            return Ok(());
+        };
+        ChartDrawer::new(report, None)
+            .draw(config.chart_type, path)
+            .map_err(|e| format!("Failed to draw chart: {}", e))?;
+        if !self.quiet {
+            println!("Chart saved to {}", path);
         }
         Ok(())
     }
 
-    /// 异步辅助函数
-    async fn run_async(&self, config: Config) -> Result<Report, String> {
+    /// 每个路径单独统计：JSON 按路径分开输出，其他格式输出合并后的报告
+    fn run_split_by_path(&self, config: &Config) -> Result<(), String> {
+        let mut multi = FileCounter::new(config.clone()).process_per_path()?;
+        for (_, report) in multi.reports.iter_mut() {
+            report.group_by = config.group_by;
+            report.sort = config.sort_by;
+            report.order = config.sort_order;
+            *report = Self::display_report(std::mem::take(report), config);
+        }
+
+        let (json, rest): (Vec<OutputFormat>, Vec<OutputFormat>) = config.output_formats
+            .iter()
+            .partition(|format| **format == OutputFormat::Json);
+        if !rest.is_empty() {
+            let config = config.clone().with_output_formats(rest);
+            self.emit(&multi.total(), &config)?;
+        }
+        if json.is_empty() {
+            return Ok(());
+        }
+
+        let exporter = JsonExporter::new();
+        match &config.output_file {
+            Some(base) => {
+                let path = FileSaver::output_path(base, OutputFormat::Json);
+                let mut file = std::fs::File::create(&path)
+                    .map_err(|e| format!("Failed to save report: {}", e))?;
+                exporter.export_multi(&multi, &mut file)
+                    .map_err(|e| format!("Failed to save report: {}", e))?;
+                if !self.quiet {
+                    println!("Report saved to {}", path.display());
+                }
+            }
+            None => {
+                exporter.export_multi(&multi, &mut std::io::stdout())
+                    .map_err(|e| format!("Failed to export report: {}", e))?;
+                println!();
+            }
+        }
+        Ok(())
+    }
+
+    /// 比较同步与异步统计的结果，有差异时返回错误
+    fn run_verify(&self, config: &Config) -> Result<(), String> {
+        let discrepancies = verify_parity(config)?;
+        println!("{}", Self::render_verify(&discrepancies));
+        if discrepancies.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Sync and async reports differ in {} field(s)", discrepancies.len()))
+        }
+    }
+
+    /// `--verify` 的输出：一致时为 "match"，否则每行一个差异（sync != async）
+    pub fn render_verify(discrepancies: &[Discrepancy]) -> String {
+        if discrepancies.is_empty() {
+            return "match: sync and async reports are identical".to_string();
+        }
+        let mut out = String::from("mismatch (sync != async):");
+        for d in discrepancies {
+            out.push_str(&format!("\n  {}", d));
+        }
+        out
+    }
+
+    /// 逐行输出单个文件的分类结果
+    fn run_explain(&self, file: &str, config: &Config) -> Result<(), String> {
+        let (lang, lines) = Counter::new(config.clone())
+            .explain(file)
+            .map_err(|e| format!("{}: {}", file, e))?;
+        print!("{}", self.render_explain(file, lang, &lines));
+        Ok(())
+    }
+
+    /// `--explain` 的输出：行号、分类、函数/类定义标记与原始内容
+    pub fn render_explain(&self, file: &str, lang: LangType, lines: &[LineExplanation]) -> String {
+        let mut out = format!("{} ({})\n", file, lang);
+        for line in lines {
+            let start = match (line.function_start, line.class_start) {
+                (true, _) => "fn",
+                (false, true) => "class",
+                (false, false) => "",
+            };
+            let kind = format!("{:?}", line.kind);
+            out.push_str(&format!("{:>5} {:<10} {:<5} | {}\n", line.number, kind, start, line.text));
+        }
+        out
+    }
+
+    /// 分别扫描目标与基线目录，输出目标相对基线的各语言净变化
+    fn run_baseline(&self, baseline: &str, config: &Config) -> Result<(), String> {
+        let target = FileCounter::new(config.clone()).process()?;
+        let base = FileCounter::new(config.clone().with_paths(vec![baseline.to_string()])).process()?;
+        let target = Self::display_report(target, config);
+        let base = Self::display_report(base, config);
+        print!("{}", self.render_delta(&target.delta(&base)));
+        Ok(())
+    }
+
+    /// `--baseline` 的输出：各语言的净变化（带符号）及合计
+    pub fn render_delta(&self, deltas: &[LangDelta]) -> String {
+        if deltas.is_empty() {
+            return "no change: target and baseline are identical\n".to_string();
+        }
+        let divider = self.painter.dim(&"-".repeat(100));
+        let header = format!(
+            "{:<12} {:<10} {:<10} {:<10} {:<10} {:<10} {:<10} {:<10}",
+            "Language", "Files", "Lines", "Code", "Comments", "Blanks", "Functions", "Classes"
+        );
+        let mut out = format!("{}\n{}\n{}\n", divider, self.painter.bold(&header), divider);
+        let row = |label: &str, d: &LangDelta| format!(
+            "{:<12} {:<+10} {:<+10} {:<+10} {:<+10} {:<+10} {:<+10} {:<+10}",
+            label, d.files, d.lines, d.code, d.comments, d.blanks, d.functions, d.classes
+        );
+
+        let mut total = LangDelta::default();
+        for d in deltas {
+            out.push_str(&format!("{}\n", row(&d.lang.to_string(), d)));
+            total.files += d.files;
+            total.lines += d.lines;
+            total.code += d.code;
+            total.comments += d.comments;
+            total.blanks += d.blanks;
+            total.functions += d.functions;
+            total.classes += d.classes;
+        }
+        out.push_str(&format!("{}\n", divider));
+        out.push_str(&format!("{}\n{}\n", self.painter.bold(&row("Total", &total)), divider));
+        out
+    }
+
+    /// 输出所有路径合并后的 git 历史代码行数变化
+    fn run_history(&self, commits: usize, config: &Config) -> Result<(), String> {
+        let points = collect_history(&config.paths, commits, config)?;
+        write_history_csv(&points, &mut std::io::stdout())
+            .map_err(|e| format!("Failed to write history: {}", e))
+    }
+
+    /// 按配置输出报告：指定了输出文件时一次写入所有格式，否则依次打印到标准输出
+    fn emit(&self, report: &Report, config: &Config) -> Result<(), String> {
+        if let Some(base) = &config.output_file {
+            self.print_or_diagnose(report, config);
+            let paths = FileSaver::save_reports(report, base, &config.output_formats)
+                .map_err(|e| format!("Failed to save report: {}", e))?;
+            if !self.quiet {
+                for path in paths {
+                    println!("Report saved to {}", path.display());
+                }
+            }
+            return Ok(());
+        }
+
+        for format in &config.output_formats {
+            match format {
+                OutputFormat::Text => self.print_or_diagnose(report, config),
+                OutputFormat::Ci => print!("{}", Self::render_ci(report)),
+                _ => {
+                    export_report(report, &mut std::io::stdout(), *format)
+                        .map_err(|e| format!("Failed to export report: {}", e))?;
+                    // JSON Lines 每行已自带换行，多余的空行会干扰逐行处理
+                    if *format != OutputFormat::Jsonl {
+                        println!();
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 打印表格；没有统计到任何文件时改为输出排查提示（`--quiet` 时不输出），而不是一张空表
+    fn print_or_diagnose(&self, report: &Report, config: &Config) {
+        if report.is_empty() {
+            if !self.quiet {
+                eprintln!("{}", Self::no_files_message(config));
+            }
+        } else if let Some(template) = &config.template {
+            // 模板已在运行开始时校验
+            if let Ok(text) = Self::render_template(template, report) {
+                print!("{}", text);
+            }
+        } else {
+            if config.by_file {
+                print!("{}", self.render_by_file(report));
+            } else {
+                self.print(report);
+            }
+            if config.detect_commented_code {
+                self.print_commented_code(report);
+            }
+            if config.skip_header_comments {
+                self.print_header_lines(report);
+            }
+            if config.complexity {
+                self.print_complexity(report);
+            }
+            if config.check_eol {
+                print!("{}", self.render_mixed_eol(report));
+            }
+            if let Some(n) = config.largest {
+                self.print_largest(report, n);
+            }
+            if config.avg_sizes {
+                print!("{}", self.render_avg_sizes(report));
+            }
+            if config.estimate {
+                self.print_estimate(report, config);
+            }
+        }
+    }
+
+    /// 打印各语言中疑似被注释掉的代码行数
+    fn print_commented_code(&self, report: &Report) {
+        println!("{}", self.painter.bold(&format!("{:<12} {:<10} {:<16}", "Language", "Comments", "Commented Code")));
+        self.print_divider();
+        for (lang, stat) in report.sort_by_lines_desc() {
+            if stat.commented_code > 0 {
+                println!("{:<12} {:<10} {:<16}", lang.to_string(), stat.comments, stat.commented_code);
+            }
+        }
+        self.print_divider();
+    }
+
+    /// 打印各语言被排除在注释之外的文件头行数
+    fn print_header_lines(&self, report: &Report) {
+        println!("{}", self.painter.bold(&format!("{:<12} {:<10} {:<16}", "Language", "Comments", "Header Lines")));
+        self.print_divider();
+        for (lang, stat) in report.sort_by_lines_desc() {
+            if stat.header_lines > 0 {
+                println!("{:<12} {:<10} {:<16}", lang.to_string(), stat.comments, stat.header_lines);
+            }
+        }
+        self.print_divider();
+    }
+
+    /// 打印各语言分支关键字的总数
+    fn print_complexity(&self, report: &Report) {
+        println!("{}", self.painter.bold(&format!("{:<12} {:<10} {:<16}", "Language", "Code", "Complexity")));
+        self.print_divider();
+        for (lang, stat) in report.sort_by_lines_desc() {
+            if stat.complexity > 0 {
+                println!("{:<12} {:<10} {:<16}", lang.to_string(), stat.code, stat.complexity);
+            }
+        }
+        self.print_divider();
+    }
+
+    /// 混用 CRLF 与 LF 行尾的文件列表，没有时输出一行提示
+    pub fn render_mixed_eol(&self, report: &Report) -> String {
+        let files = report.mixed_eol_files();
+        if files.is_empty() {
+            return "no files with mixed line endings\n".to_string();
+        }
+        let divider = self.painter.dim(&"-".repeat(80));
+        let mut out = format!("{}\n{}\n", self.painter.bold(&format!("{:<56} {:<12}", "Mixed Line Endings", "Language")), divider);
+        for stat in files {
+            out.push_str(&format!("{:<56} {:<12}\n", stat.path, stat.lang.to_string()));
+        }
+        out.push_str(&format!("{}\n", divider));
+        out
+    }
+
+    /// 各语言平均每个函数的行数与每个类的代码行数，没有函数或类的语言显示 `-`
+    pub fn render_avg_sizes(&self, report: &Report) -> String {
+        let avg = |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{:.1}", v));
+        let mut out = format!(
+            "{}\n",
+            self.painter.bold(&format!("{:<12} {:<10} {:<16} {:<16}", "Language", "Code", "Lines/Function", "Lines/Class"))
+        );
+        let divider = self.painter.dim(&"-".repeat(80));
+        out.push_str(&divider);
+        out.push('\n');
+        for (lang, stat) in report.sort_by_lines_desc() {
+            out.push_str(&format!(
+                "{:<12} {:<10} {:<16} {:<16}\n",
+                lang.to_string(),
+                stat.code,
+                avg(stat.avg_lines_per_function()),
+                avg(stat.avg_lines_per_class())
+            ));
+        }
+        out.push_str(&divider);
+        out.push('\n');
+        out
+    }
+
+    /// 按文件的明细表，按行数降序；文件中的区域（`// region`）列在文件下方
+    pub fn render_by_file(&self, report: &Report) -> String {
+        let divider = self.painter.dim(&"-".repeat(110));
+        let mut out = format!("{}\n", divider);
+        let header = format!(
+            "{:<56} {:<10} {:<10} {:<10} {:<10} {:<10}",
+            "File", "Lines", "Code", "Comments", "Blanks", "Functions"
+        );
+        out.push_str(&format!("{}\n{}\n", self.painter.bold(&header), divider));
+        for stat in report.files_sorted() {
+            out.push_str(&format!(
+                "{:<56} {:<10} {:<10} {:<10} {:<10} {:<10}\n",
+                stat.path, stat.lines, stat.code, stat.comments, stat.blanks, stat.functions
+            ));
+            for (name, lines) in &stat.regions {
+                let label = format!("  region {}", name);
+                out.push_str(&format!("{} {}\n", self.painter.dim(&format!("{:<56}", label)), lines));
+            }
+        }
+        out.push_str(&format!("{}\n", divider));
+        out
+    }
+
+    /// 打印行数最多的文件
+    fn print_largest(&self, report: &Report, n: usize) {
+        println!("{}", self.painter.bold(&format!("{:<56} {:<12} {:<10}", "Largest Files", "Language", "Lines")));
+        self.print_divider();
+        for stat in report.largest_files(n) {
+            println!("{:<56} {:<12} {:<10}", stat.path, stat.lang.to_string(), stat.lines);
+        }
+        self.print_divider();
+    }
+
+    /// 根据总代码行数打印 COCOMO 估算
+    fn print_estimate(&self, report: &Report, config: &Config) {
+        println!("{}", report.cocomo_with(&config.cocomo));
+        self.print_divider();
+    }
+
+    /// `--format ci` 的输出：每行一个 `language=lines`，按名字排序，没有表头和分隔线
+    pub fn render_ci(report: &Report) -> String {
+        CiExporter::new().render(report)
+    }
+
+    /// 按模板逐行输出，如 `{language} {lines} {code}`；`{{`、`}}` 输出花括号
+    pub fn render_template(template: &str, report: &Report) -> Result<String, String> {
+        let segments = parse_template(template)?;
+        let mut out = String::new();
+        for (label, stat) in report.rows() {
+            for segment in &segments {
+                match segment {
+                    Segment::Text(text) => out.push_str(text),
+                    Segment::Field(field) => {
+                        let value = match *field {
+                            "language" => label.clone(),
+                            "files" => stat.files.to_string(),
+                            "lines" => stat.lines.to_string(),
+                            "code" => stat.code.to_string(),
+                            "comments" => stat.comments.to_string(),
+                            "blanks" => stat.blanks.to_string(),
+                            "functions" => stat.functions.to_string(),
+                            _ => stat.classes.to_string(),
+                        };
+                        out.push_str(&value);
+                    }
+                }
+            }
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    /// 扫描结果为空时的提示：列出实际扫描的路径以及常见原因
+    pub fn no_files_message(config: &Config) -> String {
+        let types = if config.types == Config::new().types {
+            "all supported languages".to_string()
+        } else {
+            config.types.join(", ")
+        };
+
+        let mut excluded = config.exclude_files.clone();
+        if config.no_vendor {
+            excluded.extend(config.vendor_dirs.iter().cloned());
+        }
+
+        let mut msg = format!("No files found in: {}\n", config.paths.join(", "));
+        msg.push_str("Possible causes:\n");
+        msg.push_str("  - the path does not exist or contains no recognized source files\n");
+        msg.push_str(&format!("  - --type does not match the files (current: {})\n", types));
+        msg.push_str(&format!("  - --exclude-files filtered them out (current: {})", excluded.join(", ")));
+        msg
+    }
+
+    /// 异步辅助函数，已处于 tokio 运行时内时复用该运行时
+    fn run_async(&self, config: Config) -> Result<Report, String> {
         let mut async_counter = AsyncFileCounter::new(config.clone());
 
         // Set custom number of workers if specified
@@ -57,68 +528,132 @@ impl Cli {
             async_counter = async_counter.with_workers(config.num_workers);
         }
 
-        async_counter.process()
-            .await
+        async_counter.process_blocking()
             .map_err(|e| format!("Async processing failed: {}", e))
     }
 }
 
+/// `--template` 支持的占位符
+const TEMPLATE_FIELDS: &[&str] = &["language", "files", "lines", "code", "comments", "blanks", "functions", "classes"];
+
+enum Segment {
+    Text(String),
+    Field(&'static str),
+}
+
+fn parse_template(template: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut chars = template.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => return Err(format!("Unclosed placeholder {{{} in template", name)),
+                    }
+                }
+                let field = TEMPLATE_FIELDS.iter()
+                    .find(|f| **f == name)
+                    .ok_or_else(|| format!(
+                        "Unknown template placeholder {{{}}}, expected one of: {}",
+                        name, TEMPLATE_FIELDS.join(", ")))?;
+                if !text.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut text)));
+                }
+                segments.push(Segment::Field(field));
+            }
+            '}' => return Err("Unmatched '}' in template, use '}}' for a literal brace".to_string()),
+            _ => text.push(ch),
+        }
+    }
+    if !text.is_empty() {
+        segments.push(Segment::Text(text));
+    }
+    Ok(segments)
+}
+
+impl Default for Cli {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Cli {
-    pub fn print(&self, report: &Report) {
-        self.print_divider();
+    /// 按 `color` 设置终端输出是否着色
+    pub fn with_color(mut self, enabled: bool) -> Self {
+        self.painter = Painter::new(enabled);
+        self
+    }
 
-        // 使用更宽的列宽和对齐方式
-        println!(
-            "{:<12} {:<8} {:<10} {:<10} {:<10} {:<10} {:<10}",
-            "Language", "Files", "Lines", "Code", "Comments", "Blanks", "Functions"
-        );
-        self.print_divider();
+    /// 运行结束后是否处于安静模式，此时调用方也不应输出提示信息
+    pub fn is_quiet(&self) -> bool {
+        self.quiet
+    }
 
-        // 收集所有数据并按行数排序
-        let mut items: Vec<_> = report.into_iter().collect();
-        items.sort_by(|a, b| b.1.lines.cmp(&a.1.lines)); // 按行数降序排序
+    /// 文本输出使用紧凑格式，代替表格
+    pub fn with_compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
 
-        for (lang, stat) in items {
-            println!(
-                "{:<12} {:<8} {:<10} {:<10} {:<10} {:<10} {:<10}",
-                lang.to_string(),
-                stat.files,
-                stat.lines,
-                stat.code,
-                stat.comments,
-                stat.blanks,
-                stat.functions
-            );
+    pub fn print(&self, report: &Report) {
+        if self.compact {
+            print!("{}", Self::render_compact(report));
+        } else {
+            print!("{}", self.render(report));
         }
+    }
 
-        self.print_divider();
+    /// 紧凑格式：每行一个 `Language: N lines (M code)`，按行数降序，最后一行为合计
+    pub fn render_compact(report: &Report) -> String {
+        let mut out = String::new();
+        let (mut lines, mut code) = (0, 0);
+        for (label, stat) in report.rows() {
+            out.push_str(&format!("{}: {} lines ({} code)\n", label, stat.lines, stat.code));
+            lines += stat.lines;
+            code += stat.code;
+        }
+        out.push_str(&format!("Total: {} lines ({} code)\n", lines, code));
+        out
+    }
 
-        // 添加总计行
-        let total_files: usize = report.into_iter().map(|(_, s)| s.files).sum();
-        let total_lines: usize = report.into_iter().map(|(_, s)| s.lines).sum();
-        let total_code: usize = report.into_iter().map(|(_, s)| s.code).sum();
-        let total_comments: usize = report.into_iter().map(|(_, s)| s.comments).sum();
-        let total_blanks: usize = report.into_iter().map(|(_, s)| s.blanks).sum();
-        let total_functions: usize = report.into_iter().map(|(_, s)| s.functions).sum();
-
-        println!(
-            "{:<12} {:<8} {:<10} {:<10} {:<10} {:<10} {:<10}",
-            "Total", total_files, total_lines, total_code, total_comments, total_blanks, total_functions
-        );
-        self.print_divider();
+    /// 生成统计表格文本
+    pub fn render(&self, report: &Report) -> String {
+        TextExporter::new()
+            .with_painter(self.painter)
+            .with_todos(self.todos)
+            .render(report)
     }
 
     fn print_divider(&self) {
-        println!("{}", "-".repeat(80));
+        println!("{}", self.painter.dim(&"-".repeat(80)));
     }
 
     fn print_help(&self) {
-        
-        let args = self.arg_parser.get_args();
-        self.print_divider();
-        println!("{:<20} {:<8} {:<20} {}", "Name", "Short", "Long", "Help");
-        self.print_divider();
-        for (name, arg) in args.iter() {
+        print!("{}", self.help_text());
+    }
+
+    /// 帮助信息：每个参数一行，列出短/长参数名、取值与说明
+    fn help_text(&self) -> String {
+        let divider = self.painter.dim(&"-".repeat(80));
+        let mut out = String::new();
+        writeln!(out, "{}", divider).unwrap();
+        writeln!(out, "{:<20} {:<8} {:<20} {:<12} Help", "Name", "Short", "Long", "Value").unwrap();
+        writeln!(out, "{}", divider).unwrap();
+        for (name, arg) in self.arg_parser.get_args().iter() {
             let short = arg
                 .get_short()
                 .map(|c| format!("-{}", c))
@@ -128,21 +663,71 @@ impl Cli {
                 .as_ref()
                 .map(|s| format!("--{}", s))
                 .unwrap_or_else(|| "".to_string());
+            let required = if arg.is_required() { " [required]" } else { "" };
 
-            println!(
-                "{:<20} {:<8} {:<20} {}",
+            writeln!(
+                out,
+                "{:<20} {:<8} {:<20} {:<12} {}{}",
                 name,
                 short,
                 long,
-                arg.get_help()
-            );
+                value_hint(arg),
+                arg.get_help(),
+                required
+            ).unwrap();
         }
-        self.print_divider();
+        writeln!(out, "{}", divider).unwrap();
+        out
     }
 }
 
+/// 初始化日志输出到标准错误，重复初始化（如多次调用 `run`）时保持第一次的设置
+fn init_logger(level: LevelFilter) {
+    let _ = env_logger::Builder::new()
+        .filter_level(level)
+        .format_target(false)
+        .try_init();
+}
+
+/// 参数取值在帮助中的写法：开关没有取值，有可选值时以 `|` 列出，否则为值类型的可读名称
+fn value_hint(arg: &Arg) -> String {
+    if matches!(arg.get_action(), ArgAction::SetTrue | ArgAction::Count) {
+        return String::new();
+    }
+    if !arg.get_possible_values().is_empty() {
+        return arg.get_possible_values().join("|");
+    }
+    let ty = arg.get_value_type();
+    let name = if ty == TypeId::of::<String>() {
+        "string"
+    } else if ty == TypeId::of::<usize>() || ty == TypeId::of::<u8>() {
+        "number"
+    } else if ty == TypeId::of::<f64>() {
+        "float"
+    } else if ty == TypeId::of::<bool>() {
+        "bool"
+    } else if ty == TypeId::of::<Vec<String>>() {
+        "list"
+    } else if ty == TypeId::of::<Duration>() {
+        "duration"
+    } else if ty == TypeId::of::<LangType>() {
+        "language"
+    } else if ty == TypeId::of::<[f64; 4]>() {
+        "a,b,c,d"
+    } else {
+        "value"
+    };
+    format!("<{}>", name)
+}
+
 #[cfg(test)]
 mod tests { 
+    use super::Cli;
+    use crate::parser::args_parser::ArgParser;
+    use crate::langs::lang_type::LangType;
+    use crate::report::Report;
+    use crate::stats::FileStat;
+    use crate::config::Config;
     use crate::utils::format::OutputFormat;
     use crate::parser::value_parser::{ParseValue};
     use crate::value_parser;
@@ -162,4 +747,164 @@ mod tests {
 
         assert_eq!(parser.parse("text").unwrap(), OutputFormat::Text);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_help_shows_value_types() {
+        let help = Cli::new().with_color(false).help_text();
+        let line = |name: &str| help.lines().find(|l| l.starts_with(&format!("{} ", name))).unwrap().to_string();
+
+        assert!(line("output").contains("text|json|json-map|jsonl|csv|ci|treemap|md"));
+        assert!(line("num-workers").contains("<number>"));
+        assert!(line("path").contains("<list>"));
+        assert!(!line("help").contains('<'));
+        assert!(!help.contains("[required]"));
+
+        let cli = Cli {
+            arg_parser: ArgParser::new().arg(crate::parser::arg::Arg::new("name").long("name").required()),
+            ..Cli::new().with_color(false)
+        };
+        let help = cli.help_text();
+        let name = help.lines().find(|l| l.starts_with("name ")).unwrap();
+        assert!(name.contains("<string>"));
+        assert!(name.ends_with("[required]"));
+    }
+
+    #[test]
+    fn test_no_files_message() {
+        let config = Config::new()
+            .with_paths(vec!["missing/dir".to_string()])
+            .with_types(vec!["rust".to_string()]);
+        let msg = Cli::no_files_message(&config);
+
+        assert!(msg.starts_with("No files found in: missing/dir"));
+        assert!(msg.contains("--type does not match the files (current: rust)"));
+        assert!(msg.contains("--exclude-files"));
+        assert!(msg.contains("node_modules"));
+    }
+
+    #[test]
+    fn test_render_without_color() {
+        let mut report = Report::new();
+        report.add(FileStat { lang: LangType::Rust, path: "a.rs".to_string(), lines: 3, code: 3, ..FileStat::default() });
+
+        let plain = Cli::new().with_color(false).render(&report);
+        assert!(!plain.contains('\x1b'));
+        assert!(plain.contains("Rust"));
+        assert!(plain.contains("Total"));
+
+        let colored = Cli::new().with_color(true).render(&report);
+        assert!(colored.contains("\x1b[1m"));
+    }
+
+    #[test]
+    fn test_render_template() {
+        let mut report = Report::new();
+        report.add(FileStat { lang: LangType::Rust, path: "a.rs".to_string(), lines: 10, code: 8, ..FileStat::default() });
+        report.add(FileStat { lang: LangType::Python, path: "b.py".to_string(), lines: 4, code: 3, ..FileStat::default() });
+
+        let text = Cli::render_template("{language}\t{lines}\t{code} {{x}}", &report).unwrap();
+        assert_eq!(text, "Rust\t10\t8 {x}\nPython\t4\t3 {x}\n");
+
+        let err = Cli::render_template("{language} {size}", &report).unwrap_err();
+        assert!(err.contains("{size}"));
+        assert!(Cli::render_template("{language", &report).is_err());
+        assert!(Cli::render_template("lines}", &report).is_err());
+    }
+
+    #[test]
+    fn test_render_avg_sizes() {
+        let mut report = Report::new();
+        report.add(FileStat { lang: LangType::Rust, path: "a.rs".to_string(), lines: 60, code: 50, functions: 4, function_lines: 30, classes: 2, ..FileStat::default() });
+        report.add(FileStat { lang: LangType::Rust, path: "b.rs".to_string(), lines: 20, code: 10, functions: 2, function_lines: 18, ..FileStat::default() });
+        report.add(FileStat { lang: LangType::Json, path: "c.json".to_string(), lines: 30, code: 30, ..FileStat::default() });
+
+        let rust = report.get_by_lang(&LangType::Rust).unwrap();
+        assert_eq!(rust.avg_lines_per_function(), Some(8.0));
+        assert_eq!(rust.avg_lines_per_class(), Some(30.0));
+        let json = report.get_by_lang(&LangType::Json).unwrap();
+        assert_eq!(json.avg_lines_per_function(), None);
+        assert_eq!(json.avg_lines_per_class(), None);
+
+        let text = Cli::new().with_color(false).render_avg_sizes(&report);
+        let rows: Vec<&str> = text.lines().map(str::trim_end).collect();
+        assert_eq!(rows[2], "Rust         60         8.0              30.0");
+        assert_eq!(rows[3], "Json         30         -                -");
+    }
+
+    #[test]
+    fn test_render_compact() {
+        let mut report = Report::new();
+        report.add(FileStat { lang: LangType::Rust, path: "a.rs".to_string(), lines: 120, code: 100, ..FileStat::default() });
+        report.add(FileStat { lang: LangType::Python, path: "b.py".to_string(), lines: 30, code: 20, ..FileStat::default() });
+
+        assert_eq!(
+            Cli::render_compact(&report),
+            "Rust: 120 lines (100 code)\nPython: 30 lines (20 code)\nTotal: 150 lines (120 code)\n"
+        );
+
+        let mut arg_parser = ArgParser::default();
+        let matches = arg_parser.build_matches(vec!["--compact"]).unwrap();
+        assert!(arg_parser.parse_matches(&matches).unwrap().compact);
+    }
+
+    #[test]
+    fn test_exclude_comments_from_totals() {
+        let mut report = Report::new();
+        report.add(FileStat { lang: LangType::Rust, path: "a.rs".to_string(), lines: 10, code: 6, comments: 3, blanks: 1, ..FileStat::default() });
+        report.add(FileStat { lang: LangType::Rust, path: "b.rs".to_string(), lines: 5, code: 5, ..FileStat::default() });
+        report.add(FileStat { lang: LangType::Python, path: "c.py".to_string(), lines: 8, code: 2, comments: 4, blanks: 2, ..FileStat::default() });
+
+        let config = Config::new().enable_exclude_comments_from_totals(true);
+        let adjusted = Cli::display_report(report.clone(), &config);
+        assert_eq!(adjusted.get_by_lang(&LangType::Rust).unwrap().lines, 11);
+        assert_eq!(adjusted.get_by_lang(&LangType::Python).unwrap().lines, 2);
+        assert_eq!(adjusted.largest_files(1)[0].lines, 6);
+        // 其他计数与原报告不变
+        assert_eq!(adjusted.get_by_lang(&LangType::Rust).unwrap().comments, 3);
+        assert_eq!(report.get_by_lang(&LangType::Rust).unwrap().lines, 15);
+
+        let text = Cli::new().with_color(false).render(&adjusted);
+        assert!(text.lines().any(|l| l.starts_with("Total        3        13         13")), "{}", text);
+
+        let unchanged = Cli::display_report(report, &Config::new());
+        assert_eq!(unchanged.get_by_lang(&LangType::Rust).unwrap().lines, 15);
+    }
+
+    #[test]
+    fn test_render_by_file() {
+        let mut report = Report::new();
+        report.add(FileStat { lang: LangType::Rust, path: "src/a.rs".to_string(), lines: 10, code: 8, blanks: 2, ..FileStat::default() });
+        report.add(FileStat { lang: LangType::Python, path: "b.py".to_string(), lines: 40, code: 30, comments: 10, ..FileStat::default() });
+        report.add(FileStat { lang: LangType::Rust, path: "src/c.rs".to_string(), lines: 10, code: 9, comments: 1, ..FileStat::default() });
+
+        let paths: Vec<&str> = report.files_sorted().iter().map(|s| s.path.as_str()).collect();
+        assert_eq!(paths, vec!["b.py", "src/a.rs", "src/c.rs"]);
+
+        let text = Cli::new().with_color(false).render_by_file(&report);
+        let rows: Vec<&str> = text.lines().map(str::trim_end).collect();
+        assert!(rows[1].starts_with("File"));
+        assert_eq!(rows[3], format!("{:<56} 40         30         10         0          0", "b.py"));
+        assert_eq!(rows.len(), 7);
+
+        let mut report = Report::new();
+        report.add(FileStat { path: "a.rs".to_string(), lines: 10, regions: vec![("Parsing".to_string(), 6)], ..FileStat::default() });
+        let text = Cli::new().with_color(false).render_by_file(&report);
+        assert_eq!(text.lines().nth(4).unwrap(), format!("{:<56} 6", "  region Parsing"));
+    }
+
+    #[test]
+    fn test_render_ci() {
+        let mut report = Report::new();
+        report.add(FileStat { lang: LangType::Rust, path: "a.rs".to_string(), lines: 10, code: 8, ..FileStat::default() });
+        report.add(FileStat { lang: LangType::Python, path: "b.py".to_string(), lines: 40, code: 30, ..FileStat::default() });
+        report.add(FileStat { lang: LangType::Go, path: "c.go".to_string(), lines: 10, code: 9, ..FileStat::default() });
+
+        // 按名字排序，与行数无关
+        assert_eq!(Cli::render_ci(&report), "Go=10\nPython=40\nRust=10\n");
+        assert_eq!(Cli::render_ci(&Report::new()), "");
+
+        let mut parser = ArgParser::default();
+        let matches = parser.build_matches(["--format", "ci"].map(String::from)).unwrap();
+        assert_eq!(parser.parse_matches(&matches).unwrap().output_formats, vec![OutputFormat::Ci]);
+    }
+}
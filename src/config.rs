@@ -1,6 +1,18 @@
+use std::collections::HashMap;
 use std::fmt::Display;
+use std::path::Path;
+use std::time::Duration;
 
+use log::LevelFilter;
+use serde::Deserialize;
+
+use crate::consts::{DEFAULT_PARALLEL_THRESHOLD, DEFAULT_TAB_WIDTH, DEFAULT_TEST_PATTERNS, DEFAULT_TODO_MARKERS, DEFAULT_VENDOR_DIRS};
+use crate::estimate::CocomoModel;
+use crate::langs::lang_type::LangType;
 use crate::langs::registry::SUPPORTED_LANGUAGES;
+use crate::report::{GroupBy, SortKey, SortOrder};
+use crate::utils::chart::ChartType;
+use crate::utils::color::ColorChoice;
 use crate::utils::format::OutputFormat;
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
@@ -8,29 +20,137 @@ pub struct Config {
     
     pub paths: Vec<String>,
     pub types: Vec<String>,
+    /// 不参与统计的语言，优先于 `types`
+    pub exclude_types: Vec<String>,
+    /// 本次运行额外的扩展名到语言映射，优先于内置映射
+    pub ext_overrides: Vec<(String, LangType)>,
+    /// 扩展名无法识别时按开头几行的语法特征猜测语言
+    pub sniff: bool,
 
     pub ignore_blanks: bool,
     pub ignore_comments: bool,
+    /// 块注释/文档字符串内的空行计为空行，关闭后计为注释
+    pub blanks_in_comments_are_blank: bool,
+    /// 按语言覆盖 `blanks_in_comments_are_blank`，同一语言后出现的生效
+    pub lang_blanks_in_comments: Vec<(LangType, bool)>,
+    /// 跨行字符串内的空行计为代码，关闭后计为空行
+    pub blanks_in_strings_are_code: bool,
+    /// 统计看起来像被注释掉的代码的注释行
+    pub detect_commented_code: bool,
+    /// 含有非法 UTF-8 序列的文件跳过并警告，而不是替换为 U+FFFD 后照常统计
+    pub strict_utf8: bool,
+    /// 按指定编码（如 `shift_jis`、`gbk`）解码所有文件，未设置时只根据 BOM 识别
+    pub encoding: Option<String>,
+    /// 文件开头的注释块（如许可证头）不计入注释，单独统计为 `header_lines`
+    pub skip_header_comments: bool,
+    /// 统计分支关键字的个数，近似各语言的圈复杂度
+    pub complexity: bool,
+    /// 统计注释中的待办标记，并在表格中增加一列
+    pub todos: bool,
+    /// 开启 `todos` 时统计的标记，如 `TODO`、`FIXME`
+    pub todo_markers: Vec<String>,
+    /// 在表格后列出同时含有 CRLF 与 LF 行尾的文件
+    pub check_eol: bool,
+    /// 复合文件（如 `.vue`）只统计其中该语言的片段，如 `<script>` 中的 JavaScript
+    pub only_lang_content: Option<LangType>,
     pub enable_async: bool,
     pub num_workers: usize,
+    /// 整次扫描的时间上限，超时后中止
+    pub timeout: Option<Duration>,
+    /// 打开/读取文件遇到 IO 错误时的重试次数，适用于不稳定的网络文件系统
+    pub io_retries: usize,
+    pub tab_width: usize,
+    /// 是否对超大文件做分段并行分析
+    pub parallel_files: bool,
+    /// 触发分段并行分析的文件大小（字节）
+    pub parallel_threshold: usize,
     
     pub exclude_files: Vec<String>,
+    /// 遍历目录时跟随符号链接，链接形成的循环会被跳过
+    pub follow_symlinks: bool,
+    /// 遍历目录的最大深度，相对 `paths` 中的每个根目录计算；0 表示只统计根目录下直接包含的文件
+    pub max_depth: Option<usize>,
+    /// 代码行数少于该值的文件不计入报告，0 表示不过滤
+    pub min_code_lines: usize,
+    /// 是否按目录名排除 `vendor_dirs` 中的第三方目录
+    pub no_vendor: bool,
+    pub vendor_dirs: Vec<String>,
+
+    pub split_tests: bool,
+    pub test_patterns: Vec<String>,
+
+    /// 每个路径单独统计，JSON 输出按路径分开
+    pub split_by_path: bool,
+    /// 同时运行同步与异步统计并比较结果，用于排查计数问题
+    pub verify: bool,
+
+    /// 统计最近 N 个提交的代码行数变化，而不是扫描工作区
+    pub history: Option<usize>,
+    /// 同时扫描该基线目录，只输出目标相对基线的各语言净变化
+    pub baseline: Option<String>,
+    /// 只分析该文件，逐行输出分类结果，用于排查统计口径
+    pub explain: Option<String>,
+
+    /// 在表格后列出行数最多的 N 个文件
+    pub largest: Option<usize>,
+    /// 按文件输出明细表，代替按语言汇总的表格
+    pub by_file: bool,
+    /// 文本输出每行一个 `Language: N lines (M code)`，代替表格；终端过窄时自动开启
+    pub compact: bool,
+    /// 输出时 `lines` 不含注释行与空行，只影响显示，不影响统计
+    pub exclude_comments_from_totals: bool,
+    /// 在表格后输出各语言平均每个函数/类的代码行数
+    pub avg_sizes: bool,
+    /// 在表格后输出基于 COCOMO 的工作量/成本估算
+    pub estimate: bool,
+    pub cocomo: CocomoModel,
 
     pub show_stats: bool,
+    /// 扫描时在标准错误输出 `N/M files` 进度
+    pub progress: bool,
+    /// 日志详细程度：0 只输出警告，1/2/3 依次为 info/debug/trace
+    pub verbose: u8,
+    /// 不输出日志、进度与提示信息，只输出结果
+    pub quiet: bool,
+    /// 终端表格是否着色
+    pub color: ColorChoice,
+    /// 输出时按语言或语言族汇总
+    pub group_by: GroupBy,
+    /// 输出行的排序依据
+    pub sort_by: SortKey,
+    /// 输出行的排序方向，未指定时名字升序、数值降序
+    pub sort_order: Option<SortOrder>,
     pub output: OutputFormat,
+    /// 本次需要输出的全部格式，第一个与 `output` 一致
+    pub output_formats: Vec<OutputFormat>,
+    /// 文本输出按模板逐行输出，而不是表格
+    pub template: Option<String>,
+    /// 输出文件的基础路径，每种格式写入 `<base>.<ext>`
+    pub output_file: Option<String>,
+    /// 统计完成后将图表写入该路径（PNG）
+    pub chart_output: Option<String>,
+    pub chart_type: ChartType,
     pub help: bool,
+    /// 只输出版本号
+    pub version: bool,
 }
 
 impl Display for Config {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Config {{ paths: {:?}, types: {:?}, 
-            ignore_blanks: {}, ignore_comments: {}, 
-            enable_async: {}, num_workers: {}, exclude_files: {:?}, 
-            show_stats: {}, output: {:?}, help: {} }}",
-            self.paths,self.types,
-            self.ignore_blanks,self.ignore_comments,
-            self.enable_async,self.num_workers,self.exclude_files,
-            self.show_stats,self.output,self.help
+        write!(f, "Config {{ paths: {:?}, types: {:?}, exclude_types: {:?}, ext_overrides: {:?}, sniff: {}, 
+            ignore_blanks: {}, ignore_comments: {}, blanks_in_comments_are_blank: {}, lang_blanks_in_comments: {:?}, blanks_in_strings_are_code: {}, detect_commented_code: {}, strict_utf8: {}, encoding: {:?}, skip_header_comments: {}, complexity: {}, todos: {}, todo_markers: {:?}, check_eol: {}, only_lang_content: {:?}, 
+            enable_async: {}, num_workers: {}, timeout: {:?}, io_retries: {}, tab_width: {}, 
+            parallel_files: {}, parallel_threshold: {}, exclude_files: {:?}, follow_symlinks: {}, max_depth: {:?}, min_code_lines: {}, 
+            no_vendor: {}, vendor_dirs: {:?}, 
+            split_tests: {}, test_patterns: {:?}, 
+            split_by_path: {}, verify: {}, history: {:?}, baseline: {:?}, explain: {:?}, largest: {:?}, by_file: {}, compact: {}, exclude_comments_from_totals: {}, avg_sizes: {}, estimate: {}, cocomo: {:?}, show_stats: {}, progress: {}, verbose: {}, quiet: {}, color: {:?}, group_by: {:?}, sort_by: {:?}, sort_order: {:?}, output: {:?}, output_formats: {:?}, template: {:?}, output_file: {:?}, chart_output: {:?}, chart_type: {:?}, help: {}, version: {} }}",
+            self.paths,self.types,self.exclude_types,self.ext_overrides,self.sniff,
+            self.ignore_blanks,self.ignore_comments,self.blanks_in_comments_are_blank,self.lang_blanks_in_comments,self.blanks_in_strings_are_code,self.detect_commented_code,self.strict_utf8,self.encoding,self.skip_header_comments,self.complexity,self.todos,self.todo_markers,self.check_eol,self.only_lang_content,
+            self.enable_async,self.num_workers,self.timeout,self.io_retries,self.tab_width,
+            self.parallel_files,self.parallel_threshold,self.exclude_files,self.follow_symlinks,self.max_depth,self.min_code_lines,
+            self.no_vendor,self.vendor_dirs,
+            self.split_tests,self.test_patterns,
+            self.split_by_path,self.verify,self.history,self.baseline,self.explain,self.largest,self.by_file,self.compact,self.exclude_comments_from_totals,self.avg_sizes,self.estimate,self.cocomo,self.show_stats,self.progress,self.verbose,self.quiet,self.color,self.group_by,self.sort_by,self.sort_order,self.output,self.output_formats,self.template,self.output_file,self.chart_output,self.chart_type,self.help,self.version
         )
     }   
 }
@@ -41,18 +161,73 @@ impl Config {
         let paths = vec![".".to_string()];
         let types = SUPPORTED_LANGUAGES.iter().map(|s| s.to_string().to_lowercase()).collect();
         let exclude_files = vec![".git".to_string(), "target".to_string(), "node_modules".to_string(), "dist".to_string(), "build".to_string()];
+        let vendor_dirs = DEFAULT_VENDOR_DIRS.iter().map(|s| s.to_string()).collect();
+        let test_patterns = DEFAULT_TEST_PATTERNS.iter().map(|s| s.to_string()).collect();
+        let todo_markers = DEFAULT_TODO_MARKERS.iter().map(|s| s.to_string()).collect();
 
         Config {
             paths,
             types,
+            exclude_types: Vec::new(),
+            ext_overrides: Vec::new(),
+            sniff: false,
             ignore_blanks: false,
             ignore_comments: false,
+            blanks_in_comments_are_blank: true,
+            lang_blanks_in_comments: Vec::new(),
+            blanks_in_strings_are_code: true,
+            detect_commented_code: false,
+            strict_utf8: false,
+            encoding: None,
+            skip_header_comments: false,
+            complexity: false,
+            todos: false,
+            todo_markers,
+            check_eol: false,
+            only_lang_content: None,
             enable_async: false,
             num_workers: 8,
+            timeout: None,
+            io_retries: 0,
+            tab_width: DEFAULT_TAB_WIDTH,
+            parallel_files: false,
+            parallel_threshold: DEFAULT_PARALLEL_THRESHOLD,
             exclude_files,
+            follow_symlinks: false,
+            max_depth: None,
+            min_code_lines: 0,
+            no_vendor: false,
+            vendor_dirs,
+            split_tests: false,
+            test_patterns,
+            split_by_path: false,
+            verify: false,
+            history: None,
+            baseline: None,
+            explain: None,
+            largest: None,
+            by_file: false,
+            compact: false,
+            exclude_comments_from_totals: false,
+            avg_sizes: false,
+            estimate: false,
+            cocomo: CocomoModel::default(),
             show_stats: false,
+            progress: false,
+            verbose: 0,
+            quiet: false,
+            color: ColorChoice::Auto,
+            group_by: GroupBy::Language,
+            sort_by: SortKey::Lines,
+            sort_order: None,
             output: OutputFormat::Text,
+            output_formats: vec![OutputFormat::Text],
+            template: None,
+            output_file: None,
+            chart_output: None,
+            chart_type: ChartType::Pie,
             help: false,
+            version: false,
         }
     }
 
@@ -66,8 +241,140 @@ impl Config {
         self
     }
 
+    /// 排除指定的语言，即使它们也在 `types` 中
+    pub fn with_exclude_types(mut self, types: Vec<String>) -> Self {
+        self.exclude_types = types;
+        self
+    }
+
+    /// 把扩展名 `ext`（不含点）映射到 `lang`
+    pub fn with_ext_override(mut self, ext: impl Into<String>, lang: LangType) -> Self {
+        self.ext_overrides.push((ext.into().to_lowercase(), lang));
+        self
+    }
+
+    /// 语言是否在 `types` 中且不在 `exclude_types` 中，排除列表优先
+    pub fn wants(&self, lang: LangType) -> bool {
+        let name = lang.to_string();
+        !self.exclude_types.iter().any(|t| t.eq_ignore_ascii_case(&name))
+            && self.types.contains(&name.to_lowercase())
+    }
+
+    /// 扩展名覆盖映射，后出现的同名扩展名生效
+    pub fn ext_override_map(&self) -> HashMap<String, LangType> {
+        self.ext_overrides.iter().cloned().collect()
+    }
+
     pub fn with_output_format(mut self, format: OutputFormat) -> Self {
         self.output = format;
+        self.output_formats = vec![format];
+        self
+    }
+
+    pub fn with_output_formats(mut self, formats: Vec<OutputFormat>) -> Self {
+        if let Some(first) = formats.first() {
+            self.output = *first;
+        }
+        self.output_formats = formats;
+        self
+    }
+
+    pub fn with_template(mut self, template: impl Into<String>) -> Self {
+        self.template = Some(template.into());
+        self
+    }
+
+    pub fn with_output_file(mut self, path: impl Into<String>) -> Self {
+        self.output_file = Some(path.into());
+        self
+    }
+
+    /// 与基线目录比较，只输出净变化
+    pub fn with_baseline(mut self, dir: impl Into<String>) -> Self {
+        self.baseline = Some(dir.into());
+        self
+    }
+
+    /// 只逐行解释该文件的分类结果
+    pub fn with_explain(mut self, file: impl Into<String>) -> Self {
+        self.explain = Some(file.into());
+        self
+    }
+
+    pub fn with_chart_output(mut self, path: impl Into<String>) -> Self {
+        self.chart_output = Some(path.into());
+        self
+    }
+
+    pub fn with_chart_type(mut self, chart_type: ChartType) -> Self {
+        self.chart_type = chart_type;
+        self
+    }
+
+    pub fn enable_split_by_path(mut self, enable: bool) -> Self {
+        self.split_by_path = enable;
+        self
+    }
+
+    pub fn enable_verify(mut self, enable: bool) -> Self {
+        self.verify = enable;
+        self
+    }
+
+    pub fn with_history(mut self, commits: usize) -> Self {
+        self.history = Some(commits);
+        self
+    }
+
+    pub fn with_largest(mut self, n: usize) -> Self {
+        self.largest = Some(n);
+        self
+    }
+
+    pub fn enable_by_file(mut self, enable: bool) -> Self {
+        self.by_file = enable;
+        self
+    }
+
+    /// 文本输出使用每行一种语言的紧凑格式
+    pub fn enable_compact(mut self, enable: bool) -> Self {
+        self.compact = enable;
+        self
+    }
+
+    pub fn enable_exclude_comments_from_totals(mut self, enable: bool) -> Self {
+        self.exclude_comments_from_totals = enable;
+        self
+    }
+
+    pub fn enable_avg_sizes(mut self, enable: bool) -> Self {
+        self.avg_sizes = enable;
+        self
+    }
+
+    pub fn enable_estimate(mut self, enable: bool) -> Self {
+        self.estimate = enable;
+        self
+    }
+
+    pub fn with_cocomo(mut self, model: CocomoModel) -> Self {
+        self.cocomo = model;
+        self
+    }
+
+    pub fn with_color(mut self, color: ColorChoice) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_group_by(mut self, group_by: GroupBy) -> Self {
+        self.group_by = group_by;
+        self
+    }
+
+    pub fn with_sort(mut self, sort_by: SortKey, sort_order: Option<SortOrder>) -> Self {
+        self.sort_by = sort_by;
+        self.sort_order = sort_order;
         self
     }
 
@@ -76,11 +383,74 @@ impl Config {
         self
     }
 
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_io_retries(mut self, retries: usize) -> Self {
+        self.io_retries = retries;
+        self
+    }
+
+    pub fn with_tab_width(mut self, tab_width: usize) -> Self {
+        self.tab_width = tab_width;
+        self
+    }
+
+    pub fn with_parallel_threshold(mut self, bytes: usize) -> Self {
+        self.parallel_threshold = bytes;
+        self
+    }
+
+    pub fn enable_parallel_files(mut self, enable: bool) -> Self {
+        self.parallel_files = enable;
+        self
+    }
+
     pub fn with_exclude_files(mut self, files: Vec<String>) -> Self {
         self.exclude_files = files;
         self
     }
 
+    /// 遍历目录时跟随符号链接
+    pub fn enable_follow_symlinks(mut self, enable: bool) -> Self {
+        self.follow_symlinks = enable;
+        self
+    }
+
+    /// 限制遍历深度，0 表示只统计根目录下直接包含的文件
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// 代码行数少于 `min` 的文件不计入报告
+    pub fn with_min_code_lines(mut self, min: usize) -> Self {
+        self.min_code_lines = min;
+        self
+    }
+
+    pub fn with_vendor_dirs(mut self, dirs: Vec<String>) -> Self {
+        self.vendor_dirs = dirs;
+        self
+    }
+
+    pub fn enable_no_vendor(mut self, enable: bool) -> Self {
+        self.no_vendor = enable;
+        self
+    }
+
+    pub fn with_test_patterns(mut self, patterns: Vec<String>) -> Self {
+        self.test_patterns = patterns;
+        self
+    }
+
+    pub fn enable_split_tests(mut self, enable: bool) -> Self {
+        self.split_tests = enable;
+        self
+    }
+
     pub fn enable_ignore_blanks(mut self, ignore: bool) -> Self {
         self.ignore_blanks = ignore;
         self
@@ -91,8 +461,189 @@ impl Config {
         self
     }
 
+    pub fn with_blanks_in_comments_are_blank(mut self, enable: bool) -> Self {
+        self.blanks_in_comments_are_blank = enable;
+        self
+    }
+
+    /// 只对 `lang` 设置块注释内的空行是否计为空行，优先于全局设置
+    pub fn with_lang_blanks_in_comments_are_blank(mut self, lang: LangType, enable: bool) -> Self {
+        self.lang_blanks_in_comments.push((lang, enable));
+        self
+    }
+
+    /// `lang` 的块注释内的空行是否计为空行，没有按语言设置时使用全局设置
+    pub fn blanks_in_comments_are_blank_for(&self, lang: LangType) -> bool {
+        self.lang_blanks_in_comments.iter().rev()
+            .find(|(l, _)| *l == lang)
+            .map_or(self.blanks_in_comments_are_blank, |(_, enable)| *enable)
+    }
+
+    pub fn with_blanks_in_strings_are_code(mut self, enable: bool) -> Self {
+        self.blanks_in_strings_are_code = enable;
+        self
+    }
+
+    pub fn enable_detect_commented_code(mut self, enable: bool) -> Self {
+        self.detect_commented_code = enable;
+        self
+    }
+
+    pub fn enable_skip_header_comments(mut self, enable: bool) -> Self {
+        self.skip_header_comments = enable;
+        self
+    }
+
+    pub fn enable_complexity(mut self, enable: bool) -> Self {
+        self.complexity = enable;
+        self
+    }
+
+    pub fn enable_todos(mut self, enable: bool) -> Self {
+        self.todos = enable;
+        self
+    }
+
+    pub fn with_todo_markers(mut self, markers: Vec<String>) -> Self {
+        self.todo_markers = markers;
+        self
+    }
+
+    pub fn enable_sniff(mut self, enable: bool) -> Self {
+        self.sniff = enable;
+        self
+    }
+
+    pub fn enable_check_eol(mut self, enable: bool) -> Self {
+        self.check_eol = enable;
+        self
+    }
+
+    pub fn enable_progress(mut self, enable: bool) -> Self {
+        self.progress = enable;
+        self
+    }
+
+    pub fn with_verbose(mut self, verbose: u8) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    pub fn enable_quiet(mut self, enable: bool) -> Self {
+        self.quiet = enable;
+        self
+    }
+
+    /// 日志输出级别：`quiet` 时关闭，否则按 `verbose` 从 warn 逐级到 trace
+    pub fn log_level(&self) -> LevelFilter {
+        if self.quiet {
+            return LevelFilter::Off;
+        }
+        match self.verbose {
+            0 => LevelFilter::Warn,
+            1 => LevelFilter::Info,
+            2 => LevelFilter::Debug,
+            _ => LevelFilter::Trace,
+        }
+    }
+
+    pub fn with_only_lang_content(mut self, lang: LangType) -> Self {
+        self.only_lang_content = Some(lang);
+        self
+    }
+
+    pub fn enable_strict_utf8(mut self, enable: bool) -> Self {
+        self.strict_utf8 = enable;
+        self
+    }
+
+    pub fn with_encoding(mut self, encoding: impl Into<String>) -> Self {
+        self.encoding = Some(encoding.into());
+        self
+    }
+
     pub fn enable_async_processing(mut self, enable: bool) -> Self {
         self.enable_async = enable;
         self
     }
-}
\ No newline at end of file
+}
+
+/// 配置文件（如 `toukei.toml`）中可设置的字段，未出现的字段保持默认值
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileConfig {
+    paths: Option<Vec<String>>,
+    types: Option<Vec<String>>,
+    exclude_types: Option<Vec<String>>,
+    ignore_blanks: Option<bool>,
+    ignore_comments: Option<bool>,
+    exclude_files: Option<Vec<String>>,
+    /// 输出格式，多个以逗号分隔，与 `--output` 相同
+    output: Option<String>,
+    num_workers: Option<usize>,
+}
+
+/// 读取配置文件时的错误
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Toml(toml::de::Error),
+    InvalidValue(String),
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "IO error: {}", e),
+            ConfigError::Toml(e) => write!(f, "TOML error: {}", e),
+            ConfigError::InvalidValue(msg) => write!(f, "Invalid value: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// 从 TOML 配置文件读取配置，文件中未设置的字段使用默认值
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Config, ConfigError> {
+        let text = std::fs::read_to_string(path).map_err(ConfigError::Io)?;
+        Self::from_toml(&text)
+    }
+
+    /// 从 TOML 文本读取配置
+    pub fn from_toml(text: &str) -> Result<Config, ConfigError> {
+        let file: FileConfig = toml::from_str(text).map_err(ConfigError::Toml)?;
+        let mut config = Config::new();
+
+        if let Some(paths) = file.paths {
+            config.paths = paths;
+        }
+        if let Some(types) = file.types {
+            config.types = types.iter().map(|t| t.to_lowercase()).collect();
+        }
+        if let Some(types) = file.exclude_types {
+            config.exclude_types = types.iter().map(|t| t.to_lowercase()).collect();
+        }
+        if let Some(ignore) = file.ignore_blanks {
+            config.ignore_blanks = ignore;
+        }
+        if let Some(ignore) = file.ignore_comments {
+            config.ignore_comments = ignore;
+        }
+        if let Some(exclude_files) = file.exclude_files {
+            config.exclude_files = exclude_files;
+        }
+        if let Some(output) = file.output {
+            let formats = output.split(',')
+                .map(|s| s.trim().parse::<OutputFormat>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(ConfigError::InvalidValue)?;
+            config = config.with_output_formats(formats);
+        }
+        if let Some(num) = file.num_workers {
+            config.num_workers = num;
+        }
+
+        Ok(config)
+    }
+}
@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+/// 默认的测试文件匹配模式，用于区分测试代码与生产代码
+pub const DEFAULT_TEST_PATTERNS: &[&str] = &[
+    "**/tests/**",
+    "*_test.*",
+    "test_*.*",
+    "*.spec.*",
+];
+
+/// 默认的制表符宽度，用于计算缩进
+pub const DEFAULT_TAB_WIDTH: usize = 4;
+
+/// 常见的第三方依赖/构建产物目录，开启 `--no-vendor` 时按目录名排除
+pub const DEFAULT_VENDOR_DIRS: &[&str] = &[
+    "node_modules",
+    "vendor",
+    "third_party",
+    "target",
+    "dist",
+    "build",
+    ".venv",
+];
+
+/// 开启 `--todos` 时在注释中统计的待办标记
+pub const DEFAULT_TODO_MARKERS: &[&str] = &["TODO", "FIXME", "HACK", "XXX"];
+
+/// 终端宽度小于该列数时文本输出自动改用紧凑格式，与表格分隔线同宽
+pub const COMPACT_TERMINAL_WIDTH: usize = 80;
+
+/// 开启 `--parallel-files` 后，超过该字节数的文件按段并行分析
+pub const DEFAULT_PARALLEL_THRESHOLD: usize = 8 * 1024 * 1024;
+
+/// 并行分析时每段的最小字节数，过小的分段只会增加调度开销
+pub const MIN_PARALLEL_CHUNK: usize = 256 * 1024;
+
+/// `--io-retries` 第一次重试前的等待时间，之后每次翻倍
+pub const IO_RETRY_BACKOFF: Duration = Duration::from_millis(50);
+
+/// 压缩包中单个条目解压后的最大字节数，超过的条目跳过，不按压缩包头声明的大小分配内存
+pub const MAX_ARCHIVE_ENTRY_BYTES: u64 = 64 * 1024 * 1024;
@@ -1,13 +1,33 @@
-use crate::{config::Config, langs::registry::get_type_from_ext, stats::FileStat, syntax::LexerFactory};
+use crate::{config::Config, langs::registry::get_type_from_ext_with, stats::FileStat, syntax::{LexOptions, LexerFactory}};
+use crate::consts::{IO_RETRY_BACKOFF, MAX_ARCHIVE_ENTRY_BYTES, MIN_PARALLEL_CHUNK};
+use crate::langs::detect;
+use crate::langs::lang_type::LangType;
+use crate::report::{Report, SkipReason, SkipWarning};
+use crate::syntax::embedded;
+use crate::syntax::LineExplanation;
+use crate::syntax::parallel::lex_parallel;
+use crate::utils::editorconfig::EditorConfigCache;
+use crate::walker::{is_archive, FileReader};
 
-use std::path::Path;
-use std::io::{BufReader, Read, Seek};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::io::{BufReader, Cursor, ErrorKind, Read, Seek};
+use std::sync::Arc;
+use std::time::Duration;
 use std::fs::File;
-use encoding_rs_io::{DecodeReaderBytes, DecodeReaderBytesBuilder};
+use encoding_rs::Encoding;
+use encoding_rs_io::DecodeReaderBytesBuilder;
+use log::warn;
+use zip::ZipArchive;
 
 #[derive(Debug, Clone)]
 pub struct Counter {
     config: Config,
+    ext_overrides: HashMap<String, LangType>,
+    /// `config.encoding` 对应的编码，未设置或无法识别时为 None
+    encoding: Option<&'static Encoding>,
+    /// 克隆出的 Counter 共享同一份缓存
+    editorconfig: Arc<EditorConfigCache>,
 }
 
 impl Counter {
@@ -17,44 +37,330 @@ impl Counter {
             Ok(0) => false,
             Ok(n) => {
                 let _ = file.seek(std::io::SeekFrom::Start(0));
-                buffer.iter().take(n).any(|&b| b == 0)
+                Self::is_binary(&buffer[..n])
             }
             Err(_) => false,
         }
     }
+
+    /// 开头 1024 字节内含有 NUL 视为二进制
+    fn is_binary(bytes: &[u8]) -> bool {
+        bytes.iter().take(1024).any(|&b| b == 0)
+    }
+    /// 读取文件开头用于内容判断，读完后回到文件起点
+    fn read_head(file: &mut File) -> String {
+        let mut buffer = vec![0; detect::SNIFF_BYTES];
+        let n = file.read(&mut buffer).unwrap_or(0);
+        let _ = file.seek(std::io::SeekFrom::Start(0));
+        String::from_utf8_lossy(&buffer[..n]).into_owned()
+    }
+
     pub fn new(config: Config) -> Self {
+        let ext_overrides = config.ext_override_map();
+        let encoding = Self::resolve_encoding(&config);
         Counter {
-            config
+            config,
+            ext_overrides,
+            encoding,
+            editorconfig: Arc::new(EditorConfigCache::new()),
         }
     }
 
-    pub fn count(&self, path: impl AsRef<Path>) -> Result<FileStat, CounterError> {
-        let ext = path.as_ref().extension()
+    /// 替换配置，之后的统计使用新配置
+    pub fn set_config(&mut self, config: Config) {
+        self.ext_overrides = config.ext_override_map();
+        self.encoding = Self::resolve_encoding(&config);
+        self.config = config;
+    }
+
+    /// 磁盘上文件的制表符宽度：缩进敏感的语言优先使用 `.editorconfig` 中的设置，其他语言不查找
+    fn tab_width_for(&self, path: &Path, lang_type: LangType) -> usize {
+        if !LexerFactory::uses_tab_width(lang_type) {
+            return self.config.tab_width;
+        }
+        self.editorconfig.tab_width_for(path).unwrap_or(self.config.tab_width)
+    }
+
+    fn resolve_encoding(config: &Config) -> Option<&'static Encoding> {
+        let label = config.encoding.as_deref()?;
+        let encoding = Encoding::for_label(label.trim().as_bytes());
+        if encoding.is_none() {
+            warn!("Unknown encoding {}, falling back to BOM detection", label);
+        }
+        encoding
+    }
+
+    fn lang_for_ext(&self, ext: &str) -> Result<LangType, CounterError> {
+        get_type_from_ext_with(ext, &self.ext_overrides)
+            .ok_or_else(|| CounterError::LexError(format!("Unknown language for extension: {}", ext)))
+    }
+
+    fn ext_of(path: &Path) -> String {
+        path.extension()
             .and_then(|s| s.to_str())
             .unwrap_or("")
-            .to_lowercase();
+            .to_lowercase()
+    }
+
+    pub fn count(&self, path: impl AsRef<Path>) -> Result<FileStat, CounterError> {
+        if self.config.io_retries > 0 {
+            return self.count_with_retry(path.as_ref());
+        }
 
-        let lang_type = get_type_from_ext(&ext)
-            .ok_or_else(|| CounterError::LexError(format!("Unknown language for extension: {}", ext)))?;
         let mut file = File::open(path.as_ref()).map_err(|e| CounterError::IoError(e.to_string()))?;
+        if Self::is_binary_file(&mut file) {
+            return Err(CounterError::BinaryFile);
+        }
+        let lang_type = self.detect_lang(path.as_ref(), &mut file)?;
+        let size = file.metadata().map(|m| m.len() as usize).unwrap_or(0);
+
+        let tab_width = self.tab_width_for(path.as_ref(), lang_type);
+
+        self.count_as(path.as_ref(), lang_type, tab_width, size, file)
+    }
+
+    /// 按扩展名识别文件的语言，必要时参考文件开头的内容
+    fn detect_lang(&self, path: &Path, file: &mut File) -> Result<LangType, CounterError> {
+        let ext = Self::ext_of(path);
+        // 没有可识别的扩展名时看首行的 shebang，如 `#!/usr/bin/env python3`；开启 `sniff` 时再按语法特征猜测
+        let lang_type = match self.lang_for_ext(&ext) {
+            Ok(lang) => lang,
+            Err(e) => detect::lang_from_content(&Self::read_head(file), self.config.sniff).ok_or(e)?,
+        };
+
+        // 扩展名有歧义（如 .h）时根据文件开头的内容判断，用户指定了映射的除外
+        if detect::is_ambiguous(&ext)
+            && !self.ext_overrides.contains_key(&ext)
+            && let Some(lang) = detect::resolve_by_content(&ext, &Self::read_head(file)) {
+            return Ok(lang);
+        }
+        Ok(lang_type)
+    }
 
+    /// 逐行给出文件的分类结果，以及识别出的语言，用于排查某个文件为什么这样统计
+    pub fn explain(&self, path: impl AsRef<Path>) -> Result<(LangType, Vec<LineExplanation>), CounterError> {
+        let path = path.as_ref();
+        let mut file = File::open(path).map_err(|e| CounterError::IoError(e.to_string()))?;
         if Self::is_binary_file(&mut file) {
             return Err(CounterError::BinaryFile);
         }
+        let lang_type = self.detect_lang(path, &mut file)?;
+        let tab_width = self.tab_width_for(path, lang_type);
 
-        let reader = DecodeReaderBytesBuilder::new()
-            .encoding(None)
+        let decoder = DecodeReaderBytesBuilder::new()
+            .encoding(self.encoding)
+            .strip_bom(true)
             .build(file);
+        let lines = LexerFactory::get_lexer_with_options(lang_type, self.lex_options(lang_type, tab_width))
+            .ok_or_else(|| CounterError::LexError("Unknown language".to_string()))?
+            .explain(&mut BufReader::new(decoder))
+            .map_err(CounterError::LexError)?;
+        Ok((lang_type, lines))
+    }
 
-        let mut buf_reader = BufReader::new(reader);
+    fn lex_options(&self, lang_type: LangType, tab_width: usize) -> LexOptions {
+        LexOptions {
+            tab_width,
+            blanks_in_comments_are_blank: self.config.blanks_in_comments_are_blank_for(lang_type),
+            blanks_in_strings_are_code: self.config.blanks_in_strings_are_code,
+            detect_commented_code: self.config.detect_commented_code,
+            skip_header_comments: self.config.skip_header_comments,
+            count_complexity: self.config.complexity,
+            todo_markers: if self.config.todos { self.config.todo_markers.clone() } else { Vec::new() },
+        }
+    }
+
+    /// 按指定的语言统计文件，不根据扩展名或内容识别语言
+    pub fn count_with_lang(&self, path: impl AsRef<Path>, lang_type: LangType) -> Result<FileStat, CounterError> {
+        let path = path.as_ref();
+        let mut file = File::open(path).map_err(|e| CounterError::IoError(e.to_string()))?;
+        if Self::is_binary_file(&mut file) {
+            return Err(CounterError::BinaryFile);
+        }
+        let size = file.metadata().map(|m| m.len() as usize).unwrap_or(0);
+        let tab_width = self.tab_width_for(path, lang_type);
+
+        self.count_as(path, lang_type, tab_width, size, file)
+    }
+
+    /// 统计一组文件并汇总为报告，指定了语言的文件不再按扩展名识别
+    ///
+    /// 无法统计的文件（二进制、编码非法、语言未知等）跳过并记为报告中的警告，
+    /// 代码行数少于 `min_code_lines` 的文件不计入。
+    pub fn count_many(&self, items: &[(PathBuf, Option<LangType>)]) -> Report {
+        let mut report = Report::new();
+        for (path, lang) in items {
+            let result = match lang {
+                Some(lang) => self.count_with_lang(path, *lang),
+                None => self.count(path),
+            };
+            match result {
+                Ok(stat) if stat.code < self.config.min_code_lines => (),
+                Ok(stat) => report.add(stat),
+                Err(e) => {
+                    let reason = SkipReason::from_error(&e);
+                    if let SkipReason::Other(msg) = &reason {
+                        warn!("Skipping file {}: {}", path.display(), msg);
+                    }
+                    report.add_warning(SkipWarning { path: path.display().to_string(), reason });
+                }
+            }
+        }
+        report
+    }
+
+    /// 先把整个文件读入内存（读取失败时按 `io_retries` 重试），再统计
+    fn count_with_retry(&self, path: &Path) -> Result<FileStat, CounterError> {
+        let bytes = read_with_retry(|| File::open(path), self.config.io_retries, IO_RETRY_BACKOFF)
+            .map_err(|e| CounterError::IoError(e.to_string()))?;
+
+        self.count_bytes(path, bytes, true)
+    }
+
+    /// 统计已读入内存的内容，语言按 `path` 的扩展名识别，必要时参考内容开头
+    ///
+    /// `on_disk` 为 false 时（如压缩包中的条目）`path` 不是真实路径，不查找 `.editorconfig`。
+    fn count_bytes(&self, path: &Path, bytes: Vec<u8>, on_disk: bool) -> Result<FileStat, CounterError> {
+        let ext = Self::ext_of(path);
+        let by_ext = self.lang_for_ext(&ext);
+
+        if Self::is_binary(&bytes) {
+            return Err(CounterError::BinaryFile);
+        }
+        let mut lang_type = match by_ext {
+            Ok(lang) => lang,
+            Err(e) => {
+                let head = String::from_utf8_lossy(&bytes[..bytes.len().min(detect::SNIFF_BYTES)]);
+                detect::lang_from_content(&head, self.config.sniff).ok_or(e)?
+            }
+        };
+
+        if detect::is_ambiguous(&ext)
+            && !self.ext_overrides.contains_key(&ext) {
+            let head = String::from_utf8_lossy(&bytes[..bytes.len().min(detect::SNIFF_BYTES)]);
+            if let Some(lang) = detect::resolve_by_content(&ext, &head) {
+                lang_type = lang;
+            }
+        }
+
+        let tab_width = if on_disk { self.tab_width_for(path, lang_type) } else { self.config.tab_width };
+        let size = bytes.len();
+        self.count_as(path, lang_type, tab_width, size, Cursor::new(bytes))
+    }
+
+    /// 统计一个扫描到的文件：zip 压缩包返回其中各条目的统计，其他文件返回单个统计
+    ///
+    /// 遍历时有歧义的扩展名（如 `.h`）只要任一候选语言被选中就会保留，
+    /// 因此按内容确定语言后再按 `types` / `exclude_types` 过滤一次。
+    pub fn count_entries(&self, path: impl AsRef<Path>) -> Result<Vec<FileStat>, CounterError> {
+        let path = path.as_ref();
+        let stats = if is_archive(path) {
+            self.count_archive(path)?
+        } else {
+            vec![self.count(path)?]
+        };
+        Ok(stats.into_iter().filter(|stat| self.config.wants(stat.lang)).collect())
+    }
+
+    /// 统计 zip 压缩包中的文件，每个条目按自身的扩展名识别语言
+    ///
+    /// 结果的路径形如 `archive.zip!inner/file.rs`；条目与目录中的文件一样按配置过滤，
+    /// 目录、二进制条目、无法识别语言的条目以及解压后超过 `MAX_ARCHIVE_ENTRY_BYTES` 的条目跳过。
+    pub fn count_archive(&self, path: impl AsRef<Path>) -> Result<Vec<FileStat>, CounterError> {
+        let file = File::open(path.as_ref()).map_err(|e| CounterError::IoError(e.to_string()))?;
+        self.count_archive_reader(path, BufReader::new(file))
+    }
+
+    /// 与 `count_archive` 相同，压缩包内容来自任意可定位的来源，`path` 只用于拼接条目路径
+    pub fn count_archive_reader(&self, path: impl AsRef<Path>, reader: impl Read + Seek) -> Result<Vec<FileStat>, CounterError> {
+        let mut archive = ZipArchive::new(reader)
+            .map_err(|e| CounterError::IoError(e.to_string()))?;
+
+        let reader = FileReader::new(self.config.clone());
+        let mut stats = Vec::new();
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index)
+                .map_err(|e| CounterError::IoError(e.to_string()))?;
+            if entry.is_dir() {
+                continue;
+            }
+            let inner = PathBuf::from(format!("{}!{}", path.as_ref().display(), entry.name()));
+            if !reader.include_path(&inner) {
+                continue;
+            }
+            // 条目头中声明的大小不可信，按实际解压出的字节数限制
+            let Some(bytes) = read_limited(&mut entry, MAX_ARCHIVE_ENTRY_BYTES)
+                .map_err(|e| CounterError::IoError(e.to_string()))? else {
+                warn!("Skipping archive entry {}: larger than {} bytes", inner.display(), MAX_ARCHIVE_ENTRY_BYTES);
+                continue;
+            };
 
-        let lexer = LexerFactory::get_lexer(lang_type)
+            match self.count_bytes(&inner, bytes, false) {
+                Ok(stat) if stat.code < self.config.min_code_lines => (),
+                Ok(stat) => stats.push(stat),
+                Err(e) => warn!("Skipping archive entry {}: {}", inner.display(), e),
+            }
+        }
+        Ok(stats)
+    }
+
+    /// 统计任意来源的内容（如 git 对象），`path` 不需要真实存在
+    ///
+    /// 语言识别与扫描磁盘文件相同：按扩展名，无扩展名或有歧义（如 `.h`）时参考内容开头；二进制内容返回 `BinaryFile`。
+    pub fn count_reader(&self, path: impl AsRef<Path>, mut reader: impl Read) -> Result<FileStat, CounterError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).map_err(|e| CounterError::IoError(e.to_string()))?;
+        self.count_bytes(path.as_ref(), bytes, false)
+    }
+
+    fn count_as(
+        &self,
+        path: &Path,
+        lang_type: LangType,
+        tab_width: usize,
+        size: usize,
+        reader: impl Read,
+    ) -> Result<FileStat, CounterError> {
+        let mut decoder = DecodeReaderBytesBuilder::new()
+            .encoding(self.encoding)
+            // 严格模式下带 UTF-8 BOM 的文件也不做有损转码，才能发现其中的非法序列
+            .utf8_passthru(self.config.strict_utf8)
+            .strip_bom(true)
+            .build(reader);
+
+        let options = self.lex_options(lang_type, tab_width);
+        let lexer = LexerFactory::get_lexer_with_options(lang_type, options.clone())
             .ok_or_else(|| CounterError::LexError("Unknown language".to_string()))?;
 
-        let mut stat = lexer.lex(&mut buf_reader).map_err(|e| CounterError::LexError(e))?;
+        let parallel = self.config.parallel_files && size >= self.config.parallel_threshold;
+        let mut stat = if let Some(target) = self.config.only_lang_content
+            && embedded::is_composite(lang_type) {
+            // 复合文件只统计其中 `target` 语言的片段
+            let source = self.read_source(&mut decoder)?;
+            let content = embedded::extract_lang_content(&source, target);
+            LexerFactory::get_lexer_with_options(target, options)
+                .ok_or_else(|| CounterError::LexError("Unknown language".to_string()))?
+                .lex(&mut Cursor::new(content))
+                .map_err(CounterError::LexError)?
+        } else if parallel || self.config.strict_utf8 {
+            let source = self.read_source(&mut decoder)?;
+            let chunk_bytes = (source.len() / (rayon::current_num_threads() * 4)).max(MIN_PARALLEL_CHUNK);
+            match parallel.then(|| lex_parallel(lang_type, options, &source, chunk_bytes)).flatten() {
+                Some(stat) => stat,
+                None => lexer.lex(&mut Cursor::new(source)).map_err(CounterError::LexError)?,
+            }
+        } else {
+            lexer.lex(&mut BufReader::new(decoder)).map_err(CounterError::LexError)?
+        };
+        if self.config.ignore_blanks {
+            stat.ignore_blanks();
+        }
+        if self.config.ignore_comments {
+            stat.ignore_comments();
+        }
         stat.lang = lang_type;
-        stat.path = path.as_ref().display().to_string();
-        stat.name = path.as_ref().file_name()
+        stat.path = path.display().to_string();
+        stat.name = path.file_name()
             .and_then(|s| s.to_str())
             .unwrap_or("")
             .to_string();
@@ -62,25 +368,78 @@ impl Counter {
         Ok(stat)
     }
 
+    /// 读取全部内容，严格模式下遇到非法 UTF-8 返回错误，否则有损转换
+    fn read_source(&self, reader: &mut impl Read) -> Result<String, CounterError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)
+            .map_err(|e| CounterError::IoError(e.to_string()))?;
+        match String::from_utf8(bytes) {
+            Ok(source) => Ok(source),
+            Err(_) if self.config.strict_utf8 => Err(CounterError::InvalidUtf8),
+            Err(e) => Ok(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+        }
+    }
+
     /// 异步版本的计数函数
     pub async fn count_async(&self, path: impl AsRef<Path> + Send) -> Result<FileStat, CounterError> {
         // 使用spawn_blocking在阻塞线程中执行同步代码
         let path = path.as_ref().to_path_buf();
-        let config = self.config.clone();
+        let counter = self.clone();
 
-        tokio::task::spawn_blocking(move || {
-            let counter = Counter::new(config);
-            counter.count(path)
-        }).await
+        tokio::task::spawn_blocking(move || counter.count(path)).await
+        .map_err(|e| CounterError::IoError(format!("Task join error: {}", e)))?
+    }
+
+    /// `count_entries` 的异步版本
+    pub async fn count_entries_async(&self, path: impl AsRef<Path> + Send) -> Result<Vec<FileStat>, CounterError> {
+        let path = path.as_ref().to_path_buf();
+        let counter = self.clone();
+
+        tokio::task::spawn_blocking(move || counter.count_entries(path)).await
         .map_err(|e| CounterError::IoError(format!("Task join error: {}", e)))?
     }
 } 
 
+/// 读取全部内容，超过 `limit` 字节时返回 None；缓冲区随读取增长，不预先分配
+fn read_limited(reader: impl Read, limit: u64) -> std::io::Result<Option<Vec<u8>>> {
+    let mut bytes = Vec::new();
+    reader.take(limit.saturating_add(1)).read_to_end(&mut bytes)?;
+    Ok((bytes.len() as u64 <= limit).then_some(bytes))
+}
+
+/// 打开并读取全部内容，遇到暂时性的 IO 错误时最多重试 `retries` 次，等待时间从 `backoff` 开始每次翻倍
+///
+/// 文件不存在或没有权限不会因重试而改变，直接返回错误。
+pub fn read_with_retry<R, F>(mut open: F, retries: usize, backoff: Duration) -> std::io::Result<Vec<u8>>
+where
+    R: Read,
+    F: FnMut() -> std::io::Result<R>,
+{
+    let mut attempt = 0;
+    loop {
+        let result = open().and_then(|mut reader| {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).map(|_| bytes)
+        });
+        match result {
+            Err(e) if attempt < retries
+                && !matches!(e.kind(), ErrorKind::NotFound | ErrorKind::PermissionDenied) => {
+                warn!("IO error ({}), retrying ({}/{})", e, attempt + 1, retries);
+                std::thread::sleep(backoff * 2u32.saturating_pow(attempt as u32));
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum CounterError {
     IoError(String),
     LexError(String),
     BinaryFile,
+    /// `--strict-utf8` 下文件含有非法的 UTF-8 序列
+    InvalidUtf8,
 }
 
 impl std::fmt::Display for CounterError {
@@ -89,6 +448,7 @@ impl std::fmt::Display for CounterError {
             CounterError::IoError(msg) => write!(f, "IO Error: {}", msg),
             CounterError::LexError(msg) => write!(f, "Lexing Error: {}", msg),
             CounterError::BinaryFile => write!(f, "Binary file detected"),
+            CounterError::InvalidUtf8 => write!(f, "Invalid UTF-8"),
         }
     }
 }
@@ -98,6 +458,7 @@ impl std::error::Error for CounterError {}
 #[cfg(test)]
 mod tests { 
     use super::*;
+    use crate::syntax::LineKind;
 
     #[test]
     fn test_counter() {
@@ -106,6 +467,611 @@ mod tests {
 
         assert_eq!(stat.path, "./src/counter.rs");
         assert_eq!(stat.name, "counter.rs");
-        assert_eq!(stat.lines, 125);
+        let expected = std::fs::read_to_string("./src/counter.rs").unwrap().lines().count();
+        assert_eq!(stat.lines, expected);
+    }
+
+    #[test]
+    fn test_count_extensionless_script_by_shebang() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let script = dir.path().join("deploy");
+        std::fs::write(&script, "#!/usr/bin/env python3\n# deploy\n\nprint('ok')\n").unwrap();
+        let shell = dir.path().join("run");
+        std::fs::write(&shell, "#!/bin/sh\necho hi\n").unwrap();
+        let plain = dir.path().join("README");
+        std::fs::write(&plain, "just text\n").unwrap();
+
+        let counter = Counter::new(Config::new());
+        let stat = counter.count(&script).unwrap();
+        assert_eq!(stat.lang, LangType::Python);
+        assert_eq!((stat.lines, stat.code, stat.comments, stat.blanks), (4, 1, 2, 1));
+        assert_eq!(counter.count(&shell).unwrap().lang, LangType::Shell);
+        assert!(matches!(counter.count(&plain), Err(CounterError::LexError(_))));
+
+        // 整体读取的重试路径同样识别 shebang
+        let counter = Counter::new(Config::new().with_io_retries(1));
+        assert_eq!(counter.count(&script).unwrap().lang, LangType::Python);
+    }
+
+    #[test]
+    fn test_count_many_with_lang_override() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let rust = dir.path().join("main.rs");
+        std::fs::write(&rust, "// entry
+fn main() {}
+").unwrap();
+        let python = dir.path().join("tool.py");
+        std::fs::write(&python, "print('hi')
+").unwrap();
+        // 扩展名无法识别，由调用方指定为 Shell
+        let hook = dir.path().join("pre-commit.hook");
+        std::fs::write(&hook, "# lint first
+cargo clippy
+
+cargo test
+").unwrap();
+        let binary = dir.path().join("blob.rs");
+        std::fs::write(&binary, b"\0\x01\x02").unwrap();
+
+        let report = Counter::new(Config::new()).count_many(&[
+            (rust, None),
+            (python, None),
+            (hook.clone(), Some(LangType::Shell)),
+            (binary, None),
+        ]);
+
+        assert_eq!(report.file_count(), 3);
+        assert_eq!(report.get_by_lang(&LangType::Rust).unwrap().code, 1);
+        assert_eq!(report.get_by_lang(&LangType::Python).unwrap().code, 1);
+        let shell = report.get_by_lang(&LangType::Shell).unwrap();
+        assert_eq!((shell.lines, shell.code, shell.comments, shell.blanks), (4, 2, 1, 1));
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].reason, SkipReason::Binary);
+
+        // 不指定语言时无法识别的文件被跳过
+        let report = Counter::new(Config::new()).count_many(&[(hook, None)]);
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn test_mixed_line_endings() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let mixed = dir.path().join("mixed.rs");
+        std::fs::write(&mixed, "fn a() {}\r\nfn b() {}\nfn c() {}\r\n").unwrap();
+        let crlf = dir.path().join("crlf.rs");
+        std::fs::write(&crlf, "fn a() {}\r\nfn b() {}\r\nfn c() {}").unwrap();
+        let notes = dir.path().join("notes.md");
+        std::fs::write(&notes, "# Notes\r\n\nbody\n").unwrap();
+
+        let counter = Counter::new(Config::new());
+        let stat = counter.count(&mixed).unwrap();
+        assert!(stat.mixed_eol);
+        assert_eq!((stat.lines, stat.code), (3, 3));
+        // 最后一行没有换行不算 LF
+        assert!(!counter.count(&crlf).unwrap().mixed_eol);
+        assert!(counter.count(&notes).unwrap().mixed_eol);
+
+        let config = Config::new().enable_parallel_files(true).with_parallel_threshold(0);
+        assert!(Counter::new(config).count(&mixed).unwrap().mixed_eol);
+
+        let report = counter.count_many(&[(mixed.clone(), None), (crlf, None)]);
+        let files: Vec<&str> = report.mixed_eol_files().iter().map(|s| s.path.as_str()).collect();
+        assert_eq!(files, [mixed.display().to_string()]);
+    }
+
+    #[test]
+    fn test_explain_lines() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let path = dir.path().join("lib.rs");
+        std::fs::write(&path, "\
+/// Adds one.
+pub fn inc(x: i32) -> i32 {
+
+    x + 1 // plus
+}
+struct Point;
+").unwrap();
+
+        let (lang, lines) = Counter::new(Config::new()).explain(&path).unwrap();
+        assert_eq!(lang, LangType::Rust);
+        let labels: Vec<(usize, LineKind, bool, bool)> = lines.iter()
+            .map(|l| (l.number, l.kind, l.function_start, l.class_start))
+            .collect();
+        assert_eq!(labels, [
+            (1, LineKind::Comment, false, false),
+            (2, LineKind::Code, true, false),
+            (3, LineKind::Blank, false, false),
+            (4, LineKind::Mixed, false, false),
+            (5, LineKind::Code, false, false),
+            (6, LineKind::Code, false, true),
+        ]);
+        assert_eq!(lines[3].text, "    x + 1 // plus");
+    }
+
+    #[test]
+    fn test_sniff_extensionless_python() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let script = dir.path().join("manage");
+        std::fs::write(&script, "\
+import sys
+
+# dispatch sub-commands
+def main(argv):
+    for arg in argv:
+        if arg == 'migrate':
+            migrate()
+        elif arg == 'serve':
+            serve()
+
+class Command:
+    pass
+").unwrap();
+
+        assert!(Counter::new(Config::new()).count(&script).is_err());
+
+        let counter = Counter::new(Config::new().enable_sniff(true));
+        let stat = counter.count(&script).unwrap();
+        assert_eq!(stat.lang, LangType::Python);
+        assert_eq!((stat.lines, stat.comments, stat.blanks), (12, 1, 2));
+        let counter = Counter::new(Config::new().enable_sniff(true).with_io_retries(1));
+        assert_eq!(counter.count(&script).unwrap().lang, LangType::Python);
+    }
+
+    #[test]
+    fn test_editorconfig_tab_width() {
+        // 制表符缩进的方法定义，方法体使用 4 个空格
+        let code = "class A:\n\tdef f(self):\n    x = 1\n    return x\n";
+
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let file = dir.path().join("a.py");
+        std::fs::write(&file, code).unwrap();
+        std::fs::write(dir.path().join(".editorconfig"), "root = true\n").unwrap();
+
+        // 全局默认 tab 宽度 4：方法体与 def 同级，不计入函数体
+        let counter = Counter::new(Config::new());
+        assert_eq!(counter.count(&file).unwrap().function_lines, 1);
+
+        // .editorconfig 指定 tab_width = 2：方法体缩进更深；同一个 Counter 会缓存已读过的配置
+        std::fs::write(dir.path().join(".editorconfig"), "root = true\n[*.py]\ntab_width = 2\n").unwrap();
+        assert_eq!(counter.count(&file).unwrap().function_lines, 1);
+        let counter = Counter::new(Config::new());
+        assert_eq!(counter.count(&file).unwrap().function_lines, 3);
+    }
+
+    #[test]
+    fn test_parallel_files_matches_serial() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let file = dir.path().join("big.rs");
+        std::fs::write(&file, "/* a\n\n b */\nfn f() {\n    1\n}\n".repeat(5_000)).unwrap();
+
+        let serial = Counter::new(Config::new()).count(&file).unwrap();
+        let config = Config::new().enable_parallel_files(true).with_parallel_threshold(0);
+        let parallel = Counter::new(config).count(&file).unwrap();
+
+        assert_eq!(parallel, serial);
+    }
+
+    #[test]
+    fn test_objective_c_files() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let impl_file = dir.path().join("Foo.m");
+        std::fs::write(&impl_file, "\
+#import \"Foo.h\"
+
+// Foo implementation
+@implementation Foo
+- (int)add:(int)a to:(int)b {
+    return a + b;
+}
+@end
+").unwrap();
+        let header = dir.path().join("Foo.h");
+        std::fs::write(&header, "#import <Foundation/Foundation.h>\n@interface Foo : NSObject\n- (int)add:(int)a to:(int)b;\n@end\n").unwrap();
+
+        let counter = Counter::new(Config::new());
+        let stat = counter.count(&impl_file).unwrap();
+        assert_eq!(stat.lang, LangType::ObjectiveC);
+        assert_eq!((stat.lines, stat.code, stat.comments, stat.blanks), (8, 6, 1, 1));
+        assert_eq!((stat.functions, stat.function_lines), (1, 3));
+
+        // 带有 Objective-C 标记的头文件不再当作 C
+        assert_eq!(counter.count(&header).unwrap().lang, LangType::ObjectiveC);
+    }
+
+    #[test]
+    fn test_powershell_and_batch_files() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let script = dir.path().join("greet.ps1");
+        std::fs::write(&script, "\
+<#
+  Greets a user.
+#>
+function Get-Greeting($Name) {
+    # build the message
+    return \"Hello, $Name\"
+}
+
+Get-Greeting -Name 'World'
+").unwrap();
+        let batch = dir.path().join("build.bat");
+        std::fs::write(&batch, "\
+@echo off
+REM Build the project
+rem lower case works too
+:: label-style comment
+set REMOTE=origin
+
+:build
+cargo build
+").unwrap();
+
+        let counter = Counter::new(Config::new());
+        let stat = counter.count(&script).unwrap();
+        assert_eq!(stat.lang, LangType::PowerShell);
+        assert_eq!((stat.lines, stat.code, stat.comments, stat.blanks), (9, 4, 4, 1));
+        assert_eq!((stat.functions, stat.function_lines), (1, 4));
+
+        let stat = counter.count(&batch).unwrap();
+        assert_eq!(stat.lang, LangType::Batch);
+        assert_eq!((stat.lines, stat.code, stat.comments, stat.blanks), (8, 4, 3, 1));
+    }
+
+    #[test]
+    fn test_count_ext_override() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let file = dir.path().join("header.inc");
+        std::fs::write(&file, "<?php\n// shared header\nfunction greet() {\n    echo 'hi';\n}\n").unwrap();
+
+        assert!(Counter::new(Config::new()).count(&file).is_err());
+
+        let counter = Counter::new(Config::new().with_ext_override("inc", LangType::Php));
+        let stat = counter.count(&file).unwrap();
+        assert_eq!(stat.lang, LangType::Php);
+        assert_eq!((stat.lines, stat.code, stat.comments), (5, 4, 1));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_detect_commented_code() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let file = dir.path().join("lib.rs");
+        std::fs::write(&file, "\
+// Adds two numbers and returns the sum.
+// fn old_add(a: i32, b: i32) -> i32 {
+//     a + b;
+// }
+/// Documented item
+fn add(a: i32, b: i32) -> i32 {
+    a + b // plain trailing comment
+}
+").unwrap();
+
+        let stat = Counter::new(Config::new()).count(&file).unwrap();
+        assert_eq!(stat.commented_code, 0);
+
+        let stat = Counter::new(Config::new().enable_detect_commented_code(true)).count(&file).unwrap();
+        assert_eq!(stat.comments, 5);
+        assert_eq!(stat.commented_code, 3);
+    }
+
+    #[test]
+    fn test_todos() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let file = dir.path().join("lib.rs");
+        std::fs::write(&file, "// TODO: rename\nfn f() {} // NOTE: keep\n").unwrap();
+
+        assert_eq!(Counter::new(Config::new()).count(&file).unwrap().todos, 0);
+
+        let counter = Counter::new(Config::new().enable_todos(true));
+        assert_eq!(counter.count(&file).unwrap().todos, 1);
+
+        let counter = Counter::new(Config::new()
+            .enable_todos(true)
+            .with_todo_markers(vec!["TODO".to_string(), "NOTE".to_string()]));
+        assert_eq!(counter.count(&file).unwrap().todos, 2);
+    }
+
+    #[test]
+    fn test_jsonc_comments() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let settings = "\
+{
+    // editor settings
+    \"editor.tabSize\": 4,
+    /* formatting
+       options */
+    \"editor.formatOnSave\": true
+}
+";
+        let jsonc = dir.path().join("settings.jsonc");
+        std::fs::write(&jsonc, settings).unwrap();
+        let json5 = dir.path().join("settings.json5");
+        std::fs::write(&json5, settings).unwrap();
+        let json = dir.path().join("package.json");
+        std::fs::write(&json, "{\n    \"url\": \"http://example.com\"\n}\n").unwrap();
+
+        let counter = Counter::new(Config::new());
+        for file in [&jsonc, &json5] {
+            let stat = counter.count(file).unwrap();
+            assert_eq!(stat.lang, LangType::Jsonc);
+            assert_eq!((stat.lines, stat.code, stat.comments), (7, 4, 3));
+        }
+
+        // 严格 JSON 没有注释
+        let stat = counter.count(&json).unwrap();
+        assert_eq!(stat.lang, LangType::Json);
+        assert_eq!((stat.lines, stat.code, stat.comments), (3, 3, 0));
+    }
+
+    #[test]
+    fn test_strict_utf8() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let file = dir.path().join("latin1.rs");
+        std::fs::write(&file, b"// caf\xe9\nfn main() {}\n").unwrap();
+        let bom = dir.path().join("bom.rs");
+        std::fs::write(&bom, b"\xef\xbb\xbf// caf\xe9\nfn main() {}\n").unwrap();
+
+        // 默认替换为 U+FFFD 后照常统计
+        let counter = Counter::new(Config::new());
+        for path in [&file, &bom] {
+            let stat = counter.count(path).unwrap();
+            assert_eq!((stat.lines, stat.code, stat.comments), (2, 1, 1));
+        }
+
+        let counter = Counter::new(Config::new().enable_strict_utf8(true));
+        for path in [&file, &bom] {
+            assert!(matches!(counter.count(path), Err(CounterError::InvalidUtf8)));
+        }
+
+        // 合法文件不受影响
+        std::fs::write(&file, "// café\nfn main() {}\n").unwrap();
+        assert_eq!(counter.count(&file).unwrap().lines, 2);
+    }
+
+    #[test]
+    fn test_encoding_override() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let file = dir.path().join("sjis.rs");
+        // `表` 与 `ソ` 的 Shift-JIS 编码第二个字节是 `\`，按 UTF-8 解码时会转义字符串的结束引号
+        let source = "// 表示する\nfn main() {\n    let s = \"表\";\n    // コメント\n    /* ソース */\n}\n";
+        let (bytes, _, _) = encoding_rs::SHIFT_JIS.encode(source);
+        std::fs::write(&file, &bytes).unwrap();
+
+        let stat = Counter::new(Config::new().with_encoding("shift_jis")).count(&file).unwrap();
+        assert_eq!((stat.lines, stat.code, stat.comments), (6, 3, 3));
+
+        let stat = Counter::new(Config::new()).count(&file).unwrap();
+        assert_eq!(stat.comments, 1);
+
+        let (lang, lines) = Counter::new(Config::new().with_encoding("sjis")).explain(&file).unwrap();
+        assert_eq!(lang, LangType::Rust);
+        assert_eq!(lines[3].text, "    // コメント");
+    }
+
+    #[test]
+    fn test_only_lang_content() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let file = dir.path().join("App.vue");
+        std::fs::write(&file, "\
+<template>
+  <!-- greeting -->
+  <div>{{ msg }}</div>
+</template>
+
+<script>
+// state
+export default {
+
+  data() { return { msg: 'hi' } }
+}
+</script>
+
+<style>
+div { color: red; }
+</style>
+").unwrap();
+
+        let whole = Counter::new(Config::new()).count(&file).unwrap();
+        assert_eq!(whole.lang, LangType::Vue);
+        assert_eq!(whole.lines, 16);
+
+        // 只统计 <script> 中的 JavaScript：注释按 JS 的规则识别，模板和样式不计入
+        let counter = Counter::new(Config::new().with_only_lang_content(LangType::Javascript));
+        let stat = counter.count(&file).unwrap();
+        assert_eq!(stat.lang, LangType::Vue);
+        assert_eq!((stat.lines, stat.code, stat.comments, stat.blanks), (5, 3, 1, 1));
+
+        // 非复合文件不受影响
+        let rs = dir.path().join("main.rs");
+        std::fs::write(&rs, "fn main() {}\n").unwrap();
+        assert_eq!(counter.count(&rs).unwrap().code, 1);
+    }
+
+    #[test]
+    fn test_ignore_blanks_and_comments() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let file = dir.path().join("lib.rs");
+        std::fs::write(&file, "// header\n\nfn f() {}\n/* a\n\n b */\nfn g() {}\n").unwrap();
+
+        let full = Counter::new(Config::new()).count(&file).unwrap();
+        assert_eq!((full.lines, full.code, full.comments, full.blanks), (7, 2, 3, 2));
+        assert_eq!(full.code + full.comments + full.blanks, full.lines);
+
+        let stat = Counter::new(Config::new().enable_ignore_blanks(true)).count(&file).unwrap();
+        assert_eq!((stat.lines, stat.code, stat.comments, stat.blanks), (5, 2, 3, 0));
+
+        let stat = Counter::new(Config::new().enable_ignore_comments(true)).count(&file).unwrap();
+        assert_eq!((stat.lines, stat.code, stat.comments, stat.blanks), (4, 2, 0, 2));
+
+        let config = Config::new().enable_ignore_blanks(true).enable_ignore_comments(true).enable_skip_header_comments(true);
+        let stat = Counter::new(config).count(&file).unwrap();
+        assert_eq!((stat.lines, stat.code, stat.comments, stat.blanks, stat.header_lines), (2, 2, 0, 0, 0));
+    }
+
+    #[test]
+    fn test_blanks_in_comments_per_lang() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let rs = dir.path().join("lib.rs");
+        std::fs::write(&rs, "/* a\n\n b */\nfn f() {}\n").unwrap();
+        let c = dir.path().join("lib.c");
+        std::fs::write(&c, "/* a\n\n b */\nint f;\n").unwrap();
+
+        // 只有 Rust 把块注释内的空行计为注释，C 沿用全局设置
+        let counter = Counter::new(Config::new().with_lang_blanks_in_comments_are_blank(LangType::Rust, false));
+        let stat = counter.count(&rs).unwrap();
+        assert_eq!((stat.comments, stat.blanks), (3, 0));
+        let stat = counter.count(&c).unwrap();
+        assert_eq!((stat.comments, stat.blanks), (2, 1));
+
+        // 按语言的设置优先于全局设置
+        let config = Config::new()
+            .with_blanks_in_comments_are_blank(false)
+            .with_lang_blanks_in_comments_are_blank(LangType::C, true);
+        let counter = Counter::new(config);
+        assert_eq!(counter.count(&rs).unwrap().blanks, 0);
+        assert_eq!(counter.count(&c).unwrap().blanks, 1);
+    }
+
+    /// 第一次读取时失败的读取器
+    struct FlakyReader {
+        fail: bool,
+        inner: Cursor<&'static [u8]>,
+    }
+
+    impl Read for FlakyReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.fail {
+                return Err(std::io::Error::new(ErrorKind::TimedOut, "stale NFS handle"));
+            }
+            self.inner.read(buf)
+        }
+    }
+
+    #[test]
+    fn test_read_with_retry() {
+        let content: &'static [u8] = b"fn main() {}\n";
+        let mut attempts = 0;
+        let mut open = || {
+            attempts += 1;
+            Ok(FlakyReader { fail: attempts == 1, inner: Cursor::new(content) })
+        };
+
+        let bytes = read_with_retry(&mut open, 2, Duration::ZERO).unwrap();
+        assert_eq!(bytes, content);
+        assert_eq!(attempts, 2);
+
+        // 不重试时第一次的错误直接返回
+        attempts = 0;
+        let mut open = || {
+            attempts += 1;
+            Ok(FlakyReader { fail: attempts == 1, inner: Cursor::new(content) })
+        };
+        assert_eq!(read_with_retry(&mut open, 0, Duration::ZERO).unwrap_err().kind(), ErrorKind::TimedOut);
+
+        // 文件不存在不重试
+        let mut missing = 0;
+        let err = read_with_retry(|| { missing += 1; File::open("/nonexistent/toukei.rs") }, 3, Duration::ZERO).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert_eq!(missing, 1);
+    }
+
+    #[test]
+    fn test_count_with_io_retries_matches_default() {
+        let counter = Counter::new(Config::new().with_io_retries(2));
+        assert_eq!(counter.count("./src/counter.rs").unwrap(), Counter::new(Config::new()).count("./src/counter.rs").unwrap());
+    }
+
+    #[test]
+    fn test_fortran_files() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let free = dir.path().join("hello.f90");
+        std::fs::write(&free, "\
+! Greets the world
+program hello
+    implicit none
+    integer :: c
+    c = 1  ! counter
+
+    print *, 'Hello, world!'
+end program hello
+").unwrap();
+        let fixed = dir.path().join("sum.f");
+        std::fs::write(&fixed, "\
+C     Sum of two numbers
+*     fixed-form comment
+      PROGRAM SUM
+      CALL ADD(1, 2)
+      END
+").unwrap();
+
+        let counter = Counter::new(Config::new());
+        let stat = counter.count(&free).unwrap();
+        assert_eq!(stat.lang, LangType::Fortran);
+        // 字符串里的 `!` 不是注释
+        assert_eq!((stat.lines, stat.code, stat.comments, stat.blanks), (8, 6, 1, 1));
+
+        let stat = counter.count(&fixed).unwrap();
+        assert_eq!(stat.lang, LangType::Fortran);
+        assert_eq!((stat.lines, stat.code, stat.comments, stat.blanks), (5, 3, 2, 0));
+    }
+
+    #[test]
+    fn test_octave_file() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let file = dir.path().join("stats.m");
+        std::fs::write(&file, "\
+% Compute the mean
+function m = average(x)
+  # Octave-style comment
+  %{
+  Block comment
+  %}
+  m = sum(x) / numel(x);  % divide
+  printf(\"%d\\n\", m);
+end
+").unwrap();
+
+        let stat = Counter::new(Config::new()).count(&file).unwrap();
+        assert_eq!(stat.lang, LangType::Matlab);
+        assert_eq!((stat.lines, stat.code, stat.comments, stat.blanks), (9, 4, 5, 0));
+    }
+
+    #[test]
+    fn test_count_archive() {
+        use std::io::Write;
+        use zip::write::SimpleFileOptions;
+
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        writer.add_directory("src/", options).unwrap();
+        writer.start_file("src/main.rs", options).unwrap();
+        writer.write_all(b"// entry\nfn main() {\n\n    println!(\"hi\");\n}\n").unwrap();
+        writer.start_file("scripts/setup.py", options).unwrap();
+        writer.write_all(b"# build\nprint('ok')\n").unwrap();
+        writer.start_file("assets/logo.rs", options).unwrap();
+        writer.write_all(b"\x89PNG\0\0\0").unwrap();
+        writer.start_file("README.unknown", options).unwrap();
+        writer.write_all(b"notes\n").unwrap();
+        let bytes = writer.finish().unwrap().into_inner();
+
+        let stats = Counter::new(Config::new())
+            .count_archive_reader("bundle.zip", Cursor::new(bytes))
+            .unwrap();
+        assert_eq!(stats.len(), 2);
+
+        assert_eq!(stats[0].path, "bundle.zip!src/main.rs");
+        assert_eq!(stats[0].name, "main.rs");
+        assert_eq!(stats[0].lang, LangType::Rust);
+        assert_eq!((stats[0].lines, stats[0].code, stats[0].comments, stats[0].blanks), (5, 3, 1, 1));
+
+        assert_eq!(stats[1].path, "bundle.zip!scripts/setup.py");
+        assert_eq!(stats[1].lang, LangType::Python);
+        assert_eq!((stats[1].lines, stats[1].code, stats[1].comments), (2, 1, 1));
+    }
+
+    #[test]
+    fn test_read_limited() {
+        assert_eq!(read_limited(&b"abcd"[..], 4).unwrap(), Some(b"abcd".to_vec()));
+        assert_eq!(read_limited(&b"abcde"[..], 4).unwrap(), None);
+        assert_eq!(read_limited(&b""[..], 0).unwrap(), Some(Vec::new()));
+    }
+}
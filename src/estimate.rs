@@ -0,0 +1,142 @@
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+
+/// 基本 COCOMO 模型（organic 项目）的参数
+///
+/// - 工作量（人月）= a × KLOC^b
+/// - 工期（月）= c × 工作量^d
+/// - 成本 = 工作量 × 年薪 / 12 × 管理开销系数
+#[derive(Debug, Clone, Copy)]
+pub struct CocomoModel {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    /// 开发人员年薪
+    pub salary: f64,
+    /// 管理开销系数
+    pub overhead: f64,
+}
+
+impl Default for CocomoModel {
+    fn default() -> Self {
+        CocomoModel {
+            a: 2.4,
+            b: 1.05,
+            c: 2.5,
+            d: 0.38,
+            salary: 56_286.0,
+            overhead: 2.4,
+        }
+    }
+}
+
+impl CocomoModel {
+    /// 替换四个模型系数，薪资相关参数保持不变
+    pub fn with_coefficients(mut self, a: f64, b: f64, c: f64, d: f64) -> Self {
+        self.a = a;
+        self.b = b;
+        self.c = c;
+        self.d = d;
+        self
+    }
+
+    pub fn with_salary(mut self, salary: f64) -> Self {
+        self.salary = salary;
+        self
+    }
+
+    /// 根据代码行数（不含注释与空行）估算
+    pub fn estimate(&self, code_lines: usize) -> CocomoEstimate {
+        let kloc = code_lines as f64 / 1000.0;
+        let effort = self.a * kloc.powf(self.b);
+        let schedule = self.c * effort.powf(self.d);
+        let developers = if schedule > 0.0 { effort / schedule } else { 0.0 };
+        let cost = effort * self.salary / 12.0 * self.overhead;
+
+        CocomoEstimate {
+            effort_months: effort,
+            schedule_months: schedule,
+            developers,
+            cost,
+        }
+    }
+
+    fn bits(&self) -> [u64; 6] {
+        [self.a, self.b, self.c, self.d, self.salary, self.overhead].map(f64::to_bits)
+    }
+}
+
+// 按位比较，使 Config 仍可整体比较与哈希
+impl PartialEq for CocomoModel {
+    fn eq(&self, other: &Self) -> bool {
+        self.bits() == other.bits()
+    }
+}
+
+impl Eq for CocomoModel {}
+
+impl Hash for CocomoModel {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bits().hash(state);
+    }
+}
+
+/// COCOMO 估算结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CocomoEstimate {
+    /// 工作量（人月）
+    pub effort_months: f64,
+    /// 工期（月）
+    pub schedule_months: f64,
+    /// 平均所需人数
+    pub developers: f64,
+    pub cost: f64,
+}
+
+impl Display for CocomoEstimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Estimated effort (person-months): {:.2}", self.effort_months)?;
+        writeln!(f, "Estimated schedule (months):      {:.2}", self.schedule_months)?;
+        writeln!(f, "Estimated developers:             {:.2}", self.developers)?;
+        write!(f, "Estimated cost:                   {:.0}", self.cost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-6, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn test_estimate_formula() {
+        let estimate = CocomoModel::default().estimate(10_000);
+
+        assert_close(estimate.effort_months, 2.4 * 10f64.powf(1.05));
+        assert_close(estimate.schedule_months, 2.5 * estimate.effort_months.powf(0.38));
+        assert!((estimate.effort_months - 26.93).abs() < 0.01);
+        assert!((estimate.schedule_months - 8.74).abs() < 0.01);
+        assert!((estimate.developers - 3.08).abs() < 0.01);
+        assert!((estimate.cost - 303_138.87).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_custom_coefficients() {
+        let model = CocomoModel::default().with_coefficients(3.0, 1.12, 2.5, 0.35);
+        let estimate = model.estimate(2_000);
+
+        assert_close(estimate.effort_months, 3.0 * 2f64.powf(1.12));
+        assert_ne!(model, CocomoModel::default());
+    }
+
+    #[test]
+    fn test_empty_estimate() {
+        let estimate = CocomoModel::default().estimate(0);
+        assert_eq!(estimate.effort_months, 0.0);
+        assert_eq!(estimate.developers, 0.0);
+        assert_eq!(estimate.cost, 0.0);
+    }
+}
@@ -1,22 +1,28 @@
 use crate::config::Config;
-use crate::report::Report;
+use crate::report::{Discrepancy, MultiReport, Report, SkipReason, SkipWarning};
 use crate::stats::FileStat;
 use crate::counter::Counter;
-use crate::walker::FileReader;
-use crate::counter::CounterError;
+use crate::walker::{is_glob, FileReader};
+use crate::utils::watchdog::Watchdog;
 
 use log::warn;
 use rayon::prelude::*;
 use rayon::ThreadPoolBuilder;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::{mpsc, Semaphore};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::runtime::{Handle, Runtime, RuntimeFlavor};
+use tokio::sync::mpsc;
+use tokio::task::{AbortHandle, JoinHandle};
 use futures::future::join_all;
 
 #[derive(Debug)]
 pub struct FileCounter {
     config: Config,
     reader: FileReader,
+    cancel: Option<Arc<AtomicBool>>,
+    partial_on_cancel: bool,
 }
 
 impl FileCounter {
@@ -25,20 +31,98 @@ impl FileCounter {
         FileCounter {
             config,
             reader,
+            cancel: None,
+            partial_on_cancel: false,
         }
     }
+
+    /// 设置取消标志，其他线程把它置为 true 后扫描会尽快返回
+    pub fn with_cancel(mut self, cancel: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// 取消时返回已统计的部分报告，而不是 `ProcessError::Cancelled`
+    pub fn with_partial_on_cancel(mut self, partial: bool) -> Self {
+        self.partial_on_cancel = partial;
+        self
+    }
+
+    /// 替换配置，之后的 `process` 使用新配置；取消标志等设置保持不变
+    pub fn set_config(&mut self, config: Config) {
+        self.reader = FileReader::new(config.clone());
+        self.config = config;
+    }
+}
+
+/// 汇报进度的间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 扫描中已发现与已完成的文件数，由计数线程累加、汇报线程读取
+#[derive(Default)]
+struct Progress {
+    found: AtomicUsize,
+    done: AtomicUsize,
+    finished: AtomicBool,
 }
 
 impl FileCounter {
-    pub fn process(&self) -> Result<Report, String> {
+    pub fn process(&self) -> Result<Report, ProcessError> {
+        self.scan(None)
+    }
+
+    /// 与 `process` 相同，统计过程中定期调用 `progress(已完成, 已发现)`，结束时再以最终数目调用一次
+    ///
+    /// 文件边遍历边统计，`已发现` 的文件数会随遍历增长，遍历结束后即为总数；
+    /// 回调只在单独的汇报线程中调用，计数线程只更新原子计数，不会因回调而互相等待。
+    pub fn process_with_progress<F>(&self, mut progress: F) -> Result<Report, ProcessError>
+    where
+        F: FnMut(usize, usize) + Send,
+    {
+        let state = Progress::default();
+        std::thread::scope(|scope| {
+            let reporter = scope.spawn(|| {
+                let mut last = (0, 0);
+                loop {
+                    let finished = state.finished.load(Ordering::Acquire);
+                    let done = state.done.load(Ordering::Relaxed);
+                    let found = state.found.load(Ordering::Relaxed).max(done);
+                    if done > 0 && (done, found) != last {
+                        progress(done, found);
+                        last = (done, found);
+                    }
+                    if finished {
+                        break;
+                    }
+                    std::thread::park_timeout(PROGRESS_INTERVAL);
+                }
+            });
+
+            let result = self.scan(Some(&state));
+            state.finished.store(true, Ordering::Release);
+            reporter.thread().unpark();
+            result
+        })
+    }
+
+    /// 边遍历边并行计数；传入 `progress` 时累加已发现与已完成的文件数
+    fn scan(&self, progress: Option<&Progress>) -> Result<Report, ProcessError> {
+        // 设置了超时则由看门狗线程在到期后置位，遍历和计数都会检查取消与超时标志
+        let cancel = self.cancel.clone().unwrap_or_default();
+        let timed_out = Arc::new(AtomicBool::new(false));
+        let _watchdog = self.config.timeout
+            .map(|timeout| Watchdog::start(timeout, Arc::clone(&timed_out)));
+        let stopped = || cancel.load(Ordering::Relaxed) || timed_out.load(Ordering::Relaxed);
+
+        check_paths(&self.config.paths)?;
+
         let mut report = Report::new();
-        // 先收集所有文件（单线程）
-        let mut all_files = Vec::new();
-        for path in self.config.paths.iter() {
-            let files = self.reader.walk_dir(path)
-                .map_err(|e| format!("Failed to walk directory: {}", e))?;
-            all_files.extend(files);
-        }
+        // 各路径的遍历器先全部建好，无效的 glob 模式在开始计数前就报错
+        let walks = self.config.paths
+            .iter()
+            .map(|path| self.reader.walk_iter_until(path, stopped))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to walk directory: {}", e))?;
 
         // 创建线程池
         let num_threads = if self.config.num_workers > 0 {
@@ -51,40 +135,172 @@ impl FileCounter {
             .build()
             .map_err(|e| format!("Failed to build thread pool: {}", e))?;
 
-        // 克隆 config 以供并行任务使用
-        let config = self.config.clone();
+        // 所有任务共用一个 Counter，`.editorconfig` 的缓存在任务间共享
+        let counter = Counter::new(self.config.clone());
+        let config = &self.config;
 
-        // 并行计数，收集每个文件的结果（跳过二进制文件，其他错误立刻返回）
-        let results: Vec<Result<Option<FileStat>, String>> = thread_pool.install(|| {
-            all_files
-                .par_iter()
+        // 边遍历边并行计数，不预先收集文件列表（跳过二进制文件，其他错误立刻返回）
+        let results: Vec<Result<Outcome, String>> = thread_pool.install(|| {
+            walks
+                .into_iter()
+                .flatten()
+                .inspect(|_| {
+                    if let Some(progress) = progress {
+                        progress.found.fetch_add(1, Ordering::Relaxed);
+                    }
+                })
+                .par_bridge()
                 .map(|file_path| {
-                    // 每个任务创建自己的 Counter
-                    let counter = Counter::new(config.clone());
-                    match counter.count(file_path) {
-                        Ok(stat) => Ok(Some(stat)),
-                        Err(CounterError::BinaryFile) => {
-                            warn!("Skipping binary file: {}", file_path.display());
-                            Ok(None)
-                        }
-                        Err(e) => Err(format!("Failed to count file {:?}: {}", file_path, e)),
+                    if stopped() {
+                        return Ok(Outcome::Filtered);
+                    }
+                    let outcome = match counter.count_entries(&file_path) {
+                        Ok(stats) => Ok(Outcome::Counted(
+                            stats.into_iter().filter(|stat| stat.code >= config.min_code_lines).collect()
+                        )),
+                        Err(e) => match SkipReason::from_error(&e) {
+                            SkipReason::Other(_) => Err(format!("Failed to count file {:?}: {}", file_path, e)),
+                            reason => Ok(Outcome::Skipped(skip_warning(&file_path, reason))),
+                        },
+                    };
+                    if let Some(progress) = progress {
+                        progress.done.fetch_add(1, Ordering::Relaxed);
                     }
+                    outcome
                 })
                 .collect()
         });
 
+        if let Some(timeout) = self.config.timeout
+            && timed_out.load(Ordering::SeqCst) {
+            return Err(ProcessError::TimedOut(timeout));
+        }
+        if cancel.load(Ordering::SeqCst) && !self.partial_on_cancel {
+            return Err(ProcessError::Cancelled);
+        }
+
         for res in results {
             match res {
-                Ok(Some(stat)) => report.add(stat),
-                Ok(None) => (), // 二进制文件已跳过
-                Err(e) => return Err(e),
+                Ok(Outcome::Counted(stats)) => stats.into_iter().for_each(|stat| report.add(stat)),
+                Ok(Outcome::Skipped(warning)) => report.add_warning(warning),
+                Ok(Outcome::Filtered) => (), // 扫描已取消时未统计的文件不计入
+                Err(e) => return Err(ProcessError::Failed(e)),
             }
         }
 
+        if self.config.split_tests {
+            report.split_tests(&self.config.test_patterns, &self.config.paths)?;
+        }
+
         Ok(report)
     }
 }
 
+impl FileCounter {
+    /// 返回汇总报告以及所有文件的统计，文件按路径排序
+    pub fn process_detailed(&self) -> Result<(Report, Vec<FileStat>), ProcessError> {
+        let report = self.process()?;
+        let mut files: Vec<FileStat> = report.inner
+            .values()
+            .flat_map(|lang_stat| lang_stat.stats.iter().cloned())
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok((report, files))
+    }
+
+    /// 对每个配置的路径分别扫描，结果按路径分开；超时对每个路径单独计算
+    pub fn process_per_path(&self) -> Result<MultiReport, ProcessError> {
+        let mut multi = MultiReport::new();
+        for path in &self.config.paths {
+            let mut counter = FileCounter::new(self.config.clone().with_paths(vec![path.clone()]))
+                .with_partial_on_cancel(self.partial_on_cancel);
+            counter.cancel = self.cancel.clone();
+            multi.push(path.clone(), counter.process()?);
+        }
+        Ok(multi)
+    }
+}
+
+/// 用同步和异步两种统计器扫描同一配置，返回两份报告的差异（左为同步，右为异步）
+pub fn verify_parity(config: &Config) -> Result<Vec<Discrepancy>, ProcessError> {
+    let sync_report = FileCounter::new(config.clone()).process()?;
+    let async_report = AsyncFileCounter::new(config.clone()).process_blocking()?;
+    Ok(sync_report.diff(&async_report))
+}
+
+/// 单个文件的统计结果
+enum Outcome {
+    /// 代码行数足够的统计结果，zip 压缩包对应其中的多个条目
+    Counted(Vec<FileStat>),
+    /// 跳过的文件，记入报告的 `warnings`
+    Skipped(SkipWarning),
+    /// 扫描已取消，未统计的文件不计入报告
+    Filtered,
+}
+
+/// 记录跳过文件的日志并返回对应的警告
+fn skip_warning(path: &std::path::Path, reason: SkipReason) -> SkipWarning {
+    match &reason {
+        SkipReason::Binary => warn!("Skipping binary file: {}", path.display()),
+        SkipReason::DecodeError => warn!("Skipping file with invalid UTF-8: {}", path.display()),
+        SkipReason::Other(msg) => warn!("Skipping file {}: {}", path.display(), msg),
+    }
+    SkipWarning { path: path.display().to_string(), reason }
+}
+
+/// 扫描前检查每个路径是否存在且可读，避免拼错的路径静默地统计出 0 个文件；glob 模式不检查
+fn check_paths(paths: &[String]) -> Result<(), ProcessError> {
+    for path in paths.iter().filter(|p| !is_glob(p)) {
+        let metadata = std::fs::metadata(path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => ProcessError::PathNotFound(path.clone()),
+            _ => ProcessError::Failed(format!("Cannot read path {}: {}", path, e)),
+        })?;
+        if metadata.is_dir() {
+            std::fs::read_dir(path)
+                .map_err(|e| ProcessError::Failed(format!("Cannot read path {}: {}", path, e)))?;
+        }
+    }
+    Ok(())
+}
+
+/// 扫描失败的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessError {
+    /// 调用方通过取消标志中止了扫描
+    Cancelled,
+    /// 超过了配置的超时时间
+    TimedOut(Duration),
+    /// 配置的路径不存在
+    PathNotFound(String),
+    /// 遍历或统计出错
+    Failed(String),
+}
+
+impl std::fmt::Display for ProcessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProcessError::Cancelled => write!(f, "Scan cancelled"),
+            ProcessError::TimedOut(timeout) => write!(f, "Scan timed out after {:?}", timeout),
+            ProcessError::PathNotFound(path) => write!(f, "Path not found: {}", path),
+            ProcessError::Failed(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ProcessError {}
+
+impl From<String> for ProcessError {
+    fn from(msg: String) -> Self {
+        ProcessError::Failed(msg)
+    }
+}
+
+impl From<ProcessError> for String {
+    fn from(err: ProcessError) -> Self {
+        err.to_string()
+    }
+}
+
 /// 异步版本的文件统计器
 #[derive(Debug)]
 pub struct AsyncFileCounter {
@@ -92,6 +308,8 @@ pub struct AsyncFileCounter {
     counter: Arc<Counter>,
     reader: Arc<FileReader>,
     num_workers: usize,
+    cancel: Option<Arc<AtomicBool>>,
+    partial_on_cancel: bool,
 }
 
 impl AsyncFileCounter {
@@ -112,82 +330,169 @@ impl AsyncFileCounter {
             counter,
             reader,
             num_workers,
+            cancel: None,
+            partial_on_cancel: false,
         }
     }
 
+    /// 替换配置，之后的 `process` 使用新配置；工作线程数按新配置重新确定，取消标志等设置保持不变
+    pub fn set_config(&mut self, config: Config) {
+        let Self { cancel, partial_on_cancel, .. } = std::mem::replace(self, Self::new(config));
+        self.cancel = cancel;
+        self.partial_on_cancel = partial_on_cancel;
+    }
+
     /// 设置工作线程数
     pub fn with_workers(mut self, num_workers: usize) -> Self {
         self.num_workers = num_workers;
         self
     }
 
-    /// 异步处理文件
-    pub async fn process(&self) -> Result<Report, String> {
+    /// 设置取消标志，置为 true 后不再派发新的文件，已派发的任务结束后返回
+    pub fn with_cancel(mut self, cancel: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// 取消时返回已统计的部分报告，而不是 `ProcessError::Cancelled`
+    pub fn with_partial_on_cancel(mut self, partial: bool) -> Self {
+        self.partial_on_cancel = partial;
+        self
+    }
+
+    /// 异步处理文件，设置了超时则整体包在 `tokio::time::timeout` 中
+    ///
+    /// 超时后置位停止标志并中止所有生产者与工作任务，已在阻塞线程中统计的文件完成后不再取新文件。
+    pub async fn process(&self) -> Result<Report, ProcessError> {
+        let timed_out = Arc::new(AtomicBool::new(false));
+        match self.config.timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, self.scan(Arc::clone(&timed_out))).await {
+                Ok(result) => result,
+                Err(_) => {
+                    // `scan` 被丢弃时其中的任务随 `AbortOnDrop` 中止，标志让阻塞线程中的遍历也尽快返回
+                    timed_out.store(true, Ordering::SeqCst);
+                    Err(ProcessError::TimedOut(timeout))
+                }
+            },
+            None => self.scan(timed_out).await,
+        }
+    }
+
+    /// 在同步代码中运行异步统计
+    ///
+    /// 已处于多线程 tokio 运行时内时通过 `block_in_place` 复用当前运行时；
+    /// 处于单线程运行时内时在独立线程上新建运行时，避免嵌套运行时导致 panic；
+    /// 不在运行时内则新建一个运行时。
+    pub fn process_blocking(&self) -> Result<Report, ProcessError> {
+        match Handle::try_current() {
+            Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                tokio::task::block_in_place(|| handle.block_on(self.process()))
+            }
+            Ok(_) => std::thread::scope(|scope| {
+                scope.spawn(|| Self::new_runtime()?.block_on(self.process()))
+                    .join()
+                    .map_err(|_| ProcessError::Failed("Async worker thread panicked".to_string()))?
+            }),
+            Err(_) => Self::new_runtime()?.block_on(self.process()),
+        }
+    }
+
+    /// 在调用方提供的运行时上运行异步统计
+    ///
+    /// 若当前线程正处于该运行时内，运行时必须是多线程的。
+    pub fn process_on(&self, handle: &Handle) -> Result<Report, ProcessError> {
+        if Handle::try_current().is_ok() {
+            tokio::task::block_in_place(|| handle.block_on(self.process()))
+        } else {
+            handle.block_on(self.process())
+        }
+    }
+
+    fn new_runtime() -> Result<Runtime, ProcessError> {
+        Runtime::new().map_err(|e| ProcessError::Failed(format!("Failed to create async runtime: {}", e)))
+    }
+
+    /// `timed_out` 由 `process` 在超时后置位，与取消标志一样使遍历和工作任务停下
+    async fn scan(&self, timed_out: Arc<AtomicBool>) -> Result<Report, ProcessError> {
+        check_paths(&self.config.paths)?;
+
+        let cancel = self.cancel.clone().unwrap_or_default();
+        let stop = StopFlags { cancel: Arc::clone(&cancel), timed_out };
         let (tx, rx) = mpsc::channel::<PathBuf>(self.num_workers * 2); // Buffer size = 2x workers
-        let report = Arc::new(tokio::sync::Mutex::new(Report::new()));
+        let mut tasks = AbortOnDrop::default();
 
         // 生产者任务
         let mut producer_handles = vec![];
-        for path in self.config.paths.iter().cloned() {
+        for path in self.config.paths.clone() {
             let tx_clone = tx.clone();
             let reader_clone = Arc::clone(&self.reader);
+            let stop = stop.clone();
 
             let handle = tokio::spawn(async move {
-                if let Err(e) = Self::produce_files(&path, reader_clone, tx_clone).await {
+                if let Err(e) = Self::produce_files(&path, reader_clone, tx_clone, stop).await {
                     log::error!("Producer error for path {}: {}", path, e);
                 }
             });
+            tasks.push(&handle);
             producer_handles.push(handle);
         }
 
         // 丢弃原始发送者以关闭通道
         drop(tx);
 
-        // 消费者任务
-        let counter_clone = Arc::clone(&self.counter);
-        let report_clone = Arc::clone(&report);
-        let num_workers = self.num_workers;
-
-        let consumer_handle = tokio::spawn(async move {
-            let mut stream = rx;
-            let semaphore = Arc::new(Semaphore::new(num_workers));
-            let mut handles = vec![];
-
-            while let Some(file_path) = stream.recv().await {
-                let counter = Arc::clone(&counter_clone);
-                let report = Arc::clone(&report_clone);
-                let permit = Arc::clone(&semaphore);
-
-                let handle = tokio::spawn(async move {
-                    let _permit = permit.acquire().await.unwrap();
-                    match counter.count_async(&file_path).await {
-                        Ok(stat) => {
-                            let mut report_guard = report.lock().await;
-                            report_guard.add(stat);
-                        }
-                        Err(CounterError::BinaryFile) => {
-                            warn!("Skipping binary file: {}", file_path.display());
+        // 消费者：固定数量的工作任务从同一通道取文件，各自累计到自己的部分报告，
+        // 全部结束后再合并，计数结果不再逐个文件争用同一把锁
+        let rx = Arc::new(tokio::sync::Mutex::new(rx));
+        let min_code_lines = self.config.min_code_lines;
+        let workers: Vec<_> = (0..self.num_workers.max(1))
+            .map(|_| {
+                let rx = Arc::clone(&rx);
+                let counter = Arc::clone(&self.counter);
+                let stop = stop.clone();
+
+                tokio::spawn(async move {
+                    let mut partial = Report::new();
+                    loop {
+                        // 只在取下一个路径时持锁，锁在本语句结束时释放
+                        let next = rx.lock().await.recv().await;
+                        // 取消后不再取新文件，通道随之关闭，生产者也会停下
+                        let Some(file_path) = next else {
+                            break;
+                        };
+                        if stop.is_set() {
+                            break;
                         }
-                        Err(e) => {
-                            log::error!("Failed to count file {:?}: {}", file_path, e);
+                        match counter.count_entries_async(&file_path).await {
+                            Ok(stats) => stats.into_iter()
+                                .filter(|stat| stat.code >= min_code_lines)
+                                .for_each(|stat| partial.add(stat)),
+                            // 异步扫描不中断，所有无法统计的文件都记为警告
+                            Err(e) => partial.add_warning(skip_warning(&file_path, SkipReason::from_error(&e))),
                         }
                     }
-                });
-                handles.push(handle);
-            }
-
-            // 等待所有任务完成
-            join_all(handles).await;
-        });
+                    partial
+                })
+            })
+            .inspect(|handle| tasks.push(handle))
+            .collect();
+        drop(rx);
 
         // 等待所有生产者完成
         join_all(producer_handles).await;
-        consumer_handle.await.map_err(|e| format!("Consumer task failed: {}", e))?;
 
-        // 获取最终报告
-        let final_report = Arc::try_unwrap(report)
-            .map_err(|_| "Failed to unwrap Arc: still multiple references".to_string())?
-            .into_inner();
+        // 合并各工作任务的部分报告
+        let mut final_report = Report::new();
+        for partial in join_all(workers).await {
+            final_report.merge(partial.map_err(|e| format!("Worker task failed: {}", e))?);
+        }
+
+        if cancel.load(Ordering::SeqCst) && !self.partial_on_cancel {
+            return Err(ProcessError::Cancelled);
+        }
+
+        if self.config.split_tests {
+            final_report.split_tests(&self.config.test_patterns, &self.config.paths)?;
+        }
 
         Ok(final_report)
     }
@@ -197,11 +502,12 @@ impl AsyncFileCounter {
         path: &str,
         reader: Arc<FileReader>,
         tx: mpsc::Sender<PathBuf>,
+        stop: StopFlags,
     ) -> Result<(), String> {
         // 保持walker为同步，使用tokio的spawn_blocking
         let path_owned = path.to_owned();
         let files = tokio::task::spawn_blocking(move || {
-            reader.walk_dir(&path_owned)
+            reader.walk_dir_until(&path_owned, || stop.is_set())
         }).await
         .map_err(|e| format!("Failed to join blocking task: {}", e))?
         .map_err(|e| format!("Failed to walk directory: {}", e))?;
@@ -216,4 +522,35 @@ impl AsyncFileCounter {
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// 异步扫描的停止条件：调用方取消或超时
+#[derive(Clone)]
+struct StopFlags {
+    cancel: Arc<AtomicBool>,
+    timed_out: Arc<AtomicBool>,
+}
+
+impl StopFlags {
+    fn is_set(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed) || self.timed_out.load(Ordering::Relaxed)
+    }
+}
+
+/// 被丢弃时中止登记的任务；`scan` 因超时被丢弃时不会留下继续遍历和统计的任务
+#[derive(Default)]
+struct AbortOnDrop(Vec<AbortHandle>);
+
+impl AbortOnDrop {
+    fn push<T>(&mut self, handle: &JoinHandle<T>) {
+        self.0.push(handle.abort_handle());
+    }
+}
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        for handle in &self.0 {
+            handle.abort();
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use git2::{ObjectType, Repository, Sort, TreeWalkMode, TreeWalkResult};
+use log::warn;
+
+use crate::config::Config;
+use crate::counter::Counter;
+use crate::langs::lang_type::LangType;
+use crate::report::SkipReason;
+use crate::walker::FileReader;
+
+/// 某个提交中某种语言的代码行数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryPoint {
+    /// 提交的短哈希
+    pub commit: String,
+    /// 提交日期（UTC，YYYY-MM-DD）
+    pub date: String,
+    pub language: LangType,
+    /// 代码行数（不含注释和空行）
+    pub lines: usize,
+}
+
+/// 统计最近 `n` 个提交（沿第一父提交回溯）中各语言的代码行数，按时间从旧到新排列
+///
+/// 直接读取提交树中的 blob，不会改动工作区；路径过滤、语言识别与类型过滤都与目录扫描一致。
+/// 每个路径只统计其在仓库中对应的子树，多个路径须属于同一仓库，结果合并为一个序列。
+pub fn collect_history<P: AsRef<Path>>(paths: &[P], n: usize, config: &Config) -> Result<Vec<HistoryPoint>, String> {
+    let Some(first) = paths.first() else {
+        return Ok(Vec::new());
+    };
+    let repo = Repository::discover(first.as_ref()).map_err(git_error)?;
+    let workdir = repo.workdir()
+        .ok_or_else(|| "git error: bare repositories are not supported".to_string())?
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve repository root: {}", e))?;
+
+    let mut subtrees = Vec::new();
+    for path in paths {
+        subtrees.push(relative_to_workdir(path.as_ref(), &workdir)?);
+    }
+    // 被其他路径包含的子树不再重复统计
+    subtrees.sort();
+    subtrees.dedup_by(|later, earlier| later.starts_with(&*earlier));
+
+    let reader = FileReader::new(config.clone());
+    let counter = Counter::new(config.clone());
+
+    let mut walk = repo.revwalk().map_err(git_error)?;
+    walk.set_sorting(Sort::TIME).map_err(git_error)?;
+    walk.simplify_first_parent().map_err(git_error)?;
+    walk.push_head().map_err(git_error)?;
+
+    let mut commits = Vec::new();
+    for oid in walk.take(n) {
+        commits.push(repo.find_commit(oid.map_err(git_error)?).map_err(git_error)?);
+    }
+    commits.reverse();
+
+    let mut points = Vec::new();
+    for commit in commits {
+        let tree = commit.tree().map_err(git_error)?;
+        let mut totals: BTreeMap<String, (LangType, usize)> = BTreeMap::new();
+        // 与目录扫描一致：按内容确定语言后再按类型过滤，二进制与非法编码的内容跳过，其他错误中止
+        let mut add = |path: &Path, blob: &git2::Blob| -> Result<(), String> {
+            if !reader.include_path(path) {
+                return Ok(());
+            }
+            match counter.count_reader(path, blob.content()) {
+                Ok(stat) if config.wants(stat.lang) => {
+                    let entry = totals.entry(stat.lang.to_string()).or_insert((stat.lang, 0));
+                    entry.1 += stat.code;
+                }
+                Ok(_) => (),
+                Err(e) => match SkipReason::from_error(&e) {
+                    SkipReason::Other(_) => return Err(format!("Failed to count {} in {}: {}", path.display(), commit.id(), e)),
+                    _ => warn!("Skipping {} in {}: {}", path.display(), commit.id(), e),
+                },
+            }
+            Ok(())
+        };
+
+        for rel in &subtrees {
+            let subtree = if rel.as_os_str().is_empty() {
+                tree.clone()
+            } else {
+                // 该提交中还不存在的路径不计入
+                let Ok(entry) = tree.get_path(rel) else {
+                    continue;
+                };
+                let object = entry.to_object(&repo).map_err(git_error)?;
+                match object.kind() {
+                    Some(ObjectType::Tree) => object.peel_to_tree().map_err(git_error)?,
+                    Some(ObjectType::Blob) => {
+                        add(rel, &object.peel_to_blob().map_err(git_error)?)?;
+                        continue;
+                    }
+                    _ => continue,
+                }
+            };
+
+            let mut failure = None;
+            subtree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+                if entry.kind() != Some(ObjectType::Blob) {
+                    return TreeWalkResult::Ok;
+                }
+                let Some(name) = entry.name() else {
+                    return TreeWalkResult::Ok;
+                };
+                let counted = entry.to_object(&repo)
+                    .and_then(|obj| obj.peel_to_blob())
+                    .map_err(git_error)
+                    .and_then(|blob| add(&rel.join(dir).join(name), &blob));
+                match counted {
+                    Ok(()) => TreeWalkResult::Ok,
+                    Err(e) => {
+                        failure = Some(e);
+                        TreeWalkResult::Abort
+                    }
+                }
+            }).map_err(git_error)?;
+
+            if let Some(e) = failure {
+                return Err(e);
+            }
+        }
+
+        let commit_id = commit.id().to_string();
+        let date = format_date(commit.time().seconds());
+        for (language, lines) in totals.into_values() {
+            points.push(HistoryPoint {
+                commit: commit_id[..7.min(commit_id.len())].to_string(),
+                date: date.clone(),
+                language,
+                lines,
+            });
+        }
+    }
+
+    Ok(points)
+}
+
+/// 把扫描路径转换为相对仓库根目录的路径，仓库根目录本身对应空路径
+fn relative_to_workdir(path: &Path, workdir: &Path) -> Result<PathBuf, String> {
+    let full = path.canonicalize()
+        .map_err(|e| format!("Failed to resolve {}: {}", path.display(), e))?;
+    full.strip_prefix(workdir)
+        .map(Path::to_path_buf)
+        .map_err(|_| format!("{} is not inside repository {}", path.display(), workdir.display()))
+}
+
+/// 以 `commit,date,language,lines` 的 CSV 形式输出
+pub fn write_history_csv<W: Write>(points: &[HistoryPoint], writer: &mut W) -> std::io::Result<()> {
+    writeln!(writer, "commit,date,language,lines")?;
+    for point in points {
+        writeln!(writer, "{},{},{},{}", point.commit, point.date, point.language, point.lines)?;
+    }
+    Ok(())
+}
+
+fn git_error(e: git2::Error) -> String {
+    format!("git error: {}", e.message())
+}
+
+/// 把 Unix 时间戳转换为 UTC 日期
+fn format_date(secs: i64) -> String {
+    // 参考 Howard Hinnant 的 civil_from_days 算法
+    let days = secs.div_euclid(86_400);
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_700_000_000), "2023-11-14");
+    }
+
+    #[test]
+    fn test_write_history_csv() {
+        let points = vec![HistoryPoint {
+            commit: "abc1234".to_string(),
+            date: "2024-01-02".to_string(),
+            language: LangType::Rust,
+            lines: 42,
+        }];
+        let mut out = Vec::new();
+        write_history_csv(&points, &mut out).unwrap();
+
+        assert_eq!(String::from_utf8(out).unwrap(), "commit,date,language,lines\nabc1234,2024-01-02,Rust,42\n");
+    }
+}
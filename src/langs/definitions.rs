@@ -1,537 +1,956 @@
 use crate::langs::lang_def::LangDef;
 
+/// C 系语言的分支关键字与运算符，用于近似圈复杂度
+const C_BRANCHES: &[&str] = &["if", "for", "while", "case", "catch", "&&", "||", "?"];
+
 pub static ASCIIDOC: LangDef = LangDef {
     name: "AsciiDoc",
     extensions: &["adoc", "asciidoc", "asc"],
-    line_comment: None,
+    line_comments: &[],
     block_comment: None,
+    nested_block_comment: false,
+    quotes: &[],
+    multiline_strings: &[],
     doc_comment: None,
+    special_comments: &[],
     function_patterns: &[],
     class_patterns: &[],
+    branch_keywords: &[],
 };
 
 pub static ASTRO: LangDef = LangDef {
     name: "Astro",
     extensions: &["astro"],
-    line_comment: Some("//"),
+    line_comments: &["//"],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"', '\'', '`'],
+    multiline_strings: &["`"],
+    doc_comment: None,
+    special_comments: &[],
+    function_patterns: &[
+        r"function\s+\w+",
+        r"(?:const|let)\s+\w+\s*=\s*(?:async\s+)?function\b",
+        r"(?:const|let)\s+\w+\s*=\s*(?:async\s*)?\([^)]*\)\s*=>"],
+    class_patterns: &[],
+    branch_keywords: C_BRANCHES,
+};
+
+pub static AVRO: LangDef = LangDef {
+    name: "Avro",
+    extensions: &["avdl"],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    // 反引号用于转义与关键字同名的标识符
+    quotes: &['"', '`'],
+    multiline_strings: &[],
+    doc_comment: Some("/**"),
+    special_comments: &[],
+    // 协议中的消息声明没有关键字（`string hello(string greeting);`），按“返回类型 名字(”识别
+    function_patterns: &[r"^\s*[\w.<>]+\s+\w+\s*\("],
+    class_patterns: &[r"^\s*protocol\s+\w+", r"^\s*record\s+\w+", r"^\s*error\s+\w+", r"^\s*enum\s+\w+"],
+    branch_keywords: &[],
+};
+
+pub static BATCH: LangDef = LangDef {
+    name: "Batch",
+    extensions: &["bat", "cmd"],
+    // `::` 注释由 BatchClassifier 处理
+    line_comments: &["REM"],
+    block_comment: None,
+    nested_block_comment: false,
+    quotes: &['"'],
+    multiline_strings: &[],
     doc_comment: None,
-    function_patterns: &["function", "const", "let", "async function"],
+    special_comments: &[],
+    function_patterns: &[r"^:\w+"],
     class_patterns: &[],
+    branch_keywords: &["if", "for"],
 };
 
 pub static C: LangDef = LangDef {
     name: "C",
     extensions: &["c", "h"],
-    line_comment: Some("//"),
+    line_comments: &["//"],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &[],
     doc_comment: Some("/**"),
+    special_comments: &[],
     function_patterns: &[
         r"\w+\s+\w+\s*\([^)]*\)\s*\{", 
         r"\w+\s+\*\w+\s*\([^)]*\)\s*\{"],
     class_patterns: &[r"typedef\s+struct\s+\w+"],
+    branch_keywords: C_BRANCHES,
 };
 
 pub static CLOJURE: LangDef = LangDef {
     name: "Clojure",
     extensions: &["clj", "cljs", "cljc", "edn"],
-    line_comment: Some(";;"),
+    line_comments: &[";;"],
     block_comment: None,
+    nested_block_comment: false,
+    quotes: &['"'],
+    multiline_strings: &[],
     doc_comment: None,
+    special_comments: &[],
     function_patterns: &["\\(defn\\s+", "\\(def\\s+", "\\(defmacro\\s+"],
     class_patterns: &["\\(defrecord\\s+"],
+    branch_keywords: &["if", "when", "cond", "and", "or"],
 };
 
 pub static CPP: LangDef = LangDef {
     name: "C++",
     extensions: &["cpp", "cxx", "cc", "c++", "hpp", "hxx", "hh", "h++"],
-    line_comment: Some("//"),
+    line_comments: &["//"],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &[],
     doc_comment: Some("/**"),
+    special_comments: &[],
     function_patterns: &[
         r"\w+\s+\w+\s*\([^)]*\)\s*\{",
         r"\w+\s+\*\w+\s*\([^)]*\)\s*\{", 
         r"\w+\s+&\w+\s*\([^)]*\)\s*\{"],
     class_patterns: &[r"class\s+\w+"],
+    branch_keywords: C_BRANCHES,
 };
 
 pub static CSHARP: LangDef = LangDef {
     name: "C#",
     extensions: &["cs"],
-    line_comment: Some("//"),
+    line_comments: &["//"],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &["\"\"\""],
     doc_comment: Some("///"),
+    special_comments: &[],
     function_patterns: &[
         r"\w+\s+\w+\s*\([^)]*\)\s*\{", 
-        r"public\s+\w+\s+\w+\s*\([^)]*\)\s*\{"],
+        r"public\s+\w+\s+\w+\s*\([^)]*\)\s*\{",
+        // `{` 另起一行的写法，名字不能是 `if`，以免把 `else if (...)` 当成定义
+        r"^(?:\w+\s+)+(?:[A-Za-hj-z_]\w*|i(?:[A-Za-eg-z0-9_]\w*)?|if\w+)\s*\([^)]*\)\s*$"],
     class_patterns: &[r"class\s+\w+"],
+    branch_keywords: C_BRANCHES,
 };
 
 pub static CSS: LangDef = LangDef {
     name: "CSS",
     extensions: &["css"],
-    line_comment: None,
+    line_comments: &[],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &[],
     doc_comment: None,
-    function_patterns: &["@\\w+\\s+", "\\w+\\s*\\{"],
+    special_comments: &[],
+    // 选择器与 `@media` 等规则都不是函数
+    function_patterns: &[],
     class_patterns: &["\\.\\w+"],
+    branch_keywords: &[],
 };
 
 pub static D: LangDef = LangDef {
     name: "D",
     extensions: &["d", "di"],
-    line_comment: Some("//"),
+    line_comments: &["//"],
     block_comment: Some(("/*", "*/")),
+    // D 中只有 `/+ +/` 可以嵌套，`/* */` 不嵌套
+    nested_block_comment: false,
+    quotes: &['"', '\'', '`'],
+    multiline_strings: &[],
     doc_comment: Some("/**"),
+    special_comments: &[],
     function_patterns: &[
         r"\w+\s+\w+\s*\([^)]*\)\s*\{", 
-        r"\w+\s+\*\w+\s*\([^)]*\)\s*\{"],
+        r"\w+\s+\*\w+\s*\([^)]*\)\s*\{",
+        // `{` 另起一行的写法，名字不能是 `if`，以免把 `else if (...)` 当成定义
+        r"^(?:\w+\s+)+\*?(?:[A-Za-hj-z_]\w*|i(?:[A-Za-eg-z0-9_]\w*)?|if\w+)\s*\([^)]*\)\s*$"],
     class_patterns: &["class\\s+\\w+"],
+    branch_keywords: C_BRANCHES,
 };
 
 pub static DART: LangDef = LangDef {
     name: "Dart",
     extensions: &["dart"],
-    line_comment: Some("//"),
+    line_comments: &["//"],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: true,
+    quotes: &['"', '\''],
+    multiline_strings: &["\"\"\"", "'''"],
     doc_comment: Some("///"),
+    special_comments: &[],
     function_patterns: &[
         r"\w+\s+\w+\s*\([^)]*\)\s*\{", 
         r"\w+\s+\w+\s*\([^)]*\)\s*async"],
     class_patterns: &["class\\s+\\w+"],
+    branch_keywords: C_BRANCHES,
 };
 
 pub static ELM: LangDef = LangDef {
     name: "Elm",
     extensions: &["elm"],
-    line_comment: Some("--"),
+    line_comments: &["--"],
     block_comment: Some(("{-", "-}")),
+    nested_block_comment: true,
+    quotes: &['"'],
+    multiline_strings: &["\"\"\""],
     doc_comment: Some("{-|"),
-    function_patterns: &["\\w+\\s*:\\s+", "\\w+\\s+\\w+\\s*="],
+    special_comments: &[],
+    // 顶层定义 `name args =`；类型标注 `name : Type` 与定义成对出现，不单独计数
+    function_patterns: &[r"^[a-z]\w*(?:\s+[a-z_]\w*)*\s*=(?:\s|$)"],
     class_patterns: &["type\\s+\\w+"],
+    branch_keywords: &["if", "case", "&&", "||"],
 };
 
 pub static ERLANG: LangDef = LangDef {
     name: "Erlang",
     extensions: &["erl", "hrl"],
-    line_comment: Some("%"),
+    line_comments: &["%"],
     block_comment: None,
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &[],
     doc_comment: None,
+    special_comments: &[],
     function_patterns: &["\\w+\\s*\\([^)]*\\)\\s*->"],
     class_patterns: &["-module\\s+\\w+"],
+    branch_keywords: &["if", "case", "when", "andalso", "orelse"],
+};
+
+pub static FORTRAN: LangDef = LangDef {
+    name: "Fortran",
+    extensions: &["f90", "f95", "f03", "f08", "f", "for", "f77"],
+    line_comments: &["!"],
+    block_comment: None,
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &[],
+    doc_comment: None,
+    special_comments: &[],
+    function_patterns: &[r"(?i)^\s*(?:(?:pure|elemental|recursive|integer|real|logical|complex|character|double\s+precision)\s+)*(?:function|subroutine)\s+\w+"],
+    class_patterns: &[r"(?i)^\s*module\s+\w+\s*$", r"(?i)^\s*type\s*(?:,[^:]*)?::\s*\w+"],
+    branch_keywords: &[],
 };
 
 pub static FSHARP: LangDef = LangDef {
     name: "F#",
     extensions: &["fs", "fsi", "fsx", "fsscript"],
-    line_comment: Some("//"),
+    line_comments: &["//"],
     block_comment: Some(("(*", "*)")),
+    nested_block_comment: true,
+    quotes: &['"'],
+    multiline_strings: &[],
     doc_comment: Some("///"),
+    special_comments: &[],
     function_patterns: &["let\\s+\\w+", "member\\s+\\w+\\."],
     class_patterns: &["type\\s+\\w+"],
+    branch_keywords: &["if", "elif", "for", "while", "match", "&&", "||"],
 };
 
 pub static GO: LangDef = LangDef {
     name: "Go",
     extensions: &["go"],
-    line_comment: Some("//"),
+    line_comments: &["//"],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"', '\'', '`'],
+    multiline_strings: &["`"],
     doc_comment: None,
+    special_comments: &[],
     function_patterns: &["func\\s+\\w+\\s*\\([^)]*\\)"],
     class_patterns: &["type\\s+\\w+\\s+struct"],
+    branch_keywords: &["if", "for", "case", "&&", "||"],
 };
 
 pub static GRAPHQL: LangDef = LangDef {
     name: "GraphQL",
     extensions: &["graphql", "gql"],
-    line_comment: Some("#"),
+    line_comments: &["#"],
     block_comment: None,
+    nested_block_comment: false,
+    quotes: &['"'],
+    multiline_strings: &[],
     doc_comment: Some("\"\"\""),
-    function_patterns: &["type\\s+\\w+", "interface\\s+\\w+", "query\\s+\\w+"],
-    class_patterns: &["type\\s+\\w+"],
-};
-
-pub static H: LangDef = LangDef {
-    name: "C Header",
-    extensions: &["h"],
-    line_comment: Some("//"),
-    block_comment: Some(("/*", "*/")),
-    doc_comment: None,
-    function_patterns: &[
-        r"\w+\s+\w+\s*\([^)]*\)\s*;", 
-        r"\w+\s+\*\w+\s*\([^)]*\)\s*;"],
-    class_patterns: &["typedef\\s+struct\\s+\\w+"],
+    special_comments: &[],
+    function_patterns: &[r"(?:query|mutation|subscription)\s+\w+"],
+    class_patterns: &["type\\s+\\w+", "interface\\s+\\w+"],
+    branch_keywords: &[],
 };
 
 pub static HASKELL: LangDef = LangDef {
     name: "Haskell",
     extensions: &["hs", "lhs"],
-    line_comment: Some("--"),
+    line_comments: &["--"],
     block_comment: Some(("{-", "-}")),
+    nested_block_comment: true,
+    // `'` 可以出现在标识符中（如 `x'`），不作为字符串定界符
+    quotes: &['"'],
+    multiline_strings: &[],
     doc_comment: Some("{-|"),
+    special_comments: &[],
     function_patterns: &["\\w+\\s*::", "\\w+\\s+\\w+\\s*="],
     class_patterns: &["data\\s+\\w+", "class\\s+\\w+"],
+    branch_keywords: &["if", "case", "&&", "||"],
 };
 
 pub static HTML: LangDef = LangDef {
     name: "HTML",
     extensions: &["html", "htm", "xhtml"],
-    line_comment: None,
+    line_comments: &[],
     block_comment: Some(("<!--", "-->")),
+    nested_block_comment: false,
+    quotes: &[],
+    multiline_strings: &[],
     doc_comment: None,
+    special_comments: &[],
     function_patterns: &["<script", "<function"],
     class_patterns: &["class\\s*=\\s*\""],
-};
-
-pub static HPP: LangDef = LangDef {
-    name: "C++ Header",
-    extensions: &["hpp", "hxx", "hh", "h++"],
-    line_comment: Some("//"),
-    block_comment: Some(("/*", "*/")),
-    doc_comment: Some("/**"),
-    function_patterns: &[
-        r"\w+\s+\w+\s*\([^)]*\)\s*\{",
-        r"\w+\s+\*\w+\s*\([^)]*\)\s*\{", 
-        r"\w+\s+&\w+\s*\([^)]*\)\s*\{"],
-    class_patterns: &[r"class\s+\w+"],
+    branch_keywords: &[],
 };
 
 pub static JAVA: LangDef = LangDef {
     name: "Java",
     extensions: &["java", "class", "jar"],
-    line_comment: Some("//"),
+    line_comments: &["//"],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &["\"\"\""],
     doc_comment: Some("/**"),
+    special_comments: &[],
     function_patterns: &["\\w+\\s+\\w+\\s*\\([^)]*\\)\\s*\\{", "public\\s+\\w+\\s+\\w+\\s*\\([^)]*\\)\\s*\\{"],
     class_patterns: &["class\\s+\\w+", "interface\\s+\\w+"],
+    branch_keywords: C_BRANCHES,
 };
 
 pub static JAVASCRIPT: LangDef = LangDef {
     name: "JavaScript",
     extensions: &["js", "jsx", "mjs", "cjs"],
-    line_comment: Some("//"),
+    line_comments: &["//"],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"', '\'', '`'],
+    multiline_strings: &["`"],
     doc_comment: Some("/**"),
+    special_comments: &[],
     function_patterns: &["function\\s+\\w+", "const\\s+\\w+\\s*=\\s*\\(", "\\w+\\s*:\\s*function"],
     class_patterns: &["class\\s+\\w+"],
+    branch_keywords: C_BRANCHES,
 };
 
 pub static JSON: LangDef = LangDef {
     name: "JSON",
-    extensions: &["json", "jsonc"],
-    line_comment: None,
+    extensions: &["json"],
+    line_comments: &[],
     block_comment: None,
+    nested_block_comment: false,
+    quotes: &['"'],
+    multiline_strings: &[],
+    doc_comment: None,
+    special_comments: &[],
+    function_patterns: &[],
+    class_patterns: &[],
+    branch_keywords: &[],
+};
+
+pub static JSONC: LangDef = LangDef {
+    name: "JSONC",
+    extensions: &["jsonc", "json5"],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"'],
+    multiline_strings: &[],
     doc_comment: None,
+    special_comments: &[],
     function_patterns: &[],
     class_patterns: &[],
+    branch_keywords: &[],
 };
 
 pub static JSONNET: LangDef = LangDef {
     name: "Jsonnet",
     extensions: &["jsonnet", "libsonnet"],
-    line_comment: Some("//"),
+    line_comments: &["//", "#"],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &[],
     doc_comment: Some("/**"),
-    function_patterns: &["function\\s+\\w+", "local\\s+\\w+"],
+    special_comments: &[],
+    function_patterns: &[r"local\s+\w+\s*\(", r"^\w+\s*\([^)]*\)\s*:", r"=\s*function\s*\("],
     class_patterns: &[],
+    branch_keywords: &["if", "&&", "||"],
 };
 
 pub static JULIA: LangDef = LangDef {
     name: "Julia",
     extensions: &["jl"],
-    line_comment: Some("#"),
+    line_comments: &["#"],
     block_comment: Some(("#=", "=#")),
+    nested_block_comment: true,
+    quotes: &['"'],
+    multiline_strings: &["\"\"\""],
     doc_comment: None,
+    special_comments: &[],
     function_patterns: &["function\\s+\\w+", "\\w+\\s*\\([^)]*\\)\\s*="],
     class_patterns: &["struct\\s+\\w+", "type\\s+\\w+"],
+    branch_keywords: &["if", "elseif", "for", "while", "&&", "||", "?"],
 };
 
 pub static KOTLIN: LangDef = LangDef {
     name: "Kotlin",
     extensions: &["kt", "kts", "ktm"],
-    line_comment: Some("//"),
+    line_comments: &["//"],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: true,
+    quotes: &['"', '\''],
+    multiline_strings: &["\"\"\""],
     doc_comment: Some("/**"),
-    function_patterns: &["fun\\s+\\w+", "val\\s+\\w+", "var\\s+\\w+"],
+    special_comments: &[],
+    function_patterns: &[r"fun\s+(?:<[^>]*>\s*)?[\w.]+\s*\("],
     class_patterns: &["class\\s+\\w+", "interface\\s+\\w+", "object\\s+\\w+"],
+    branch_keywords: &["if", "for", "while", "when", "catch", "&&", "||"],
 };
 
 pub static LUA: LangDef = LangDef {
     name: "Lua",
     extensions: &["lua", "wlua"],
-    line_comment: Some("--"),
+    line_comments: &["--"],
     block_comment: Some(("--[[", "]]")),
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &[],
     doc_comment: None,
+    special_comments: &[],
     function_patterns: &["function\\s+\\w+", "local\\s+function\\s+\\w+"],
     class_patterns: &[],
+    branch_keywords: &["if", "elseif", "for", "while", "until", "and", "or"],
 };
 
 pub static MARKDOWN: LangDef = LangDef {
     name: "Markdown",
     extensions: &["md", "markdown", "mdown", "mkdn"],
-    line_comment: None,
+    line_comments: &[],
     block_comment: Some(("<!--", "-->")),
+    nested_block_comment: false,
+    quotes: &[],
+    multiline_strings: &[],
     doc_comment: None,
+    special_comments: &[],
     function_patterns: &[],
     class_patterns: &[],
+    branch_keywords: &[],
+};
+
+pub static MATLAB: LangDef = LangDef {
+    name: "MATLAB",
+    extensions: &["m"],
+    line_comments: &["%", "#"],
+    block_comment: Some(("%{", "%}")),
+    nested_block_comment: false,
+    // `'` 同时是转置运算符，无法可靠区分，只识别双引号字符串
+    quotes: &['"'],
+    multiline_strings: &[],
+    doc_comment: None,
+    special_comments: &[],
+    function_patterns: &[r"^\s*function\b"],
+    class_patterns: &[r"^\s*classdef\b"],
+    branch_keywords: &["if", "elseif", "for", "while", "case", "&&", "||"],
 };
 
 pub static NIX: LangDef = LangDef {
     name: "Nix",
     extensions: &["nix"],
-    line_comment: Some("#"),
+    line_comments: &["#"],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"'],
+    multiline_strings: &["''"],
     doc_comment: None,
-    function_patterns: &["\\w+\\s*=", "\\w+\\s*:"],
+    special_comments: &[],
+    // lambda 的参数：`x: ...` 或属性集模式 `{ pkgs, ... }: ...`
+    function_patterns: &[r"\b\w+\s*:(?:\s|$)", r"\{[^}]*\}\s*:(?:\s|$)"],
     class_patterns: &[],
+    branch_keywords: &["if", "&&", "||"],
+};
+
+pub static OBJECTIVE_C: LangDef = LangDef {
+    name: "Objective-C",
+    extensions: &["m"],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &[],
+    doc_comment: Some("/**"),
+    special_comments: &[],
+    function_patterns: &[
+        r"^[-+]\s*\([^)]*\)[^;]*$",
+        r"\w+\s+\w+\s*\([^)]*\)\s*\{"],
+    class_patterns: &[r"@interface\s+\w+", r"@implementation\s+\w+"],
+    branch_keywords: C_BRANCHES,
+};
+
+pub static OBJECTIVE_CPP: LangDef = LangDef {
+    name: "Objective-C++",
+    extensions: &["mm"],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &[],
+    doc_comment: Some("/**"),
+    special_comments: &[],
+    function_patterns: &[
+        r"^[-+]\s*\([^)]*\)[^;]*$",
+        r"\w+\s+\w+\s*\([^)]*\)\s*\{",
+        r"\w+\s+&\w+\s*\([^)]*\)\s*\{"],
+    class_patterns: &[r"@interface\s+\w+", r"@implementation\s+\w+", r"class\s+\w+"],
+    branch_keywords: C_BRANCHES,
 };
 
 pub static OCAML: LangDef = LangDef {
     name: "OCaml",
     extensions: &["ml", "mli", "cmi", "cmo", "cmx"],
-    line_comment: None,
+    line_comments: &[],
     block_comment: Some(("(*", "*)")),
+    nested_block_comment: true,
+    quotes: &['"'],
+    multiline_strings: &[],
     doc_comment: Some("(**"),
+    special_comments: &[],
     function_patterns: &["let\\s+\\w+", "let rec\\s+\\w+"],
     class_patterns: &["type\\s+\\w+", "module\\s+\\w+", "class\\s+\\w+"],
+    branch_keywords: &["if", "for", "while", "match", "&&", "||"],
 };
 
 pub static PERL: LangDef = LangDef { 
     name: "Perl",
     extensions: &["pl", "pm"],
-    line_comment: Some("#"),
+    line_comments: &["#"],
     block_comment: None,
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &[],
     doc_comment: None,
+    special_comments: &[],
     function_patterns: &["sub\\s+\\w+"],
     class_patterns: &["class\\s+\\w+"],
+    branch_keywords: &["if", "elsif", "unless", "for", "foreach", "while", "until", "&&", "||", "?"],
 };
 
 pub static PHP: LangDef = LangDef {
     name: "PHP",
     extensions: &["php", "phtml", "php3", "php4", "php5", "phps", "phpt"],
-    line_comment: Some("//"),
+    line_comments: &["//"],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &[],
     doc_comment: Some("/**"),
+    special_comments: &[],
     function_patterns: &["function\\s+\\w+", "\\w+\\s+\\w+\\s*\\([^)]*\\)\\s*\\{"],
     class_patterns: &["class\\s+\\w+", "interface\\s+\\w+"],
+    branch_keywords: C_BRANCHES,
+};
+
+pub static POWERSHELL: LangDef = LangDef {
+    name: "PowerShell",
+    extensions: &["ps1", "psm1", "psd1"],
+    line_comments: &["#"],
+    block_comment: Some(("<#", "#>")),
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &[],
+    doc_comment: None,
+    special_comments: &[],
+    function_patterns: &[r"(?i)function\s+[\w-]+", r"(?i)filter\s+[\w-]+"],
+    class_patterns: &[r"(?i)class\s+\w+", r"(?i)enum\s+\w+"],
+    branch_keywords: &["if", "elseif", "for", "foreach", "while", "switch", "catch", "-and", "-or"],
+};
+
+pub static PROTOBUF: LangDef = LangDef {
+    name: "Protobuf",
+    extensions: &["proto"],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &[],
+    doc_comment: None,
+    special_comments: &[],
+    function_patterns: &[r"^\s*rpc\s+\w+"],
+    class_patterns: &[r"^\s*message\s+\w+", r"^\s*service\s+\w+", r"^\s*enum\s+\w+"],
+    branch_keywords: &[],
 };
 
 pub static PYTHON: LangDef = LangDef {
     name: "Python",
     extensions: &["py", "pyi", "pyc", "pyd", "pyw", "pyz", "pyzw"],
-    line_comment: Some("#"),
+    line_comments: &["#"],
     block_comment: Some(("\"\"\"", "\"\"\"")),
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &["\"\"\"", "'''"],
     doc_comment: Some("\"\"\""),
-    function_patterns: &["def\\s+\\w+", "class\\s+\\w+", "async\\s+def\\s+\\w+"],
+    special_comments: &["#:"],
+    function_patterns: &["def\\s+\\w+", "async\\s+def\\s+\\w+"],
     class_patterns: &["class\\s+\\w+"],
+    branch_keywords: &["if", "elif", "for", "while", "except", "and", "or"],
 };
 
 pub static QCL: LangDef = LangDef {
     name: "QCL",
     extensions: &["qcl"],
-    line_comment: Some("//"),
+    line_comments: &["//"],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"'],
+    multiline_strings: &[],
     doc_comment: Some("/**"),
+    special_comments: &[],
     function_patterns: &["\\w+\\s+\\w+\\s*\\([^)]*\\)\\s*\\{", "procedure\\s+\\w+"],
     class_patterns: &[],
+    branch_keywords: &["if", "for", "while", "&&", "||"],
 };
 
 pub static QSHARP: LangDef = LangDef {
     name: "Q#",
     extensions: &["qs"],
-    line_comment: Some("//"),
+    line_comments: &["//"],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"'],
+    multiline_strings: &[],
     doc_comment: Some("/**"),
+    special_comments: &[],
     function_patterns: &["operation\\s+\\w+", "function\\s+\\w+"],
     class_patterns: &[],
+    branch_keywords: &["if", "elif", "for", "while", "repeat", "&&&", "|||"],
 };
 
 pub static R: LangDef = LangDef {
     name: "R",
     extensions: &["r", "R", "s", "Rhistory", "Rprofile", "Renviron"],
-    line_comment: Some("#"),
+    line_comments: &["#"],
     block_comment: None,
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &[],
     doc_comment: None,
-    function_patterns: &["\\w+\\s*<-\\s*function", "\\w+\\s*\\([^)]*\\)"],
+    special_comments: &[],
+    function_patterns: &[r"\w+\s*(?:<<?-|=)\s*function\s*\("],
     class_patterns: &[],
+    branch_keywords: &["if", "for", "while", "&&", "||"],
 };
 
 pub static REGEX: LangDef = LangDef {
     name: "Regex",
     extensions: &["regex"],
-    line_comment: None,
+    line_comments: &[],
     block_comment: None,
+    nested_block_comment: false,
+    quotes: &[],
+    multiline_strings: &[],
     doc_comment: None,
+    special_comments: &[],
     function_patterns: &[],
     class_patterns: &[],
+    branch_keywords: &[],
 };
 
 pub static RUBY: LangDef = LangDef {
     name: "Ruby",
     extensions: &["rb", "rbw", "gemspec", "rake", "ru", "erb"],
-    line_comment: Some("#"),
+    line_comments: &["#"],
     block_comment: Some(("=begin", "=end")),
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &[],
     doc_comment: None,
-    function_patterns: &["def\\s+\\w+", "def\\s+self\\.\\w+", "class\\s+\\w+", "module\\s+\\w+"],
+    special_comments: &[],
+    function_patterns: &["def\\s+\\w+", "def\\s+self\\.\\w+"],
     class_patterns: &["class\\s+\\w+", "module\\s+\\w+"],
+    branch_keywords: &["if", "elsif", "unless", "while", "until", "for", "when", "rescue", "&&", "||"],
 };
 
 pub static RUST: LangDef = LangDef {
     name: "Rust",
     extensions: &["rs"],
-    line_comment: Some("//"),
+    line_comments: &["//"],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: true,
+    // `'` 还用于生命周期，不作为字符串定界符
+    quotes: &['"'],
+    multiline_strings: &["\""],
     doc_comment: Some("///"),
+    special_comments: &[],
     function_patterns: &["fn\\s+\\w+", "pub\\s+fn\\s+\\w+", "async\\s+fn\\s+\\w+"],
     class_patterns: &["struct\\s+\\w+", "enum\\s+\\w+", "impl\\s+\\w+"],
+    branch_keywords: &["if", "for", "while", "&&", "||", "?"],
 };
 
 pub static SASS: LangDef = LangDef {
     name: "Sass",
     extensions: &["sass", "scss"],
-    line_comment: Some("//"),
+    line_comments: &["//"],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &[],
     doc_comment: None,
-    function_patterns: &["@\\w+\\s+", "\\w+\\s*\\{"],
+    special_comments: &[],
+    function_patterns: &[r"@(?:mixin|function)\s+[\w-]+"],
     class_patterns: &["\\.\\w+", "%\\w+"],
+    branch_keywords: &["@if", "@else if", "@each", "@for", "@while"],
 };
 
 pub static SCALA: LangDef = LangDef {
     name: "Scala",
     extensions: &["scala", "sc", "sbt"],
-    line_comment: Some("//"),
+    line_comments: &["//"],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: true,
+    quotes: &['"', '\''],
+    multiline_strings: &["\"\"\""],
     doc_comment: Some("/**"),
+    special_comments: &[],
     function_patterns: &["def\\s+\\w+", "val\\s+\\w+", "var\\s+\\w+"],
     class_patterns: &["class\\s+\\w+", "object\\s+\\w+", "trait\\s+\\w+"],
+    branch_keywords: &["if", "for", "while", "case", "catch", "&&", "||"],
 };
 
 pub static SHELL: LangDef = LangDef {
     name: "Shell",
     extensions: &["sh", "bash", "zsh", "ksh", "csh"],
-    line_comment: Some("#"),
+    line_comments: &["#"],
     block_comment: None,
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &[],
     doc_comment: None,
+    special_comments: &[],
     function_patterns: &["function\\s+\\w+", "\\w+\\s*\\(\\s*\\)"],
     class_patterns: &[],
+    branch_keywords: &["if", "elif", "for", "while", "until", "&&", "||"],
 };
 
 pub static SQL: LangDef = LangDef {
     name: "SQL",
     extensions: &["sql", "ddl", "dml"],
-    line_comment: Some("--"),
+    line_comments: &["--"],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &[],
     doc_comment: None,
-    function_patterns: &["CREATE\\s+\\w+", "ALTER\\s+\\w+", "DROP\\s+\\w+", "SELECT\\s+"],
+    special_comments: &[],
+    function_patterns: &[r"(?i)CREATE\s+(?:OR\s+REPLACE\s+)?(?:FUNCTION|PROCEDURE|TRIGGER)\s+"],
     class_patterns: &["CREATE\\s+TABLE\\s+\\w+"],
+    branch_keywords: &[],
 };
 
 pub static SWIFT: LangDef = LangDef {
     name: "Swift",
     extensions: &["swift", "swiftinterface", "swiftmodule"],
-    line_comment: Some("//"),
+    line_comments: &["//"],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: true,
+    quotes: &['"'],
+    multiline_strings: &["\"\"\""],
     doc_comment: Some("///"),
+    special_comments: &[],
     function_patterns: &["func\\s+\\w+", "init\\s*\\(", "deinit"],
     class_patterns: &["class\\s+\\w+", "struct\\s+\\w+", "enum\\s+\\w+"],
+    branch_keywords: &["if", "guard", "for", "while", "case", "catch", "&&", "||"],
 };
 
 pub static TCL: LangDef = LangDef {
     name: "Tcl",
     extensions: &["tcl", "tk"],
-    line_comment: Some("#"),
+    line_comments: &["#"],
     block_comment: None,
+    nested_block_comment: false,
+    quotes: &['"'],
+    multiline_strings: &[],
     doc_comment: None,
+    special_comments: &[],
     function_patterns: &["proc\\s+\\w+"],
     class_patterns: &[],
+    branch_keywords: &["if", "elseif", "for", "foreach", "while", "&&", "||"],
 };
 
 pub static TEX: LangDef = LangDef {
     name: "TeX",
     extensions: &["tex", "latex", "sty", "cls", "bib"],
-    line_comment: Some("%"),
+    line_comments: &["%"],
     block_comment: None,
+    nested_block_comment: false,
+    quotes: &[],
+    multiline_strings: &[],
     doc_comment: None,
-    function_patterns: &["\\\\\\w+\\s*\\{"],
+    special_comments: &[],
+    // 宏定义，`\begin{...}` 等普通命令不算
+    function_patterns: &[r"\\(?:newcommand|renewcommand|providecommand|DeclareRobustCommand|def)\b"],
     class_patterns: &[],
+    branch_keywords: &[],
 };
 
 pub static TEXT: LangDef = LangDef {
     name: "Text",
     extensions: &["txt"],
-    line_comment: None,
+    line_comments: &[],
     block_comment: None,
+    nested_block_comment: false,
+    quotes: &[],
+    multiline_strings: &[],
     doc_comment: None,
+    special_comments: &[],
     function_patterns: &[],
     class_patterns: &[],
+    branch_keywords: &[],
+};
+
+pub static THRIFT: LangDef = LangDef {
+    name: "Thrift",
+    extensions: &["thrift"],
+    line_comments: &["//", "#"],
+    block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &[],
+    doc_comment: Some("/**"),
+    special_comments: &[],
+    // 服务方法没有关键字（`i32 add(1: i32 a)`），按“返回类型 名字(”识别；结构体字段以 `1:` 开头不会匹配
+    function_patterns: &[r"^\s*(?:oneway\s+)?[\w.<>, ]+\s+\w+\s*\("],
+    class_patterns: &[r"^\s*struct\s+\w+", r"^\s*union\s+\w+", r"^\s*exception\s+\w+", r"^\s*service\s+\w+", r"^\s*enum\s+\w+"],
+    branch_keywords: &[],
 };
 
 pub static TOML: LangDef = LangDef {
     name: "TOML",
     extensions: &["toml"],
-    line_comment: Some("#"),
+    line_comments: &["#"],
     block_comment: None,
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &["\"\"\"", "'''"],
     doc_comment: None,
+    special_comments: &[],
     function_patterns: &[],
     class_patterns: &[],
+    branch_keywords: &[],
 };
 
 pub static TYPESCRIPT: LangDef = LangDef {
     name: "TypeScript",
     extensions: &["ts", "tsx", "cts", "mts"],
-    line_comment: Some("//"),
+    line_comments: &["//"],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"', '\'', '`'],
+    multiline_strings: &["`"],
     doc_comment: Some("/**"),
+    special_comments: &[],
     function_patterns: &["function\\s+\\w+", "const\\s+\\w+\\s*=\\s*\\(", "\\w+\\s*:\\s*function"],
     class_patterns: &["class\\s+\\w+", "interface\\s+\\w+", "type\\s+\\w+"],
+    branch_keywords: C_BRANCHES,
 };
 
 pub static V: LangDef = LangDef {
     name: "V",
     extensions: &["v", "vv", "vsh"],
-    line_comment: Some("//"),
+    line_comments: &["//"],
     block_comment: Some(("/*", "*/")),
+    nested_block_comment: false,
+    quotes: &['"', '\'', '`'],
+    multiline_strings: &[],
     doc_comment: None,
+    special_comments: &[],
     function_patterns: &["fn\\s+\\w+", "pub\\s+fn\\s+\\w+"],
     class_patterns: &["struct\\s+\\w+", "enum\\s+\\w+", "const\\s+\\w+", "var\\s+\\w+"],
+    branch_keywords: &["if", "for", "match", "&&", "||"],
+};
+
+/// 单文件组件；`<script>`/`<style>` 中的内容可用 `--only-lang-content` 单独统计
+pub static VUE: LangDef = LangDef {
+    name: "Vue",
+    extensions: &["vue"],
+    line_comments: &[],
+    block_comment: Some(("<!--", "-->")),
+    nested_block_comment: false,
+    quotes: &['"', '\'', '`'],
+    multiline_strings: &["`"],
+    doc_comment: None,
+    special_comments: &[],
+    function_patterns: &["function\\s+\\w+", "\\w+\\s*\\([^)]*\\)\\s*\\{"],
+    class_patterns: &[],
+    branch_keywords: &[],
 };
 
 pub static WENYAN: LangDef = LangDef {
     name: "文言",
     extensions: &["wy"],
-    line_comment: Some("註"),
+    line_comments: &["註"],
     block_comment: Some(("〔", "〕")),
+    nested_block_comment: false,
+    quotes: &[],
+    multiline_strings: &[],
     doc_comment: None,
+    special_comments: &[],
     function_patterns: &["有"],
     class_patterns: &[],
+    branch_keywords: &[],
 };
 
 pub static XML: LangDef = LangDef {
     name: "XML",
     extensions: &["xml", "xsl", "xslt", "svg", "wsdl", "wsdd", "xhtml"],
-    line_comment: None,
+    line_comments: &[],
     block_comment: Some(("<!--", "-->")),
+    nested_block_comment: false,
+    quotes: &[],
+    multiline_strings: &[],
     doc_comment: None,
-    function_patterns: &["<\\w+", "</\\w+"],
+    special_comments: &[],
+    function_patterns: &[],
     class_patterns: &["<\\w+\\s+class\\s*=\\s*\""],
+    branch_keywords: &[],
 };
 
 pub static YAML: LangDef = LangDef {
     name: "YAML",
     extensions: &["yaml", "yml"],
-    line_comment: Some("#"),
+    line_comments: &["#"],
     block_comment: None,
+    nested_block_comment: false,
+    // 未加引号的标量中常见撇号（如 `don't`），只识别双引号字符串
+    quotes: &['"'],
+    multiline_strings: &[],
     doc_comment: None,
+    special_comments: &[],
     function_patterns: &[],
     class_patterns: &[],
+    branch_keywords: &[],
 };
 
 pub static ZIG: LangDef = LangDef {
     name: "Zig",
     extensions: &["zig"],
-    line_comment: Some("//"),
+    line_comments: &["//"],
     block_comment: None,
+    nested_block_comment: false,
+    quotes: &['"', '\''],
+    multiline_strings: &[],
     doc_comment: None,
+    special_comments: &[],
     function_patterns: &["fn\\s+\\w+", "pub\\s+fn\\s+\\w+"],
     class_patterns: &["const\\s+\\w+", "var\\s+\\w+"],
+    branch_keywords: &["if", "for", "while", "switch", "catch", "orelse", "and", "or"],
 };
\ No newline at end of file
@@ -0,0 +1,237 @@
+use lazy_static::lazy_static;
+use regex::{Regex, RegexSet};
+
+use super::lang_type::LangType;
+use super::registry::LANGUAGE_DEFINITIONS;
+
+/// 扩展名有歧义时，`get_type_from_ext` 默认结果之外还可能对应的语言
+pub static AMBIGUOUS_EXTENSIONS: &[(&str, LangType)] = &[
+    ("h", LangType::ObjectiveC),
+    ("m", LangType::Matlab),
+];
+
+/// 只看文件开头的这么多字节来判断歧义扩展名
+pub const SNIFF_BYTES: usize = 8 * 1024;
+
+/// 某个扩展名是否需要根据内容进一步判断
+pub fn is_ambiguous(ext: &str) -> bool {
+    AMBIGUOUS_EXTENSIONS.iter().any(|(e, _)| *e == ext)
+}
+
+/// 扩展名可能对应的其他语言
+pub fn alternatives_for_ext(ext: &str) -> impl Iterator<Item = LangType> + '_ {
+    AMBIGUOUS_EXTENSIONS
+        .iter()
+        .filter(move |(e, _)| *e == ext)
+        .map(|(_, lang)| *lang)
+}
+
+/// 根据文件开头的内容解决扩展名歧义，无法确定时返回 None，沿用扩展名的默认语言
+pub fn resolve_by_content(ext: &str, head: &str) -> Option<LangType> {
+    match ext {
+        "h" if looks_like_objective_c(head) => Some(LangType::ObjectiveC),
+        "m" if !looks_like_objective_c(head) && looks_like_matlab(head) => Some(LangType::Matlab),
+        _ => None,
+    }
+}
+
+/// shebang 中的解释器名到语言的映射，版本号后缀（如 `python3.11`）会先去掉
+pub static SHEBANG_INTERPRETERS: &[(&str, LangType)] = &[
+    ("python", LangType::Python),
+    ("bash", LangType::Shell),
+    ("sh", LangType::Shell),
+    ("node", LangType::Javascript),
+    ("ruby", LangType::Ruby),
+    ("perl", LangType::Perl),
+];
+
+/// 根据首行的 shebang 判断语言，支持 `#!/bin/sh` 与 `#!/usr/bin/env -S python3 -u` 两种写法
+pub fn lang_from_shebang(first_line: &str) -> Option<LangType> {
+    let mut words = first_line.strip_prefix("#!")?.split_whitespace();
+    let mut program = words.next()?.rsplit('/').next()?;
+    if program == "env" {
+        program = words.find(|w| !w.starts_with('-') && !w.contains('='))?;
+    }
+    let name = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    SHEBANG_INTERPRETERS
+        .iter()
+        .find(|(interpreter, _)| *interpreter == name)
+        .map(|(_, lang)| *lang)
+}
+
+/// 扩展名无法识别时根据内容判断语言：先看 shebang，`sniff` 时再按语法特征猜测
+pub fn lang_from_content(head: &str, sniff: bool) -> Option<LangType> {
+    lang_from_shebang(head.lines().next().unwrap_or(""))
+        .or_else(|| sniff.then(|| sniff_lang(head)).flatten())
+}
+
+/// 按语法特征猜测语言时只看开头的这么多行
+pub const SNIFF_LINES: usize = 50;
+
+/// 得分低于该值时不做猜测
+const MIN_SNIFF_SCORE: usize = 3;
+
+/// 一种语言的语法特征：行注释标记、行首以关键字开始的函数/类定义、行首的分支关键字
+struct Sniffer {
+    lang: LangType,
+    line_comments: &'static [&'static str],
+    decls: RegexSet,
+    branches: Option<Regex>,
+}
+
+impl Sniffer {
+    /// 注释行 1 分，函数/类定义 2 分，分支语句 1 分
+    fn score(&self, line: &str) -> usize {
+        if self.line_comments.iter().any(|prefix| line.starts_with(prefix)) {
+            return 1;
+        }
+        let decl = if self.decls.is_match(line) { 2 } else { 0 };
+        let branch = self.branches.as_ref().map_or(0, |re| usize::from(re.is_match(line)));
+        decl + branch
+    }
+}
+
+lazy_static! {
+    /// 只为有函数定义模式的语言建立特征，数据与文档格式不参与猜测
+    static ref SNIFFERS: Vec<Sniffer> = LANGUAGE_DEFINITIONS
+        .iter()
+        .filter(|(_, def)| !def.function_patterns.is_empty())
+        .map(|(lang, def)| {
+            // 以 `\w` 开头的模式（如 `\w+\s*\(`）会匹配任意函数调用，不能区分语言
+            let decls = def.function_patterns
+                .iter()
+                .chain(def.class_patterns)
+                .filter(|p| !p.starts_with("\\w"))
+                .map(|p| format!("^(?:{})", p));
+            let keywords: Vec<String> = def.branch_keywords
+                .iter()
+                .filter(|kw| kw.chars().all(char::is_alphanumeric))
+                .map(|kw| regex::escape(kw))
+                .collect();
+            Sniffer {
+                lang: *lang,
+                line_comments: def.line_comments,
+                decls: RegexSet::new(decls).unwrap(),
+                branches: (!keywords.is_empty())
+                    .then(|| Regex::new(&format!(r"^(?:{})\b", keywords.join("|"))).unwrap()),
+            }
+        })
+        .collect();
+}
+
+/// 用开头 [`SNIFF_LINES`] 行匹配各语言的语法特征，取得分最高的语言；
+/// 得分过低或有多种语言并列最高时返回 None
+pub fn sniff_lang(head: &str) -> Option<LangType> {
+    let lines: Vec<&str> = head.lines()
+        .take(SNIFF_LINES)
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    let mut best: Option<(LangType, usize)> = None;
+    let mut tied = false;
+    for sniffer in SNIFFERS.iter() {
+        let score: usize = lines.iter().map(|line| sniffer.score(line)).sum();
+        match best {
+            Some((_, top)) if score == top => tied = true,
+            Some((_, top)) if score < top => {}
+            _ => {
+                best = Some((sniffer.lang, score));
+                tied = false;
+            }
+        }
+    }
+
+    best.filter(|&(_, score)| !tied && score >= MIN_SNIFF_SCORE)
+        .map(|(lang, _)| lang)
+}
+
+/// MATLAB/Octave 脚本：以 `%`、`#` 注释或 `function`/`classdef` 开头的行，或单独的 `end`
+fn looks_like_matlab(head: &str) -> bool {
+    head.lines()
+        .map(str::trim)
+        .any(|line| {
+            line.starts_with('%')
+                || (line.starts_with('#') && !line.starts_with("#import") && !line.starts_with("#include"))
+                || line.starts_with("function ")
+                || line.starts_with("classdef ")
+                || matches!(line, "end" | "endfunction")
+        })
+}
+
+fn looks_like_objective_c(head: &str) -> bool {
+    const MARKERS: &[&str] = &["@interface", "@implementation", "@protocol", "@property", "@end", "#import"];
+    head.lines()
+        .map(str::trim_start)
+        .any(|line| MARKERS.iter().any(|m| line.starts_with(m)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::langs::registry::get_type_from_ext;
+
+    #[test]
+    fn test_objective_c_extensions() {
+        assert_eq!(get_type_from_ext("m"), Some(LangType::ObjectiveC));
+        assert_eq!(get_type_from_ext("mm"), Some(LangType::ObjectiveCpp));
+        assert!(is_ambiguous("h"));
+        assert!(is_ambiguous("m"));
+        assert!(!is_ambiguous("mm"));
+        assert_eq!(alternatives_for_ext("h").collect::<Vec<_>>(), [LangType::ObjectiveC]);
+    }
+
+    #[test]
+    fn test_resolve_header_by_content() {
+        let objc = "#import <Foundation/Foundation.h>\n\n@interface Foo : NSObject\n@end\n";
+        assert_eq!(resolve_by_content("h", objc), Some(LangType::ObjectiveC));
+
+        let c = "#include <stdio.h>\n\nint add(int a, int b);\n";
+        assert_eq!(resolve_by_content("h", c), None);
+        assert_eq!(resolve_by_content("c", objc), None);
+    }
+
+    #[test]
+    fn test_lang_from_shebang() {
+        assert_eq!(lang_from_shebang("#!/usr/bin/env python3"), Some(LangType::Python));
+        assert_eq!(lang_from_shebang("#!/usr/bin/python3.11 -u"), Some(LangType::Python));
+        assert_eq!(lang_from_shebang("#!/bin/sh"), Some(LangType::Shell));
+        assert_eq!(lang_from_shebang("#! /bin/bash -e"), Some(LangType::Shell));
+        assert_eq!(lang_from_shebang("#!/usr/bin/env -S node --harmony"), Some(LangType::Javascript));
+        assert_eq!(lang_from_shebang("#!/usr/bin/env ruby"), Some(LangType::Ruby));
+        assert_eq!(lang_from_shebang("#!/usr/bin/perl -w"), Some(LangType::Perl));
+        assert_eq!(lang_from_shebang("#!/usr/bin/env awk"), None);
+        assert_eq!(lang_from_shebang("# not a shebang"), None);
+    }
+
+    #[test]
+    fn test_sniff_lang() {
+        let python = "\
+import sys
+
+# entry point
+def main(argv):
+    for arg in argv:
+        if arg.startswith('-'):
+            continue
+        elif arg == 'x':
+            print(arg)
+    return 0
+";
+        assert_eq!(sniff_lang(python), Some(LangType::Python));
+        assert_eq!(lang_from_content(python, true), Some(LangType::Python));
+        assert_eq!(lang_from_content(python, false), None);
+
+        // 没有可识别的特征
+        assert_eq!(sniff_lang("hello world\nsome notes\n"), None);
+    }
+
+    #[test]
+    fn test_resolve_m_by_content() {
+        let octave = "% add two numbers\nfunction r = add(a, b)\n  r = a + b;\nend\n";
+        assert_eq!(resolve_by_content("m", octave), Some(LangType::Matlab));
+
+        let objc = "#import \"Foo.h\"\n@implementation Foo\n@end\n";
+        assert_eq!(resolve_by_content("m", objc), None);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::langs::lang_type::LangType;
+
+/// 未归入任何语言族时使用的名称
+pub const OTHER_FAMILY: &str = "Other";
+
+/// 语言族与其包含的语言，用于 `--group-by family` 汇总
+pub static LANGUAGE_FAMILIES: &[(&str, &[LangType])] = &[
+    ("Web", &[
+        LangType::Astro,
+        LangType::Css,
+        LangType::Html,
+        LangType::Javascript,
+        LangType::Php,
+        LangType::Sass,
+        LangType::Typescript,
+        LangType::Vue,
+    ]),
+    ("Systems", &[
+        LangType::C,
+        LangType::Cpp,
+        LangType::D,
+        LangType::ObjectiveC,
+        LangType::ObjectiveCpp,
+        LangType::Rust,
+        LangType::V,
+        LangType::Zig,
+    ]),
+    ("JVM", &[
+        LangType::Clojure,
+        LangType::Java,
+        LangType::Kotlin,
+        LangType::Scala,
+    ]),
+    (".NET", &[
+        LangType::Csharp,
+        LangType::Fsharp,
+    ]),
+    ("Mobile", &[
+        LangType::Dart,
+        LangType::Swift,
+    ]),
+    ("Scripting", &[
+        LangType::Batch,
+        LangType::Lua,
+        LangType::Perl,
+        LangType::PowerShell,
+        LangType::Python,
+        LangType::Ruby,
+        LangType::Shell,
+        LangType::Tcl,
+    ]),
+    ("Functional", &[
+        LangType::Elm,
+        LangType::Erlang,
+        LangType::Haskell,
+        LangType::Ocaml,
+    ]),
+    ("Scientific", &[
+        LangType::Fortran,
+        LangType::Julia,
+        LangType::Matlab,
+        LangType::Qcl,
+        LangType::Qsharp,
+        LangType::R,
+    ]),
+    ("Data", &[
+        LangType::Avro,
+        LangType::Graphql,
+        LangType::Json,
+        LangType::Jsonc,
+        LangType::Jsonnet,
+        LangType::Nix,
+        LangType::Protobuf,
+        LangType::Sql,
+        LangType::Thrift,
+        LangType::Toml,
+        LangType::Xml,
+        LangType::Yaml,
+    ]),
+    ("Docs", &[
+        LangType::Asciidoc,
+        LangType::Markdown,
+        LangType::Tex,
+        LangType::Text,
+    ]),
+];
+
+/// 返回语言所属的语言族，未登记的语言归入 [`OTHER_FAMILY`]
+pub fn family_of(lang: LangType) -> &'static str {
+    LANGUAGE_FAMILIES
+        .iter()
+        .find(|(_, langs)| langs.contains(&lang))
+        .map(|(family, _)| *family)
+        .unwrap_or(OTHER_FAMILY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_family_of() {
+        assert_eq!(family_of(LangType::Typescript), "Web");
+        assert_eq!(family_of(LangType::Rust), "Systems");
+        assert_eq!(family_of(LangType::Kotlin), "JVM");
+        assert_eq!(family_of(LangType::Regex), OTHER_FAMILY);
+    }
+}
@@ -2,9 +2,22 @@
 pub struct LangDef {
     pub name: &'static str,
     pub extensions: &'static [&'static str],
-    pub line_comment: Option<&'static str>,
+    /// 行注释标记，可以有多个
+    pub line_comments: &'static [&'static str],
     pub block_comment: Option<(&'static str, &'static str)>,
+    /// 块注释可以嵌套（如 Rust 的 `/* /* */ */`），只有最外层的结束符才结束注释
+    pub nested_block_comment: bool,
+    /// 字符串定界符，字符串内的注释标记不计为注释；字符串内可以用 `\` 转义
+    pub quotes: &'static [char],
+    /// 可以跨行的字符串定界符（如 `"""`、模板字符串的 `` ` ``），以同一定界符闭合；
+    /// 较长的定界符须排在前面
+    pub multiline_strings: &'static [&'static str],
     pub doc_comment: Option<&'static str>,
+    /// 文档生成工具约定的特殊注释标记（如 Sphinx 的 `#:`），可出现在行尾
+    pub special_comments: &'static [&'static str],
     pub function_patterns: &'static [&'static str],
     pub class_patterns: &'static [&'static str],
+    /// 代码中表示一个分支的关键字或运算符（如 `if`、`&&`），用于近似圈复杂度；
+    /// 首尾是字母数字时该侧按词边界匹配
+    pub branch_keywords: &'static [&'static str],
 }
\ No newline at end of file
@@ -2,10 +2,12 @@ use std::hash::{Hash, Hasher};
 
 use strum_macros::{Display, EnumString, VariantNames};
 
-#[derive(Debug, EnumString, VariantNames, Display, Clone, Copy)]
+#[derive(Debug, Default, EnumString, VariantNames, Display, Clone, Copy)]
 pub enum LangType {
     Asciidoc,
     Astro,
+    Avro,
+    Batch,
     C,
     Clojure,
     Cpp,
@@ -15,24 +17,29 @@ pub enum LangType {
     Dart,
     Elm,
     Erlang,
+    Fortran,
     Fsharp,
     Go,
     Graphql,
-    H,
-    Hpp,
     Haskell,
     Html,
     Java,
     Javascript,
     Json,
+    Jsonc,
     Jsonnet,
     Julia,
     Kotlin,
     Lua,
     Markdown,
+    Matlab,
     Nix,
+    ObjectiveC,
+    ObjectiveCpp,
     Ocaml,
     Php,
+    PowerShell,
+    Protobuf,
     Python,
     Qcl,
     Qsharp,
@@ -46,9 +53,11 @@ pub enum LangType {
     Swift,
     Tcl,
     Tex,
+    Thrift,
     Toml,
     Typescript,
     V,
+    Vue,
     WenYan,
     Xml,
     Yaml,
@@ -56,6 +65,7 @@ pub enum LangType {
     Shell,
     Perl,
     Text,
+    #[default]
     Unknown,
 }
 
@@ -71,10 +81,4 @@ impl Hash for LangType {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.to_string().hash(state);
     }
-}
-
-impl Default for LangType {
-    fn default() -> Self {
-        LangType::Unknown
-    }
 }
\ No newline at end of file
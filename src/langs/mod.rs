@@ -1,4 +1,6 @@
 pub mod definitions;
+pub mod detect;
+pub mod families;
 pub mod lang_def;
 pub mod lang_err;
 pub mod lang_type;
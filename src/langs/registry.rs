@@ -1,9 +1,10 @@
 use lazy_static::lazy_static;
 use strum::VariantNames;
-use regex::RegexSet;
+use regex::{Regex, RegexSet};
 
 use std::collections::HashMap;
 
+use super::detect::alternatives_for_ext;
 use super::lang_type::LangType;
 use super::lang_def::LangDef;
 use super::definitions::*;
@@ -16,6 +17,8 @@ lazy_static! {
         
         map.insert(LangType::Asciidoc, &ASCIIDOC);
         map.insert(LangType::Astro, &ASTRO);
+        map.insert(LangType::Avro, &AVRO);
+        map.insert(LangType::Batch, &BATCH);
         map.insert(LangType::C, &C);
         map.insert(LangType::Clojure, &CLOJURE);
         map.insert(LangType::Cpp, &CPP);
@@ -25,24 +28,29 @@ lazy_static! {
         map.insert(LangType::Dart, &DART);
         map.insert(LangType::Elm, &ELM);
         map.insert(LangType::Erlang, &ERLANG);
+        map.insert(LangType::Fortran, &FORTRAN);
         map.insert(LangType::Fsharp, &FSHARP);
         map.insert(LangType::Go, &GO);
         map.insert(LangType::Graphql, &GRAPHQL);
-        map.insert(LangType::H, &H);
-        map.insert(LangType::Hpp, &HPP);
         map.insert(LangType::Haskell, &HASKELL);
         map.insert(LangType::Html, &HTML);
         map.insert(LangType::Java, &JAVA);
         map.insert(LangType::Javascript, &JAVASCRIPT);
         map.insert(LangType::Json, &JSON);
+        map.insert(LangType::Jsonc, &JSONC);
         map.insert(LangType::Jsonnet, &JSONNET);
         map.insert(LangType::Julia, &JULIA);
         map.insert(LangType::Kotlin, &KOTLIN);
         map.insert(LangType::Lua, &LUA);
         map.insert(LangType::Markdown, &MARKDOWN);
+        map.insert(LangType::Matlab, &MATLAB);
         map.insert(LangType::Nix, &NIX);
+        map.insert(LangType::ObjectiveC, &OBJECTIVE_C);
+        map.insert(LangType::ObjectiveCpp, &OBJECTIVE_CPP);
         map.insert(LangType::Ocaml, &OCAML);
         map.insert(LangType::Php, &PHP);
+        map.insert(LangType::PowerShell, &POWERSHELL);
+        map.insert(LangType::Protobuf, &PROTOBUF);
         map.insert(LangType::Python, &PYTHON);
         map.insert(LangType::Qcl, &QCL);
         map.insert(LangType::Qsharp, &QSHARP);
@@ -56,9 +64,11 @@ lazy_static! {
         map.insert(LangType::Swift, &SWIFT);
         map.insert(LangType::Tcl, &TCL);
         map.insert(LangType::Tex, &TEX);
+        map.insert(LangType::Thrift, &THRIFT);
         map.insert(LangType::Toml, &TOML);
         map.insert(LangType::Typescript, &TYPESCRIPT);
         map.insert(LangType::V, &V);
+        map.insert(LangType::Vue, &VUE);
         map.insert(LangType::WenYan, &WENYAN);
         map.insert(LangType::Xml, &XML);
         map.insert(LangType::Yaml, &YAML);
@@ -91,11 +101,30 @@ lazy_static! {
         map
     };
 
+    /// 分支关键字合成的单个正则，没有分支关键字的语言不登记
+    pub static ref BRANCH_REGEX_MAP: HashMap<LangType, Regex> = {
+        let mut map = HashMap::new();
+
+        for (k, v) in LANGUAGE_DEFINITIONS.iter() {
+            if v.branch_keywords.is_empty() {
+                continue;
+            }
+            let alternatives: Vec<String> = v.branch_keywords.iter().map(|kw| branch_pattern(kw)).collect();
+            map.insert(*k, Regex::new(&alternatives.join("|")).unwrap());
+        }
+
+        map
+    };
+
     pub static ref EXT_LANG_MAP: HashMap<String, LangType> = {
         let mut map = HashMap::new();
 
         for (k, v) in LANGUAGE_DEFINITIONS.iter() {
             for ext in v.extensions.iter() {
+                // 有歧义的扩展名只登记默认语言，其他候选语言由内容决定
+                if alternatives_for_ext(ext).any(|alt| alt == *k) {
+                    continue;
+                }
                 map.insert(ext.to_string(), *k);
             }
         }
@@ -108,14 +137,90 @@ pub fn get_lang_def(lang_type: &LangType) -> Option<&'static LangDef> {
     LANGUAGE_DEFINITIONS.get(lang_type).copied()
 }
 
-pub fn get_function_regex(lang_type: &LangType) -> Option<&RegexSet> {
+pub fn get_function_regex(lang_type: &LangType) -> Option<&'static RegexSet> {
     FUNCTION_REGEX_MAP.get(lang_type)
 }
 
-pub fn get_class_regex(lang_type: &LangType) -> Option<&RegexSet> {
+pub fn get_class_regex(lang_type: &LangType) -> Option<&'static RegexSet> {
     CLASS_REGEX_MAP.get(lang_type)
 }
 
+pub fn get_branch_regex(lang_type: &LangType) -> Option<&'static Regex> {
+    BRANCH_REGEX_MAP.get(lang_type)
+}
+
+/// 关键字首尾是字母数字时在该侧加上词边界，避免 `if` 匹配到 `elif`、`notify` 这类标识符内部
+fn branch_pattern(keyword: &str) -> String {
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let start = if is_word(keyword.chars().next()) { r"\b" } else { "" };
+    let end = if is_word(keyword.chars().last()) { r"\b" } else { "" };
+    format!("{}{}{}", start, regex::escape(keyword), end)
+}
+
 pub fn get_type_from_ext(ext: &str) -> Option<LangType> {
     EXT_LANG_MAP.get(ext).copied()
-}
\ No newline at end of file
+}
+
+/// 语言支持的扩展名（不含点），未注册的语言返回空切片
+pub fn extensions_for(lang: LangType) -> &'static [&'static str] {
+    get_lang_def(&lang).map(|def| def.extensions).unwrap_or(&[])
+}
+
+/// 扩展名对应的语言，可带前导的点，不区分大小写
+pub fn languages_for_extension(ext: &str) -> Option<LangType> {
+    get_type_from_ext(&ext.trim_start_matches('.').to_lowercase())
+}
+
+/// 按扩展名查找语言，`overrides` 中的映射优先于内置的扩展名表
+pub fn get_type_from_ext_with(ext: &str, overrides: &HashMap<String, LangType>) -> Option<LangType> {
+    overrides.get(ext).copied().or_else(|| get_type_from_ext(ext))
+}
+
+/// 按名字查找语言，不区分大小写，如 `php` 对应 `LangType::Php`
+pub fn get_type_from_name(name: &str) -> Option<LangType> {
+    SUPPORTED_LANGUAGES
+        .iter()
+        .find(|variant| variant.eq_ignore_ascii_case(name))
+        .and_then(|variant| variant.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ext_overrides() {
+        let mut overrides = HashMap::new();
+        overrides.insert("inc".to_string(), LangType::Php);
+        overrides.insert("h".to_string(), LangType::Cpp);
+
+        assert_eq!(get_type_from_ext("inc"), None);
+        assert_eq!(get_type_from_ext_with("inc", &overrides), Some(LangType::Php));
+        assert_eq!(get_type_from_ext_with("h", &overrides), Some(LangType::Cpp));
+        assert_eq!(get_type_from_ext_with("rs", &overrides), Some(LangType::Rust));
+    }
+
+    #[test]
+    fn test_type_from_name() {
+        assert_eq!(get_type_from_name("php"), Some(LangType::Php));
+        assert_eq!(get_type_from_name("PowerShell"), Some(LangType::PowerShell));
+        assert_eq!(get_type_from_name("cobol"), None);
+    }
+
+    #[test]
+    fn test_extensions_for() {
+        assert_eq!(extensions_for(LangType::Rust), &["rs"]);
+        assert!(extensions_for(LangType::Unknown).is_empty());
+
+        assert_eq!(languages_for_extension("rs"), Some(LangType::Rust));
+        assert_eq!(languages_for_extension(".RS"), Some(LangType::Rust));
+        assert_eq!(languages_for_extension("xyz"), None);
+    }
+
+    #[test]
+    fn test_idl_extensions() {
+        assert_eq!(get_type_from_ext("proto"), Some(LangType::Protobuf));
+        assert_eq!(get_type_from_ext("thrift"), Some(LangType::Thrift));
+        assert_eq!(get_type_from_ext("avdl"), Some(LangType::Avro));
+    }
+}
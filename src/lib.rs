@@ -10,4 +10,9 @@ pub mod stats;
 pub mod counter;
 pub mod fc;
 pub mod report;
-pub mod saver;
\ No newline at end of file
+pub mod estimate;
+pub mod history;
+pub mod saver;
+
+pub use langs::registry::{extensions_for, languages_for_extension};
+pub use syntax::stream::strip_comments;
@@ -5,6 +5,7 @@ fn main() {
     let mut cli = Cli::new();
 
     match cli.run() {
+        Ok(_) if cli.is_quiet() => (),
         Ok(_) => println!("CLI executed successfully"),
         Err(e) => eprintln!("CLI error: {}", e),
     }
@@ -27,6 +27,8 @@ pub struct Arg {
     parser: Box<dyn ValueParser>,
     value_type: TypeId,
     conflicts: Vec<String>,
+    /// 可选的取值，只用于帮助信息
+    possible_values: Vec<String>,
 }
 
 impl Arg {
@@ -43,6 +45,7 @@ impl Arg {
             parser: Box::new(default_parser),
             value_type: TypeId::of::<String>(),
             conflicts: vec![],
+            possible_values: vec![],
         }
     }
 
@@ -104,6 +107,15 @@ impl Arg {
         self
     }
 
+    pub fn possible_values<I, S>(mut self, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        self.possible_values = values.into_iter().map(|v| v.to_string()).collect();
+        self
+    }
+
     pub fn conflicts_with(mut self, arg_name: &str) -> Self {
         self.conflicts.push(arg_name.to_string());
         self
@@ -148,6 +160,10 @@ impl Arg {
         &self.conflicts
     }
 
+    pub fn get_possible_values(&self) -> &[String] {
+        &self.possible_values
+    }
+
     pub fn is_conflict_with(&self, arg_name: &str) -> bool {
         self.conflicts.contains(&arg_name.to_string())
     }
@@ -164,6 +180,7 @@ impl Debug for Arg {
             .field("required", &self.required)
             .field("value_type", &self.value_type)
             .field("conflicts", &self.conflicts)
+            .field("possible_values", &self.possible_values)
             .finish()
     }
 }
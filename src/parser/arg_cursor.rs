@@ -11,10 +11,6 @@ impl<I: Iterator<Item = String>> ArgCursor<I> {
         }
     }
 
-    pub fn next(&mut self) -> Option<String> {
-        self.iter.next()
-    }
-
     pub fn peek(&mut self) -> Option<&String> {
         self.iter.peek()
     }
@@ -32,4 +28,12 @@ impl<I: Iterator<Item = String>> ArgCursor<I> {
             self.next()
         }
     }
+}
+
+impl<I: Iterator<Item = String>> Iterator for ArgCursor<I> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.iter.next()
+    }
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 use std::any::{Any, TypeId};
 use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
 
 use super::arg::{Arg, ArgAction};
 use super::parse_error::ParseError;
@@ -7,8 +8,14 @@ use super::parse_error::ParseError;
 use crate::config::Config;
 use crate::parser::matches::{Matches};
 use crate::parser::arg_cursor::ArgCursor;
+use crate::parser::suggest::did_you_mean;
 use crate::{extract_config, value_parser};
-use crate::utils::format::OutputFormat;
+use crate::langs::lang_type::LangType;
+use crate::langs::registry::get_type_from_name;
+use crate::report::{GroupBy, SortKey, SortOrder, SORT_KEY_NAMES};
+use crate::utils::chart::ChartType;
+use crate::utils::color::ColorChoice;
+use crate::utils::format::{OutputFormat, OUTPUT_FORMAT_NAMES};
 
 /// 命令行参数解析器，用于定义和解析命令行参数
 ///
@@ -31,15 +38,23 @@ use crate::utils::format::OutputFormat;
 /// # 示例
 ///
 /// ```rust
+/// # use toukei::parser::args_parser::ArgParser;
+/// # use toukei::parser::arg::{Arg, ArgAction};
+/// # use toukei::value_parser;
+/// # fn main() -> Result<(), toukei::parser::parse_error::ParseError> {
 /// let mut parser = ArgParser::new()
 ///     .arg(Arg::new("verbose")
 ///         .short('v')
 ///         .long("verbose")
 ///         .help("启用详细输出")
+///         .parser(value_parser!(bool))
 ///         .action(ArgAction::SetTrue));
 ///
 /// let matches = parser.build_matches(vec!["-v"])?;
 /// let verbose = matches.get_one::<bool>("verbose")?;
+/// # assert!(*verbose);
+/// # Ok(())
+/// # }
 /// ```
 #[derive(Debug)]
 pub struct ArgParser {
@@ -142,12 +157,18 @@ impl ArgParser {
 
         while let Some(arg_str) = cursor.next() { 
             if let Some(long_flag) = arg_str.strip_prefix("--") {
-                self.handle_long_flag(&long_flag, &mut cursor, &mut matches)?;
-                cur_arg = Some(long_flag.to_string());
+                // `--key=value`：等号之后的部分作为值，不再从后续参数中读取
+                let (key, inline) = split_inline_value(long_flag);
+                self.handle_long_flag(key, inline, &mut cursor, &mut matches)?;
+                cur_arg = Some(key.to_string());
             }
             else if let Some(short_flag) = arg_str.strip_prefix("-") {
-                for c in short_flag.chars() {
-                    self.handle_short_flag(c, &mut cursor, &mut matches)?;
+                // `-p=value` 的值属于等号前的最后一个短参数
+                let (flags, inline) = split_inline_value(short_flag);
+                let count = flags.chars().count();
+                for (i, c) in flags.chars().enumerate() {
+                    let value = if i + 1 == count { inline } else { None };
+                    self.handle_short_flag(c, value, &mut cursor, &mut matches)?;
                 }
                 cur_arg = None;
             }
@@ -158,35 +179,204 @@ impl ArgParser {
             }
         }
 
+        self.validate(&matches)?;
         Ok(matches)
     }
 
+    /// 解析后的检查：缺少必需（`required`）的参数，或同时给出了互相冲突（`conflicts_with`）的两个参数时报错
+    fn validate(&self, matches: &Matches) -> Result<(), ParseError> {
+        if let Some(arg) = self.args.values().find(|arg| arg.is_required() && !matches.contains(arg.get_name())) {
+            return Err(ParseError::MissingRequired(Self::flag_name(arg)));
+        }
+        for arg in self.args.values().filter(|arg| matches.contains(arg.get_name())) {
+            if let Some(other) = arg.get_conflicts().iter().find(|name| matches.contains(name)) {
+                let b = self.get_arg(other).map_or_else(|| other.clone(), Self::flag_name);
+                return Err(ParseError::Conflict { a: Self::flag_name(arg), b });
+            }
+        }
+        Ok(())
+    }
+
+    /// 错误信息中参数的写法：优先使用长参数名
+    fn flag_name(arg: &Arg) -> String {
+        match (arg.get_long(), arg.get_short()) {
+            (Some(long), _) => format!("--{}", long),
+            (None, Some(short)) => format!("-{}", short),
+            (None, None) => arg.get_name().to_string(),
+        }
+    }
+
     pub fn parse_matches(&self, matches: &Matches) -> Result<Config, ParseError> {
 
-        let mut config = Config::new();
+        // 先读取配置文件，命令行中显式给出的参数再覆盖文件中的值
+        let mut config = match matches.get_one::<String>("config") {
+            Ok(path) => Config::from_file(path)
+                .map_err(|e| ParseError::ConfigFile { path: path.clone(), msg: e.to_string() })?,
+            Err(_) => Config::new(),
+        };
     
         extract_config!(matches, config, {
             vecs: [
                 paths <- "path",
                 types <- "type",
-                exclude_files <- "exclude-files"
+                exclude_types <- "exclude-type",
+                exclude_files <- "exclude-files",
+                vendor_dirs <- "vendor-dirs",
+                test_patterns <- "test-patterns"
             ],
             scalars: [
                 ignore_blanks <- "ignore-blanks" : bool,
                 ignore_comments <- "ignore-comments" : bool,
+                detect_commented_code <- "detect-commented-code" : bool,
+                strict_utf8 <- "strict-utf8" : bool,
+                skip_header_comments <- "skip-header-comments" : bool,
+                complexity <- "complexity" : bool,
+                todos <- "todos" : bool,
+                check_eol <- "check-eol" : bool,
+                progress <- "progress" : bool,
+                quiet <- "quiet" : bool,
+                verbose <- "verbose" : u8,
+                sniff <- "sniff" : bool,
                 enable_async <- "enable-async" : bool,
+                no_vendor <- "no-vendor" : bool,
+                follow_symlinks <- "follow-links" : bool,
+                parallel_files <- "parallel-files" : bool,
+                parallel_threshold <- "parallel-threshold" : usize,
+                min_code_lines <- "min-code-lines" : usize,
+                by_file <- "by-file" : bool,
+                compact <- "compact" : bool,
+                exclude_comments_from_totals <- "exclude-comments-from-totals" : bool,
+                avg_sizes <- "avg-sizes" : bool,
+                estimate <- "estimate" : bool,
+                group_by <- "group-by" : GroupBy,
+                sort_by <- "sort" : SortKey,
+                chart_type <- "chart-type" : ChartType,
+                color <- "color" : ColorChoice,
+                split_tests <- "split-tests" : bool,
+                split_by_path <- "split-by-path" : bool,
+                verify <- "verify" : bool,
                 num_workers <- "num-workers" : usize,
-                output <- "output" : OutputFormat,
-                help <- "help" : bool
+                io_retries <- "io-retries" : usize,
+                tab_width <- "tab-width" : usize,
+                help <- "help" : bool,
+                version <- "version" : bool
             ]
         });
 
+        if let Ok(formats) = matches.get_one::<Vec<OutputFormat>>("output")
+            .or_else(|_| matches.get_one::<Vec<OutputFormat>>("format"))
+            && let Some(first) = formats.first() {
+            config.output = *first;
+            config.output_formats = formats.clone();
+        }
+
+        if matches.contains("no-color") {
+            config.color = ColorChoice::Never;
+        }
+
+        if matches.contains("comment-blanks-as-comments") {
+            config.blanks_in_comments_are_blank = false;
+        }
+
+        if let Ok(overrides) = matches.get_many::<(LangType, bool)>("comment-blanks") {
+            config.lang_blanks_in_comments = overrides.into_iter().cloned().collect();
+        }
+
+        if matches.contains("string-blanks-as-blanks") {
+            config.blanks_in_strings_are_code = false;
+        }
+
+        if let Ok(commits) = matches.get_one::<usize>("history") {
+            config.history = Some(*commits);
+        }
+
+        // `--cocomo` 与 `--estimate` 相同，沿用 cloc 的叫法
+        if matches.contains("cocomo") {
+            config.estimate = true;
+        }
+
+        if let Ok([a, b, c, d]) = matches.get_one::<[f64; 4]>("cocomo-coefficients") {
+            config.cocomo = config.cocomo.with_coefficients(*a, *b, *c, *d);
+        }
+
+        if let Ok(salary) = matches.get_one::<f64>("salary") {
+            config.cocomo = config.cocomo.with_salary(*salary);
+        }
+
+        if let Ok(overrides) = matches.get_many::<(String, LangType)>("count-ext") {
+            config.ext_overrides = overrides.into_iter().cloned().collect();
+        }
+
+        if let Ok(lang) = matches.get_one::<LangType>("only-lang-content") {
+            config.only_lang_content = Some(*lang);
+        }
+
+        if let Ok(n) = matches.get_one::<usize>("largest") {
+            config.largest = Some(*n);
+        }
+
+        if let Ok(timeout) = matches.get_one::<Duration>("timeout") {
+            config.timeout = Some(*timeout);
+        }
+
+        if let Ok(template) = matches.get_one::<String>("template") {
+            config.template = Some(template.clone());
+        }
+
+        if let Ok(path) = matches.get_one::<String>("output-file") {
+            config.output_file = Some(path.clone());
+        }
+
+        if let Ok(depth) = matches.get_one::<usize>("max-depth") {
+            config.max_depth = Some(*depth);
+        }
+
+        if let Ok(dir) = matches.get_one::<String>("baseline") {
+            config.baseline = Some(dir.clone());
+        }
+
+        // 无法识别的编码直接报错，不在统计时才退回 BOM 检测
+        if let Ok(encoding) = matches.get_one::<String>("encoding") {
+            let label = encoding.trim();
+            if encoding_rs::Encoding::for_label(label.as_bytes()).is_none() {
+                return Err(ParseError::BadValue {
+                    arg: "--encoding".to_string(),
+                    ty: "an encoding label such as shift_jis",
+                    msg: format!("got {}", encoding),
+                });
+            }
+            config.encoding = Some(label.to_string());
+        }
+
+        if let Ok(file) = matches.get_one::<String>("explain") {
+            config.explain = Some(file.clone());
+        }
+
+        if let Ok(order) = matches.get_one::<SortOrder>("order") {
+            config.sort_order = Some(*order);
+        }
+
+        if let Ok(path) = matches.get_one::<String>("chart") {
+            config.chart_output = Some(path.clone());
+        }
+
+        // 指定了第三方目录列表即视为开启排除
+        if matches.contains("vendor-dirs") {
+            config.no_vendor = true;
+        }
+
+        // 指定了测试文件模式即视为开启拆分
+        if matches.contains("test-patterns") {
+            config.split_tests = true;
+        }
+
         Ok(config)
     }
 
     fn handle_long_flag<I>(
         &self, 
         key: &str, 
+        inline: Option<&str>,
         cursor: &mut ArgCursor<I>, 
         matches: &mut Matches
     ) -> Result<(), ParseError>
@@ -194,14 +384,15 @@ impl ArgParser {
         I: Iterator<Item = String> 
     {
         let arg = self.get_arg_by_long(key)
-            .ok_or(ParseError::UnknownFlag(key.to_string()))?;
+            .ok_or_else(|| self.unknown_long_flag(key))?;
 
-        self.act_parse(key, arg, cursor, matches)
+        self.act_parse(key, arg, inline, cursor, matches)
     }
 
     fn handle_short_flag<I>(
         &self, 
         key: char,
+        inline: Option<&str>,
         cursor: &mut ArgCursor<I>, 
         matches: &mut Matches
     ) -> Result<(), ParseError>
@@ -209,9 +400,9 @@ impl ArgParser {
         I: Iterator<Item = String> 
     {
         let arg = self.get_arg_by_short(key)
-            .ok_or(ParseError::UnknownFlag(key.to_string()))?;
+            .ok_or_else(|| self.unknown_short_flag(key))?;
 
-        self.act_parse(&key.to_string(), arg, cursor, matches)
+        self.act_parse(&key.to_string(), arg, inline, cursor, matches)
     }
 
     fn handle_value<I>(
@@ -225,33 +416,41 @@ impl ArgParser {
         I: Iterator<Item = String> 
     {
         if let Some(arg) = self.args.get(cur) {
-            self.act_parse(key, arg, cursor, matches)
+            self.act_parse(key, arg, None, cursor, matches)
         }
         else {
             Err(ParseError::UnknownArg(cur.to_string()))
         }
     }
 
+    /// `inline` 是 `--key=value` 中等号之后的值，为 None 时从后续参数中读取
     fn act_parse<I>(
         &self, 
         key: &str, 
         arg: &Arg,
+        inline: Option<&str>,
         cursor: &mut ArgCursor<I>, 
         matches: &mut Matches
     ) -> Result<(), ParseError>
     where 
         I: Iterator<Item = String> 
     {
+        let mut next_value = || match inline {
+            Some("") => None,
+            Some(value) => Some(value.to_string()),
+            None => cursor.next_if_value(),
+        };
+
         match arg.get_action() {
             ArgAction::Set => {
-                let value = cursor.next_if_value()
+                let value = next_value()
                     .ok_or(ParseError::NoValue(key.to_string()))?;
 
                 let value = arg.parse(&value)?;
                 matches.set(arg.get_name(), value, arg.get_value_type())
             },
             ArgAction::Append => {
-                let value = cursor.next_if_value()
+                let value = next_value()
                     .ok_or(ParseError::NoValue(key.to_string()))?;
 
                 let value = arg.parse(&value)?;
@@ -260,19 +459,36 @@ impl ArgParser {
             ArgAction::Count => {
                 let cnt = matches.try_get_one::<u8>(arg.get_name())
                     .copied()
-                    .unwrap_or(0 as u8);
+                    .unwrap_or(0);
                 let new = cnt.saturating_add(1);
 
                 let value = arg.parse(&new.to_string())?;
                 matches.set(arg.get_name(), value, arg.get_value_type())
             },
             ArgAction::SetTrue => {
-                let value = arg.parse("true")?;
+                // 开关也接受 `--flag=false` 这样的显式写法
+                let value = arg.parse(inline.unwrap_or("true"))?;
                 matches.set(arg.get_name(), value, arg.get_value_type())
             }
         }
     }
 
+    fn unknown_long_flag(&self, key: &str) -> ParseError {
+        let suggestion = did_you_mean(key, self.long_arg.keys().map(|k| k.as_str()))
+            .map(|s| format!("--{}", s));
+
+        ParseError::UnknownFlag { flag: format!("--{}", key), suggestion }
+    }
+
+    fn unknown_short_flag(&self, key: char) -> ParseError {
+        // 单字符之间的编辑距离没有意义，只提示大小写不同的短标志
+        let suggestion = self.short_arg.keys()
+            .find(|c| c.eq_ignore_ascii_case(&key))
+            .map(|c| format!("-{}", c));
+
+        ParseError::UnknownFlag { flag: format!("-{}", key), suggestion }
+    }
+
     fn get_arg_by_long(&self, long: &str) -> Option<&Arg> { 
         self.long_arg.get(long).and_then(|name| self.args.get(name))
     }
@@ -282,6 +498,14 @@ impl ArgParser {
     }
 }
 
+/// 在第一个 `=` 处拆成参数名与内联值，没有 `=` 时值为 None
+fn split_inline_value(flag: &str) -> (&str, Option<&str>) {
+    match flag.split_once('=') {
+        Some((key, value)) => (key, Some(value)),
+        None => (flag, None),
+    }
+}
+
 impl Default for ArgParser {
     fn default() -> Self {
         ArgParser::new()
@@ -291,6 +515,29 @@ impl Default for ArgParser {
                     .help("显示帮助信息")
                     .parser(value_parser!(bool))
                     .action(ArgAction::SetTrue))
+                .arg(Arg::new("version")
+                    .short('V')
+                    .long("version")
+                    .help("显示版本号")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("verbose")
+                    .short('v')
+                    .long("verbose")
+                    .help("输出更详细的日志，可重复：-v 为 info，-vv 为 debug，-vvv 为 trace")
+                    .parser(value_parser!(u8))
+                    .action(ArgAction::Count))
+                .arg(Arg::new("quiet")
+                    .short('q')
+                    .long("quiet")
+                    .help("不输出日志、进度与提示信息，只输出统计结果")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with("verbose"))
+                .arg(Arg::new("config")
+                    .long("config")
+                    .help("从 TOML 配置文件（如 toukei.toml）读取配置，命令行参数优先于文件中的值")
+                    .parser(value_parser!(String)))
                 .arg(Arg::new("path")
                     .short('p')
                     .long("path")
@@ -305,6 +552,12 @@ impl Default for ArgParser {
                     .parser(value_parser!(Vec<String>, |s| {
                         Ok(s.split(',').map(|s| s.trim().to_string()).collect())
                     })))
+                .arg(Arg::new("exclude-type")
+                    .long("exclude-type")
+                    .help("指定不分析的语言类型，多个以逗号分隔，优先于 --type")
+                    .parser(value_parser!(Vec<String>, |s| {
+                        Ok(s.split(',').map(|s| s.trim().to_lowercase()).collect())
+                    })))
                 .arg(Arg::new("exclude-files")
                     .short('e')
                     .long("exclude-files")
@@ -312,6 +565,60 @@ impl Default for ArgParser {
                     .parser(value_parser!(Vec<String>, |s| {
                         Ok(s.split(',').map(|s| s.trim().to_string()).collect())
                     })))
+                .arg(Arg::new("follow-links")
+                    .long("follow-links")
+                    .help("遍历目录时跟随符号链接，已访问过的目录不会重复进入")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("max-depth")
+                    .long("max-depth")
+                    .help("遍历目录的最大深度，相对每个 --path 计算，0 表示只统计该目录下直接包含的文件")
+                    .parser(value_parser!(usize)))
+                .arg(Arg::new("count-ext")
+                    .long("count-ext")
+                    .help("本次运行把扩展名按指定语言统计，格式 <ext>:<lang>，可重复，如 inc:php")
+                    .action(ArgAction::Append)
+                    .parser(value_parser!((String, LangType), |s| {
+                        let (ext, lang) = s.split_once(':')
+                            .ok_or_else(|| ParseError::FromString(format!("expected <ext>:<lang>, got {}", s)))?;
+                        let lang = get_type_from_name(lang.trim())
+                            .ok_or_else(|| ParseError::FromString(format!("unknown language: {}", lang)))?;
+                        Ok((ext.trim().trim_start_matches('.').to_lowercase(), lang))
+                    })))
+                .arg(Arg::new("sniff")
+                    .long("sniff")
+                    .help("扩展名无法识别时，按文件开头的注释与函数定义等语法特征猜测语言")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("only-lang-content")
+                    .long("only-lang-content")
+                    .help("复合文件(.vue/.html)只统计其中指定语言的片段，如 javascript 只统计 <script> 中的代码")
+                    .parser(value_parser!(LangType, |s| {
+                        get_type_from_name(s.trim())
+                            .ok_or_else(|| ParseError::FromString(format!("unknown language: {}", s)))
+                    })))
+                .arg(Arg::new("no-vendor")
+                    .long("no-vendor")
+                    .help("排除常见的第三方依赖目录(node_modules、vendor、third_party 等)")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("vendor-dirs")
+                    .long("vendor-dirs")
+                    .help("覆盖 --no-vendor 使用的目录名列表，多个以逗号分隔，设置后自动开启排除")
+                    .parser(value_parser!(Vec<String>, |s| {
+                        Ok(s.split(',').map(|s| s.trim().to_string()).collect())
+                    })))
+                .arg(Arg::new("split-tests")
+                    .long("split-tests")
+                    .help("按默认测试文件模式拆分测试代码与生产代码")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("test-patterns")
+                    .long("test-patterns")
+                    .help("指定测试文件的 glob 模式，多个以逗号分隔，设置后自动拆分统计")
+                    .parser(value_parser!(Vec<String>, |s| {
+                        Ok(s.split(',').map(|s| s.trim().to_string()).collect())
+                    })))
                 .arg(Arg::new("ignore-blanks")
                     .long("ignore-blanks")
                     .help("忽略空白行")
@@ -322,6 +629,65 @@ impl Default for ArgParser {
                     .help("忽略注释行")
                     .parser(value_parser!(bool))
                     .action(ArgAction::SetTrue))
+                .arg(Arg::new("comment-blanks-as-comments")
+                    .long("comment-blanks-as-comments")
+                    .help("块注释/文档字符串内的空行计为注释行，默认计为空白行")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("comment-blanks")
+                    .long("comment-blanks")
+                    .help("按语言设置块注释内的空行计为空白行还是注释行，格式 <lang>:<blank|comment>，可重复，如 python:comment")
+                    .action(ArgAction::Append)
+                    .parser(value_parser!((LangType, bool), |s| {
+                        let (lang, kind) = s.split_once(':')
+                            .ok_or_else(|| ParseError::FromString(format!("expected <lang>:<blank|comment>, got {}", s)))?;
+                        let lang = get_type_from_name(lang.trim())
+                            .ok_or_else(|| ParseError::FromString(format!("unknown language: {}", lang)))?;
+                        match kind.trim().to_lowercase().as_str() {
+                            "blank" => Ok((lang, true)),
+                            "comment" => Ok((lang, false)),
+                            other => Err(ParseError::FromString(format!("expected blank or comment, got {}", other))),
+                        }
+                    })))
+                .arg(Arg::new("string-blanks-as-blanks")
+                    .long("string-blanks-as-blanks")
+                    .help("跨行字符串内的空行计为空白行，默认计为代码行")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("detect-commented-code")
+                    .long("detect-commented-code")
+                    .help("统计看起来像被注释掉的代码的注释行")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("skip-header-comments")
+                    .long("skip-header-comments")
+                    .help("文件开头的注释块（如许可证头）不计入注释，单独统计为文件头行数")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("complexity")
+                    .long("complexity")
+                    .help("统计 if/for/&& 等分支关键字，在表格后输出各语言的近似圈复杂度")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("todos")
+                    .long("todos")
+                    .help("统计注释中的 TODO/FIXME/HACK/XXX 标记，在表格中增加一列")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("check-eol")
+                    .long("check-eol")
+                    .help("在表格后列出混用 CRLF 与 LF 行尾的文件")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("encoding")
+                    .long("encoding")
+                    .help("按指定编码解码文件，如 shift_jis、gbk；默认只根据 BOM 识别，其余按 UTF-8 处理")
+                    .parser(value_parser!(String)))
+                .arg(Arg::new("strict-utf8")
+                    .long("strict-utf8")
+                    .help("跳过含有非法 UTF-8 序列的文件并给出警告，默认替换为 U+FFFD 后照常统计")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
                 .arg(Arg::new("enable-async")
                     .long("enable-async")
                     .help("启用异步文件统计")
@@ -331,11 +697,174 @@ impl Default for ArgParser {
                     .long("num-workers")
                     .help("指定并发工作线程数,同步模式下为线程数，异步模式下为异步任务数")
                     .parser(value_parser!(usize)))
+                .arg(Arg::new("io-retries")
+                    .long("io-retries")
+                    .help("打开/读取文件遇到 IO 错误时的重试次数，每次等待时间翻倍，适用于网络文件系统")
+                    .parser(value_parser!(usize)))
+                .arg(Arg::new("timeout")
+                    .long("timeout")
+                    .help("指定整次扫描的超时时间(秒)，超时后中止")
+                    .parser(value_parser!(Duration, |s| {
+                        s.parse::<u64>()
+                            .map(Duration::from_secs)
+                            .map_err(|e| ParseError::FromString(e.to_string()))
+                    })))
+                .arg(Arg::new("tab-width")
+                    .long("tab-width")
+                    .help("指定制表符宽度，用于缩进敏感语言；.editorconfig 中的设置优先")
+                    .parser(value_parser!(usize)))
+                .arg(Arg::new("parallel-files")
+                    .long("parallel-files")
+                    .help("对超过阈值的大文件分段并行分析")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("parallel-threshold")
+                    .long("parallel-threshold")
+                    .help("指定分段并行分析的文件大小阈值(字节)，配合 --parallel-files 使用")
+                    .parser(value_parser!(usize)))
+                .arg(Arg::new("min-code-lines")
+                    .long("min-code-lines")
+                    .help("代码行数少于 N 的文件不计入统计，如空的 __init__.py 或只有许可证注释的文件")
+                    .parser(value_parser!(usize)))
+                .arg(Arg::new("split-by-path")
+                    .long("split-by-path")
+                    .help("每个路径单独统计，JSON 输出按路径分开并附带总计")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("verify")
+                    .long("verify")
+                    .help("调试用：同时运行同步与异步统计，逐语言比较结果并输出差异")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("baseline")
+                    .long("baseline")
+                    .help("同时扫描基线目录，只输出目标相对基线各语言的净变化，如比较 fork 与上游")
+                    .parser(value_parser!(String)))
+                .arg(Arg::new("progress")
+                    .long("progress")
+                    .help("扫描时在标准错误输出已处理的文件数，如 120/3400 files")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("explain")
+                    .long("explain")
+                    .help("只分析指定文件，逐行输出分类（Blank/Comment/Code/Mixed）及是否为函数/类定义，用于排查统计结果")
+                    .parser(value_parser!(String)))
+                .arg(Arg::new("history")
+                    .long("history")
+                    .help("统计 git 仓库最近 N 个提交中各语言的代码行数，以 CSV 输出")
+                    .parser(value_parser!(usize)))
+                .arg(Arg::new("group-by")
+                    .long("group-by")
+                    .help("指定汇总维度(language/family)，family 按语言族汇总")
+                    .possible_values(["language", "family"])
+                    .parser(value_parser!(GroupBy)))
+                .arg(Arg::new("sort")
+                    .long("sort")
+                    .help("输出行的排序依据，默认按行数；name 按名字排序")
+                    .possible_values(SORT_KEY_NAMES)
+                    .parser(value_parser!(SortKey)))
+                .arg(Arg::new("order")
+                    .long("order")
+                    .help("排序方向，默认数值降序、名字升序")
+                    .possible_values(["asc", "desc"])
+                    .parser(value_parser!(SortOrder)))
+                .arg(Arg::new("largest")
+                    .long("largest")
+                    .help("在表格后列出行数最多的 N 个文件")
+                    .parser(value_parser!(usize)))
+                .arg(Arg::new("by-file")
+                    .long("by-file")
+                    .help("按文件输出明细表（按行数降序），并列出文件中 // region 标记的区域行数，代替按语言汇总的表格")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("compact")
+                    .long("compact")
+                    .help("文本输出每行一个 `Language: N lines (M code)`，代替表格；终端宽度不足 80 列时自动开启")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("exclude-comments-from-totals")
+                    .long("exclude-comments-from-totals")
+                    .help("输出的行数不含注释行与空行，只表示代码行；与 --ignore-comments 不同，不影响统计本身")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("avg-sizes")
+                    .long("avg-sizes")
+                    .help("在表格后列出各语言平均每个函数/类的代码行数")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("estimate")
+                    .long("estimate")
+                    .help("在表格后输出基于 COCOMO 模型的工作量/工期/成本估算")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("cocomo")
+                    .long("cocomo")
+                    .help("同 --estimate，在文本输出后追加 COCOMO 估算")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("cocomo-coefficients")
+                    .long("cocomo-coefficients")
+                    .help("指定 COCOMO 系数 a,b,c,d，默认 2.4,1.05,2.5,0.38")
+                    .parser(value_parser!([f64; 4], |s| {
+                        let values = s.split(',')
+                            .map(|v| v.trim().parse::<f64>()
+                                .map_err(|e| ParseError::FromString(e.to_string())))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        <[f64; 4]>::try_from(values)
+                            .map_err(|v| ParseError::FromString(format!("expected 4 coefficients, got {}", v.len())))
+                    })))
+                .arg(Arg::new("salary")
+                    .long("salary")
+                    .help("指定估算成本时使用的开发人员年薪，默认 56286")
+                    .parser(value_parser!(f64)))
                 .arg(Arg::new("output")
                     .short('o')
                     .long("output")
-                    .help("指定输出文件")
-                    .parser(value_parser!(OutputFormat)))
+                    .help("指定输出格式(text/json/json-map/jsonl/csv/ci/treemap/md)，多个以逗号分隔")
+                    .possible_values(OUTPUT_FORMAT_NAMES)
+                    .parser(value_parser!(Vec<OutputFormat>, |s| {
+                        s.split(',')
+                            .map(|s| s.trim().parse::<OutputFormat>()
+                                .map_err(ParseError::InvalidOutputFormat))
+                            .collect()
+                    })))
+                .arg(Arg::new("format")
+                    .long("format")
+                    .help("同 --output；ci 每行输出一个 language=lines，便于在 CI 日志中比较")
+                    .possible_values(OUTPUT_FORMAT_NAMES)
+                    .parser(value_parser!(Vec<OutputFormat>, |s| {
+                        s.split(',')
+                            .map(|s| s.trim().parse::<OutputFormat>()
+                                .map_err(ParseError::InvalidOutputFormat))
+                            .collect()
+                    })))
+                .arg(Arg::new("color")
+                    .long("color")
+                    .help("终端输出着色(auto/always/never)，auto 仅在终端且未设置 NO_COLOR 时着色")
+                    .possible_values(["auto", "always", "never"])
+                    .parser(value_parser!(ColorChoice)))
+                .arg(Arg::new("no-color")
+                    .long("no-color")
+                    .help("关闭终端输出着色，等同于 --color never")
+                    .parser(value_parser!(bool))
+                    .action(ArgAction::SetTrue))
+                .arg(Arg::new("template")
+                    .long("template")
+                    .help("按模板逐行输出每种语言，如 \"{language} {lines} {code}\"")
+                    .parser(value_parser!(String)))
+                .arg(Arg::new("output-file")
+                    .long("output-file")
+                    .help("指定输出文件的基础路径，每种格式写入 <base>.<ext>")
+                    .parser(value_parser!(String)))
+                .arg(Arg::new("chart")
+                    .long("chart")
+                    .help("统计完成后将各语言行数绘制为 PNG 图表，写入指定路径")
+                    .parser(value_parser!(String)))
+                .arg(Arg::new("chart-type")
+                    .long("chart-type")
+                    .help("指定图表类型(pie/bar)，默认 pie")
+                    .possible_values(["pie", "bar"])
+                    .parser(value_parser!(ChartType)))
     }
 }
 
@@ -363,6 +892,7 @@ macro_rules! extract_config {
 #[cfg(test)]
 mod tests { 
     use super::*;
+    use crate::estimate::CocomoModel;
     use crate::value_parser;
 
         #[test]
@@ -393,6 +923,35 @@ mod tests {
         assert_eq!(matches.get_one::<String>("path").unwrap(), "/home/user");
     }
 
+    #[test]
+    fn test_inline_values() {
+        let mut arg_parser = ArgParser::default();
+
+        let matches = arg_parser.build_matches(vec!["--output=json", "--path=/a,/b"]).unwrap();
+        let config = arg_parser.parse_matches(&matches).unwrap();
+        assert_eq!(config.output, OutputFormat::Json);
+        assert_eq!(config.paths, ["/a", "/b"]);
+
+        let matches = arg_parser.build_matches(vec!["-p=/c", "--ignore-blanks=false"]).unwrap();
+        let config = arg_parser.parse_matches(&matches).unwrap();
+        assert_eq!(config.paths, ["/c"]);
+        assert!(!config.ignore_blanks);
+
+        // 值中再出现的 `=` 原样保留
+        let mut parser = ArgParser::new();
+        parser.add_arg(Arg::new("define").long("define"));
+        let matches = parser.build_matches(vec!["--define=a=b"]).unwrap();
+        assert_eq!(matches.get_one::<String>("define").unwrap(), "a=b");
+    }
+
+    #[test]
+    fn test_empty_inline_value() {
+        let mut arg_parser = ArgParser::default();
+
+        let err = arg_parser.build_matches(vec!["--path="]).unwrap_err();
+        assert!(matches!(err, ParseError::NoValue(ref key) if key == "path"));
+    }
+
     #[test]
     fn test_multiple_short_flags() {
         let mut parser = ArgParser::new();
@@ -415,13 +974,79 @@ mod tests {
         assert!(matches.get_one::<bool>("debug").is_ok());
     }
 
+    #[test]
+    fn test_conflicting_flags() {
+        let mut parser = ArgParser::new()
+            .arg(Arg::new("quiet")
+                .short('q')
+                .long("quiet")
+                .parser(value_parser!(bool))
+                .action(ArgAction::SetTrue)
+                .conflicts_with("verbose"))
+            .arg(Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .parser(value_parser!(bool))
+                .action(ArgAction::SetTrue));
+
+        let err = parser.build_matches(vec!["-v", "--quiet"]).unwrap_err();
+        assert!(matches!(&err, ParseError::Conflict { a, b } if a == "--quiet" && b == "--verbose"));
+        assert_eq!(err.to_string(), "argument --quiet conflicts with argument --verbose");
+
+        assert!(parser.build_matches(vec!["--quiet"]).is_ok());
+        assert!(parser.build_matches(vec!["-v"]).is_ok());
+    }
+
+    #[test]
+    fn test_missing_required_arg() {
+        let mut parser = ArgParser::new()
+            .arg(Arg::new("path")
+                .short('p')
+                .long("path")
+                .required())
+            .arg(Arg::new("verbose")
+                .short('v')
+                .parser(value_parser!(bool))
+                .action(ArgAction::SetTrue));
+
+        let err = parser.build_matches(vec!["-v"]).unwrap_err();
+        assert!(matches!(&err, ParseError::MissingRequired(name) if name == "--path"));
+    }
+
+    #[test]
+    fn test_required_arg_supplied() {
+        let mut parser = ArgParser::new()
+            .arg(Arg::new("path")
+                .short('p')
+                .long("path")
+                .required());
+
+        let matches = parser.build_matches(vec!["-p", "src"]).unwrap();
+        assert_eq!(matches.get_one::<String>("path").unwrap(), "src");
+    }
+
     #[test]
     fn test_unknown_flag() {
         let mut parser = ArgParser::new();
         let args = vec!["--unknown"];
         let result = parser.build_matches(args);
         assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), ParseError::UnknownFlag(_)));
+        assert!(matches!(result.unwrap_err(), ParseError::UnknownFlag { .. }));
+    }
+
+    #[test]
+    fn test_unknown_flag_suggestion() {
+        let mut parser = ArgParser::default();
+
+        let err = parser.build_matches(vec!["--pdth", "src"]).unwrap_err();
+        assert_eq!(err.suggestion(), Some("--path"));
+        assert_eq!(err.to_string(), "unknown flag: --pdth; did you mean --path?");
+
+        let err = parser.build_matches(vec!["-P", "src"]).unwrap_err();
+        assert_eq!(err.suggestion(), Some("-p"));
+
+        let err = parser.build_matches(vec!["--zzzzzzzz"]).unwrap_err();
+        assert_eq!(err.suggestion(), None);
     }
 
     #[test]
@@ -484,7 +1109,7 @@ mod tests {
         let result = parser.build_matches(args);
         assert!(result.is_ok());
         let matches = result.unwrap();
-        assert_eq!(*matches.get_one::<bool>("debug").unwrap(), true);
+        assert!(*matches.get_one::<bool>("debug").unwrap());
     }
 
     #[test]
@@ -508,7 +1133,301 @@ mod tests {
             num_workers: 4,
             show_stats: false,
             output: OutputFormat::Json,
-            help: false
+            output_formats: vec![OutputFormat::Json],
+            help: false,
+            ..Config::new()
         });
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_multiple_output_formats() {
+        let mut arg_parser = ArgParser::default();
+
+        let args = vec!["--output", "json,csv", "--output-file", "stats"];
+        let matches = arg_parser.build_matches(args).unwrap();
+        let config = arg_parser.parse_matches(&matches).unwrap();
+
+        assert_eq!(config.output, OutputFormat::Json);
+        assert_eq!(config.output_formats, vec![OutputFormat::Json, OutputFormat::Csv]);
+        assert_eq!(config.output_file, Some("stats".to_string()));
+
+        let matches = arg_parser.build_matches(vec!["--output", "json,xml"]);
+        assert!(matches.is_err());
+    }
+
+    #[test]
+    fn test_test_patterns_enable_split() {
+        let mut arg_parser = ArgParser::default();
+
+        let args = vec!["--test-patterns", "spec/**,*_spec.rb"];
+        let matches = arg_parser.build_matches(args).unwrap();
+        let config = arg_parser.parse_matches(&matches).unwrap();
+
+        assert!(config.split_tests);
+        assert_eq!(config.test_patterns, vec!["spec/**".to_string(), "*_spec.rb".to_string()]);
+    }
+
+    #[test]
+    fn test_estimate_coefficients() {
+        let mut arg_parser = ArgParser::default();
+
+        let args = vec!["--estimate", "--cocomo-coefficients", "3.0,1.12,2.5,0.35", "--salary", "100000"];
+        let matches = arg_parser.build_matches(args).unwrap();
+        let config = arg_parser.parse_matches(&matches).unwrap();
+
+        assert!(config.estimate);
+        assert_eq!(config.cocomo, CocomoModel::default()
+            .with_coefficients(3.0, 1.12, 2.5, 0.35)
+            .with_salary(100_000.0));
+
+        let matches = arg_parser.build_matches(vec!["--cocomo-coefficients", "1,2,3"]);
+        assert!(matches.is_err());
+
+        let matches = arg_parser.build_matches(vec!["--cocomo"]).unwrap();
+        assert!(arg_parser.parse_matches(&matches).unwrap().estimate);
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut arg_parser = ArgParser::default();
+
+        let matches = arg_parser.build_matches(vec!["--sort", "name", "--order", "desc"]).unwrap();
+        let config = arg_parser.parse_matches(&matches).unwrap();
+        assert_eq!(config.sort_by, SortKey::Name);
+        assert_eq!(config.sort_order, Some(SortOrder::Desc));
+
+        let config = Config::new();
+        assert_eq!(config.sort_by, SortKey::Lines);
+        assert_eq!(config.sort_order, None);
+
+        assert!(arg_parser.build_matches(vec!["--sort", "size"]).is_err());
+        assert!(arg_parser.build_matches(vec!["--order", "up"]).is_err());
+    }
+
+    #[test]
+    fn test_group_by() {
+        let mut arg_parser = ArgParser::default();
+
+        let matches = arg_parser.build_matches(vec!["--group-by", "family"]).unwrap();
+        let config = arg_parser.parse_matches(&matches).unwrap();
+        assert_eq!(config.group_by, GroupBy::Family);
+
+        assert!(arg_parser.build_matches(vec!["--group-by", "team"]).is_err());
+    }
+
+    #[test]
+    fn test_encoding() {
+        let mut arg_parser = ArgParser::default();
+
+        let matches = arg_parser.build_matches(vec!["--encoding", "shift_jis"]).unwrap();
+        assert_eq!(arg_parser.parse_matches(&matches).unwrap().encoding.as_deref(), Some("shift_jis"));
+        assert_eq!(Config::new().encoding, None);
+
+        let matches = arg_parser.build_matches(vec!["--encoding", " GBK "]).unwrap();
+        assert_eq!(arg_parser.parse_matches(&matches).unwrap().encoding.as_deref(), Some("GBK"));
+
+        let matches = arg_parser.build_matches(vec!["--encoding", "klingon"]).unwrap();
+        let err = arg_parser.parse_matches(&matches).unwrap_err();
+        assert!(matches!(err, ParseError::BadValue { ref arg, .. } if arg == "--encoding"));
+        assert!(err.to_string().contains("klingon"));
+    }
+
+    #[test]
+    fn test_only_lang_content() {
+        let mut arg_parser = ArgParser::default();
+
+        let matches = arg_parser.build_matches(vec!["--only-lang-content", "javascript"]).unwrap();
+        let config = arg_parser.parse_matches(&matches).unwrap();
+        assert_eq!(config.only_lang_content, Some(LangType::Javascript));
+
+        assert!(arg_parser.build_matches(vec!["--only-lang-content", "klingon"]).is_err());
+    }
+
+    #[test]
+    fn test_exclude_type() {
+        let mut arg_parser = ArgParser::default();
+
+        let matches = arg_parser.build_matches(vec!["--exclude-type", "JSON, yaml"]).unwrap();
+        let config = arg_parser.parse_matches(&matches).unwrap();
+        assert_eq!(config.exclude_types, vec!["json".to_string(), "yaml".to_string()]);
+        assert_eq!(config.types, Config::new().types);
+    }
+
+    #[test]
+    fn test_complexity() {
+        let mut arg_parser = ArgParser::default();
+
+        let matches = arg_parser.build_matches(vec!["--complexity"]).unwrap();
+        assert!(arg_parser.parse_matches(&matches).unwrap().complexity);
+        assert!(!Config::new().complexity);
+    }
+
+    #[test]
+    fn test_todos() {
+        let mut arg_parser = ArgParser::default();
+
+        let matches = arg_parser.build_matches(vec!["--todos"]).unwrap();
+        assert!(arg_parser.parse_matches(&matches).unwrap().todos);
+        assert!(!Config::new().todos);
+        assert_eq!(Config::new().todo_markers, vec!["TODO", "FIXME", "HACK", "XXX"]);
+    }
+
+    #[test]
+    fn test_verbose_and_quiet() {
+        use log::LevelFilter;
+
+        let mut arg_parser = ArgParser::default();
+        let config = Config::new();
+        assert_eq!((config.verbose, config.quiet), (0, false));
+        assert_eq!(config.log_level(), LevelFilter::Warn);
+
+        for (args, level) in [
+            (vec!["-v"], LevelFilter::Info),
+            (vec!["-vv"], LevelFilter::Debug),
+            (vec!["--verbose", "-vv"], LevelFilter::Trace),
+            (vec!["-vvvv"], LevelFilter::Trace),
+            (vec!["--quiet"], LevelFilter::Off),
+        ] {
+            let matches = arg_parser.build_matches(args.clone()).unwrap();
+            assert_eq!(arg_parser.parse_matches(&matches).unwrap().log_level(), level, "{:?}", args);
+        }
+
+        assert!(arg_parser.build_matches(vec!["-q", "-v"]).is_err());
+    }
+
+    #[test]
+    fn test_version() {
+        let mut arg_parser = ArgParser::default();
+
+        let matches = arg_parser.build_matches(vec!["--version"]).unwrap();
+        assert!(arg_parser.parse_matches(&matches).unwrap().version);
+
+        let matches = arg_parser.build_matches(vec!["-V"]).unwrap();
+        assert!(arg_parser.parse_matches(&matches).unwrap().version);
+        assert!(!Config::new().version);
+    }
+
+    #[test]
+    fn test_string_blanks_as_blanks() {
+        let mut arg_parser = ArgParser::default();
+
+        let matches = arg_parser.build_matches(vec!["--string-blanks-as-blanks"]).unwrap();
+        assert!(!arg_parser.parse_matches(&matches).unwrap().blanks_in_strings_are_code);
+        assert!(Config::new().blanks_in_strings_are_code);
+    }
+
+    #[test]
+    fn test_sniff() {
+        let mut arg_parser = ArgParser::default();
+
+        let matches = arg_parser.build_matches(vec!["--sniff"]).unwrap();
+        assert!(arg_parser.parse_matches(&matches).unwrap().sniff);
+        assert!(!Config::new().sniff);
+    }
+
+    #[test]
+    fn test_check_eol() {
+        let mut arg_parser = ArgParser::default();
+
+        let matches = arg_parser.build_matches(vec!["--check-eol"]).unwrap();
+        assert!(arg_parser.parse_matches(&matches).unwrap().check_eol);
+        assert!(!Config::new().check_eol);
+    }
+
+    #[test]
+    fn test_follow_links() {
+        let mut arg_parser = ArgParser::default();
+
+        let matches = arg_parser.build_matches(vec!["--follow-links"]).unwrap();
+        assert!(arg_parser.parse_matches(&matches).unwrap().follow_symlinks);
+        assert!(!Config::new().follow_symlinks);
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let mut arg_parser = ArgParser::default();
+
+        let matches = arg_parser.build_matches(vec!["--max-depth", "0"]).unwrap();
+        assert_eq!(arg_parser.parse_matches(&matches).unwrap().max_depth, Some(0));
+
+        let matches = arg_parser.build_matches(Vec::<&str>::new()).unwrap();
+        assert_eq!(arg_parser.parse_matches(&matches).unwrap().max_depth, None);
+    }
+
+    #[test]
+    fn test_progress() {
+        let mut arg_parser = ArgParser::default();
+
+        let matches = arg_parser.build_matches(vec!["--progress"]).unwrap();
+        assert!(arg_parser.parse_matches(&matches).unwrap().progress);
+        assert!(!Config::new().progress);
+    }
+
+    #[test]
+    fn test_explain() {
+        let mut arg_parser = ArgParser::default();
+
+        let matches = arg_parser.build_matches(vec!["--explain", "src/main.rs"]).unwrap();
+        let config = arg_parser.parse_matches(&matches).unwrap();
+        assert_eq!(config.explain, Some("src/main.rs".to_string()));
+        assert_eq!(Config::new().explain, None);
+    }
+
+    #[test]
+    fn test_baseline() {
+        let mut arg_parser = ArgParser::default();
+
+        let matches = arg_parser.build_matches(vec!["-p", "fork", "--baseline", "upstream"]).unwrap();
+        let config = arg_parser.parse_matches(&matches).unwrap();
+        assert_eq!(config.baseline, Some("upstream".to_string()));
+        assert_eq!(config.paths, vec!["fork".to_string()]);
+    }
+
+    #[test]
+    fn test_chart() {
+        let mut arg_parser = ArgParser::default();
+
+        let matches = arg_parser.build_matches(vec!["--chart", "out.png", "--chart-type", "bar"]).unwrap();
+        let config = arg_parser.parse_matches(&matches).unwrap();
+        assert_eq!(config.chart_output, Some("out.png".to_string()));
+        assert_eq!(config.chart_type, ChartType::Bar);
+
+        let matches = arg_parser.build_matches(vec!["--chart", "out.png"]).unwrap();
+        let config = arg_parser.parse_matches(&matches).unwrap();
+        assert_eq!(config.chart_type, ChartType::Pie);
+
+        assert!(arg_parser.build_matches(vec!["--chart-type", "line"]).is_err());
+    }
+
+    #[test]
+    fn test_count_ext() {
+        let mut arg_parser = ArgParser::default();
+
+        let args = vec!["--count-ext", "inc:php", "--count-ext", ".tpl:Html"];
+        let matches = arg_parser.build_matches(args).unwrap();
+        let config = arg_parser.parse_matches(&matches).unwrap();
+        assert_eq!(config.ext_overrides, vec![
+            ("inc".to_string(), LangType::Php),
+            ("tpl".to_string(), LangType::Html),
+        ]);
+
+        assert!(arg_parser.build_matches(vec!["--count-ext", "inc"]).is_err());
+        assert!(arg_parser.build_matches(vec!["--count-ext", "inc:cobol"]).is_err());
+    }
+
+    #[test]
+    fn test_comment_blanks_per_lang() {
+        let mut arg_parser = ArgParser::default();
+
+        let args = vec!["--comment-blanks-as-comments", "--comment-blanks", "python:blank", "--comment-blanks", "c:comment"];
+        let matches = arg_parser.build_matches(args).unwrap();
+        let config = arg_parser.parse_matches(&matches).unwrap();
+        assert!(!config.blanks_in_comments_are_blank);
+        assert!(config.blanks_in_comments_are_blank_for(LangType::Python));
+        assert!(!config.blanks_in_comments_are_blank_for(LangType::C));
+        assert!(!config.blanks_in_comments_are_blank_for(LangType::Rust));
+
+        assert!(arg_parser.build_matches(vec!["--comment-blanks", "python"]).is_err());
+        assert!(arg_parser.build_matches(vec!["--comment-blanks", "python:code"]).is_err());
+    }
+}
@@ -28,6 +28,12 @@ impl MatchedArg {
     }
 }
 
+impl Default for MatchedArg {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 
 #[derive(Debug, Default)]
 pub struct Matches {
@@ -66,7 +72,7 @@ impl Matches {
             self.types.insert(name.to_string(), value_type);
         }
 
-        self.values.entry(name.to_string()).or_insert_with(MatchedArg::new).push(value);
+        self.values.entry(name.to_string()).or_default().push(value);
 
         Ok(())
     }
@@ -280,6 +286,6 @@ mod tests {
         
         assert_eq!(*matches.get_one::<i32>("number").unwrap(), 42);
         assert_eq!(matches.get_one::<String>("text").unwrap(), "hello");
-        assert_eq!(*matches.get_one::<bool>("flag").unwrap(), true);
+        assert!(*matches.get_one::<bool>("flag").unwrap());
     }
 }
@@ -4,4 +4,5 @@ pub mod value_parser;
 pub mod parse_error;
 pub mod matches;
 pub mod any_value;
-pub mod arg_cursor;
\ No newline at end of file
+pub mod arg_cursor;
+pub mod suggest;
\ No newline at end of file
@@ -7,11 +7,14 @@ pub enum ParseError {
     UnknownArg(String),
     InternalError(String),
     MissingRequired(String),
-    UnknownFlag(String),
+    /// 未知的标志，`flag` 带有 `-`/`--` 前缀，`suggestion` 为最接近的已知标志
+    UnknownFlag { flag: String, suggestion: Option<String> },
     InvalidOutputFormat(String),
     NoValue(String),
     BadValue { arg: String, ty: &'static str, msg: String },
     Conflict { a: String, b: String },
+    /// `--config` 指定的配置文件读取或解析失败
+    ConfigFile { path: String, msg: String },
 }
 
 impl std::fmt::Display for ParseError {
@@ -26,7 +29,8 @@ impl std::fmt::Display for ParseError {
             ParseError::InternalError(msg) => write!(f, "internal error: {}", msg),
             ParseError::UnknownArg(arg) => write!(f, "unknown argument: {}", arg),
             ParseError::MissingRequired(arg) => write!(f, "missing required argument: {}", arg),
-            ParseError::UnknownFlag(flag) => write!(f, "unknown flag: {}", flag),
+            ParseError::UnknownFlag { flag, suggestion: Some(s) } => write!(f, "unknown flag: {}; did you mean {}?", flag, s),
+            ParseError::UnknownFlag { flag, suggestion: None } => write!(f, "unknown flag: {}", flag),
             ParseError::InvalidOutputFormat(format) => write!(f, "invalid output format: {}", format),
             ParseError::NoValue(arg) => write!(f, "no value provided for argument: {}", arg),
             ParseError::BadValue { arg, ty, msg } => write!(
@@ -39,6 +43,17 @@ impl std::fmt::Display for ParseError {
                 "argument {} conflicts with argument {}",
                 a, b
             ),
+            ParseError::ConfigFile { path, msg } => write!(f, "failed to load config file {}: {}", path, msg),
+        }
+    }
+}
+
+impl ParseError {
+    /// 返回错误附带的修正建议（如果有）
+    pub fn suggestion(&self) -> Option<&str> {
+        match self {
+            ParseError::UnknownFlag { suggestion, .. } => suggestion.as_deref(),
+            _ => None,
         }
     }
 }
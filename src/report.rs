@@ -1,11 +1,171 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
+use serde::Serialize;
+
+use crate::counter::CounterError;
+use crate::estimate::{CocomoEstimate, CocomoModel};
+use crate::langs::families::family_of;
 use crate::langs::lang_type::LangType;
-use crate::stats::{LangStat, FileStat};
+use crate::stats::{BucketStat, LangStat, FileStat, TestSplit};
+use crate::utils::path_match::PathMatcher;
+use crate::walker::is_glob;
+
+/// 输出时的汇总维度
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GroupBy {
+    #[default]
+    Language,
+    Family,
+}
+
+impl GroupBy {
+    /// 输出表头中使用的列名
+    pub fn label(&self) -> &'static str {
+        match self {
+            GroupBy::Language => "Language",
+            GroupBy::Family => "Family",
+        }
+    }
+}
+
+impl FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "language" => Ok(GroupBy::Language),
+            "family" => Ok(GroupBy::Family),
+            _ => Err(format!("Invalid group: {}", s)),
+        }
+    }
+}
+
+/// 输出行的排序依据：数值列按降序，`Name` 按名字升序
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortKey {
+    #[default]
+    Lines,
+    Code,
+    Comments,
+    Files,
+    Name,
+}
+
+impl SortKey {
+    /// 未指定方向时的默认排序方向
+    pub fn default_order(&self) -> SortOrder {
+        match self {
+            SortKey::Name => SortOrder::Asc,
+            _ => SortOrder::Desc,
+        }
+    }
+
+    /// 按默认方向比较两个输出行
+    pub fn compare(&self, a: &(String, BucketStat), b: &(String, BucketStat)) -> Ordering {
+        self.compare_in(self.default_order(), a, b)
+    }
+
+    /// 按指定方向比较两个输出行，数值相同时总是按名字升序，保证顺序稳定
+    pub fn compare_in(&self, order: SortOrder, a: &(String, BucketStat), b: &(String, BucketStat)) -> Ordering {
+        let by_name = a.0.cmp(&b.0);
+        let value = |row: &(String, BucketStat)| match self {
+            SortKey::Lines => row.1.lines,
+            SortKey::Code => row.1.code,
+            SortKey::Comments => row.1.comments,
+            SortKey::Files => row.1.files,
+            SortKey::Name => 0,
+        };
+        let by_value = value(a).cmp(&value(b));
+        match (self, order) {
+            (SortKey::Name, SortOrder::Asc) => by_name,
+            (SortKey::Name, SortOrder::Desc) => by_name.reverse(),
+            (_, SortOrder::Asc) => by_value.then(by_name),
+            (_, SortOrder::Desc) => by_value.reverse().then(by_name),
+        }
+    }
+}
+
+/// `--sort` 可选的排序依据
+pub const SORT_KEY_NAMES: &[&str] = &["lines", "code", "comments", "files", "name"];
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lines" => Ok(SortKey::Lines),
+            "code" => Ok(SortKey::Code),
+            "comments" => Ok(SortKey::Comments),
+            "files" => Ok(SortKey::Files),
+            "name" => Ok(SortKey::Name),
+            _ => Err(format!("Invalid sort key: {}", s)),
+        }
+    }
+}
+
+/// 输出行的排序方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl FromStr for SortOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "asc" => Ok(SortOrder::Asc),
+            "desc" => Ok(SortOrder::Desc),
+            _ => Err(format!("Invalid sort order: {}", s)),
+        }
+    }
+}
+
+/// 文件被跳过的原因
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// 文件开头含有 NUL，视为二进制文件
+    Binary,
+    /// `--strict-utf8` 下含有非法的 UTF-8 序列
+    DecodeError,
+    /// 其他统计错误（如读取失败），附带错误信息
+    Other(String),
+}
+
+impl SkipReason {
+    /// 统计出错时对应的跳过原因，二进制与非法编码以外的错误归为 `Other`
+    pub fn from_error(err: &CounterError) -> Self {
+        match err {
+            CounterError::BinaryFile => SkipReason::Binary,
+            CounterError::InvalidUtf8 => SkipReason::DecodeError,
+            other => SkipReason::Other(other.to_string()),
+        }
+    }
+}
+
+/// 扫描中被跳过的文件及原因
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkipWarning {
+    pub path: String,
+    pub reason: SkipReason,
+}
 
 #[derive(Debug, Clone, Default)]
 pub struct Report {
     pub inner: HashMap<LangType, LangStat>,
+    /// 输出时按语言还是语言族汇总，不影响 `inner` 中的数据
+    pub group_by: GroupBy,
+    /// 输出行的排序依据，不影响 `inner` 中的数据
+    pub sort: SortKey,
+    /// 输出行的排序方向，未指定时使用 `sort` 的默认方向
+    pub order: Option<SortOrder>,
+    /// 被跳过的文件，按路径排序
+    pub warnings: Vec<SkipWarning>,
 }
 
 pub type StatItem<'a> = (&'a LangType, &'a LangStat);
@@ -14,8 +174,24 @@ impl Report {
     pub fn new() -> Self {
         Report {
             inner: HashMap::new(),
+            group_by: GroupBy::Language,
+            sort: SortKey::Lines,
+            order: None,
+            warnings: Vec::new(),
         }
     }
+
+    pub fn with_group_by(mut self, group_by: GroupBy) -> Self {
+        self.group_by = group_by;
+        self
+    }
+
+    /// 设置输出行的排序依据与方向，`order` 为 None 时使用 `sort` 的默认方向
+    pub fn with_sort(mut self, sort: SortKey, order: Option<SortOrder>) -> Self {
+        self.sort = sort;
+        self.order = order;
+        self
+    }
 }
 
 impl Report {
@@ -23,9 +199,52 @@ impl Report {
         self.inner.get(lang)
     }
 
+    /// 没有统计到任何文件
+    pub fn is_empty(&self) -> bool {
+        self.file_count() == 0
+    }
+
+    /// 所有语言的文件总数
+    pub fn file_count(&self) -> usize {
+        self.inner.values().map(|s| s.files).sum()
+    }
+
+    /// 统计到的语言种数
+    pub fn language_count(&self) -> usize {
+        self.inner.values().filter(|s| s.files > 0).count()
+    }
+
+    /// 所有语言中行数最多的 `n` 个文件，行数相同时按路径排序
+    pub fn largest_files(&self, n: usize) -> Vec<&FileStat> {
+        let mut files = self.files_sorted();
+        files.truncate(n);
+        files
+    }
+
+    /// 同时含有 CRLF 与 LF 行尾的文件，按路径排序
+    pub fn mixed_eol_files(&self) -> Vec<&FileStat> {
+        let mut files: Vec<&FileStat> = self.inner
+            .values()
+            .flat_map(|lang_stat| lang_stat.stats.iter())
+            .filter(|stat| stat.mixed_eol)
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        files
+    }
+
+    /// 所有文件按行数降序排列，行数相同时按路径排序
+    pub fn files_sorted(&self) -> Vec<&FileStat> {
+        let mut files: Vec<&FileStat> = self.inner
+            .values()
+            .flat_map(|lang_stat| lang_stat.stats.iter())
+            .collect();
+        files.sort_by(|a, b| b.lines.cmp(&a.lines).then_with(|| a.path.cmp(&b.path)));
+        files
+    }
+
     pub fn add(&mut self, stat: FileStat) {
         let lang = stat.lang;
-        let lang_stat = self.inner.entry(lang.clone()).or_insert_with(|| LangStat::new(lang));
+        let lang_stat = self.inner.entry(lang).or_insert_with(|| LangStat::new(lang));
 
         lang_stat.files += 1;
         lang_stat.lines += stat.lines;
@@ -33,11 +252,174 @@ impl Report {
         lang_stat.comments += stat.comments;
         lang_stat.blanks += stat.blanks;
         lang_stat.functions += stat.functions;
+        lang_stat.function_lines += stat.function_lines;
         lang_stat.classes += stat.classes;
+        lang_stat.complexity += stat.complexity;
+        lang_stat.commented_code += stat.commented_code;
+        lang_stat.special_comments += stat.special_comments;
+        lang_stat.todos += stat.todos;
+        lang_stat.header_lines += stat.header_lines;
         
         lang_stat.stats.push(stat);
     }
 
+    /// 合并另一次扫描的报告：同一语言的统计相加，逐文件统计依次拼接，警告按路径合并
+    pub fn merge(&mut self, other: Report) {
+        for (lang, stat) in other.inner {
+            *self.inner.entry(lang).or_insert_with(|| LangStat::new(lang)) += stat;
+        }
+        for warning in other.warnings {
+            self.add_warning(warning);
+        }
+    }
+
+    /// 记录一个被跳过的文件，保持 `warnings` 按路径有序
+    pub fn add_warning(&mut self, warning: SkipWarning) {
+        let pos = self.warnings.partition_point(|w| w.path <= warning.path);
+        self.warnings.insert(pos, warning);
+    }
+
+    /// 输出用的视图：`lines` 减去注释行与空行，使"行数"只表示代码行；原报告不受影响
+    pub fn excluding_comments_from_lines(&self) -> Report {
+        let adjust = |lines: &mut usize, comments: usize, blanks: usize| {
+            *lines = lines.saturating_sub(comments + blanks);
+        };
+
+        let mut report = self.clone();
+        for lang_stat in report.inner.values_mut() {
+            adjust(&mut lang_stat.lines, lang_stat.comments, lang_stat.blanks);
+            for stat in lang_stat.stats.iter_mut() {
+                adjust(&mut stat.lines, stat.comments, stat.blanks);
+            }
+            if let Some(split) = lang_stat.test_split.as_mut() {
+                for bucket in [&mut split.code, &mut split.test] {
+                    adjust(&mut bucket.lines, bucket.comments, bucket.blanks);
+                }
+            }
+        }
+        report
+    }
+
+    /// 根据文件路径把每种语言的统计拆分为生产代码与测试代码
+    ///
+    /// 模式匹配的是文件相对于扫描路径 `roots` 的路径，扫描路径本身位于 `tests` 之类的目录下时不受影响；
+    /// 不在任何扫描路径下的文件按完整路径匹配。
+    pub fn split_tests<S: AsRef<str>, R: AsRef<str>>(&mut self, patterns: &[S], roots: &[R]) -> Result<(), String> {
+        let matcher = PathMatcher::new(patterns)?;
+
+        for lang_stat in self.inner.values_mut() {
+            let mut split = TestSplit::default();
+            for stat in lang_stat.stats.iter() {
+                if matcher.is_match(&relative_to_roots(&stat.path, roots)) {
+                    split.test.add(stat);
+                } else {
+                    split.code.add(stat);
+                }
+            }
+            lang_stat.test_split = Some(split);
+        }
+
+        Ok(())
+    }
+
+}
+
+/// `path` 相对于所在扫描路径的部分，取最长的匹配；glob 扫描路径取第一个含通配符的部分之前的目录
+fn relative_to_roots<R: AsRef<str>>(path: &str, roots: &[R]) -> String {
+    let path = Path::new(path);
+    roots
+        .iter()
+        .map(|root| {
+            let root = root.as_ref();
+            if is_glob(root) {
+                Path::new(root).components().take_while(|c| !is_glob(&c.as_os_str().to_string_lossy())).collect()
+            } else {
+                PathBuf::from(root)
+            }
+        })
+        .filter_map(|root| path.strip_prefix(&root).ok().map(|rel| (root.components().count(), rel)))
+        .max_by_key(|(depth, _)| *depth)
+        .map_or(path, |(_, rel)| rel)
+        .display()
+        .to_string()
+}
+
+impl Report {
+    /// 按 `group_by` 汇总后的输出行，按报告的 `sort` 与 `order` 排列（默认行数降序）
+    pub fn rows(&self) -> Vec<(String, BucketStat)> {
+        self.rows_sorted(self.sort, self.order.unwrap_or(self.sort.default_order()))
+    }
+
+    /// 按 `group_by` 汇总后的输出行，按 `sort` 的默认方向排列
+    pub fn rows_by(&self, sort: SortKey) -> Vec<(String, BucketStat)> {
+        self.rows_sorted(sort, sort.default_order())
+    }
+
+    /// 按 `group_by` 汇总后的输出行，按 `sort` 与 `order` 排列
+    pub fn rows_sorted(&self, sort: SortKey, order: SortOrder) -> Vec<(String, BucketStat)> {
+        let mut rows: Vec<(String, BucketStat)> = match self.group_by {
+            GroupBy::Language => self.inner
+                .iter()
+                .map(|(lang, stat)| (lang.to_string(), BucketStat::from(stat)))
+                .collect(),
+            GroupBy::Family => {
+                let mut families: HashMap<&str, BucketStat> = HashMap::new();
+                for (lang, stat) in &self.inner {
+                    *families.entry(family_of(*lang)).or_default() += BucketStat::from(stat);
+                }
+                families.into_iter().map(|(family, stat)| (family.to_string(), stat)).collect()
+            }
+        };
+        rows.sort_by(|a, b| sort.compare_in(order, a, b));
+        rows
+    }
+}
+
+/// 按扫描路径分别统计的多份报告，顺序与配置的路径一致
+#[derive(Debug, Clone, Default)]
+pub struct MultiReport {
+    pub reports: Vec<(String, Report)>,
+}
+
+impl MultiReport {
+    pub fn new() -> Self {
+        MultiReport {
+            reports: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, path: impl Into<String>, report: Report) {
+        self.reports.push((path.into(), report));
+    }
+
+    /// 所有路径合并后的报告
+    pub fn total(&self) -> Report {
+        let mut total = Report::new();
+        for (_, report) in &self.reports {
+            total.group_by = report.group_by;
+            total.sort = report.sort;
+            total.order = report.order;
+            for lang_stat in report.inner.values() {
+                for stat in &lang_stat.stats {
+                    total.add(stat.clone());
+                }
+            }
+            for warning in &report.warnings {
+                total.add_warning(warning.clone());
+            }
+        }
+        total
+    }
+}
+
+// 不导入 `Add`：在作用域内时按值接收者优先，会遮蔽固有方法 `Report::add(FileStat)`
+impl std::ops::Add for Report {
+    type Output = Report;
+
+    fn add(mut self, other: Report) -> Report {
+        self.merge(other);
+        self
+    }
 }
 
 impl<'a> IntoIterator for &'a Report {
@@ -58,4 +440,401 @@ impl Report {
         items.sort_by(cmp);
         items
     }
+
+    /// 按行数降序，行数相同时按语言名排序
+    pub fn sort_by_lines_desc(&self) -> Vec<StatItem<'_>> {
+        self.sort_stats(|a, b| b.1.lines.cmp(&a.1.lines).then_with(|| by_name(a, b)))
+    }
+
+    /// 按代码行数降序，行数相同时按语言名排序
+    pub fn sort_by_code_desc(&self) -> Vec<StatItem<'_>> {
+        self.sort_stats(|a, b| b.1.code.cmp(&a.1.code).then_with(|| by_name(a, b)))
+    }
+
+    /// 按语言名排序
+    pub fn sort_by_name(&self) -> Vec<StatItem<'_>> {
+        self.sort_stats(by_name)
+    }
+}
+
+fn by_name(a: &StatItem<'_>, b: &StatItem<'_>) -> std::cmp::Ordering {
+    a.0.to_string().cmp(&b.0.to_string())
+}
+
+/// 目录树中的一个节点，`lines` 为该目录下（含子目录）所有文件的行数之和
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DirNode {
+    pub dir: String,
+    pub lines: usize,
+    /// 子目录，按名字排序
+    pub children: Vec<DirNode>,
+}
+
+impl DirNode {
+    fn new(dir: impl Into<String>) -> Self {
+        DirNode {
+            dir: dir.into(),
+            ..Default::default()
+        }
+    }
+
+    /// 按名字查找直接子目录
+    pub fn child(&self, dir: &str) -> Option<&DirNode> {
+        self.children.iter().find(|c| c.dir == dir)
+    }
+
+    fn insert(&mut self, dirs: &[String], lines: usize) {
+        self.lines += lines;
+        let Some((first, rest)) = dirs.split_first() else {
+            return;
+        };
+        let pos = match self.children.binary_search_by(|c| c.dir.as_str().cmp(first)) {
+            Ok(pos) => pos,
+            Err(pos) => {
+                self.children.insert(pos, DirNode::new(first.clone()));
+                pos
+            }
+        };
+        self.children[pos].insert(rest, lines);
+    }
+}
+
+impl Report {
+    /// 按 `FileStat.path` 所在目录逐级汇总行数，供 treemap 等可视化使用
+    ///
+    /// 根节点是所有文件共同的上级目录，没有共同目录时为 `.`。
+    pub fn dir_tree(&self) -> DirNode {
+        let files: Vec<(Vec<String>, usize)> = self.inner
+            .values()
+            .flat_map(|lang_stat| lang_stat.stats.iter())
+            .map(|stat| {
+                let dirs = Path::new(&stat.path)
+                    .parent()
+                    .map(|dir| dir.iter().map(|c| c.to_string_lossy().into_owned()).collect())
+                    .unwrap_or_default();
+                (dirs, stat.lines)
+            })
+            .collect();
+
+        let common = files.iter()
+            .map(|(dirs, _)| dirs.as_slice())
+            .reduce(|a, b| {
+                let n = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+                &a[..n]
+            })
+            .unwrap_or_default();
+        let prefix = common.len();
+
+        let root_name = if prefix == 0 {
+            ".".to_string()
+        } else {
+            common.iter().collect::<PathBuf>().display().to_string()
+        };
+        let mut root = DirNode::new(root_name);
+        for (dirs, lines) in &files {
+            root.insert(&dirs[prefix..], *lines);
+        }
+        root
+    }
+}
+
+/// 两份报告中某个语言某项指标的差异
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Discrepancy {
+    pub lang: LangType,
+    pub field: &'static str,
+    pub left: usize,
+    pub right: usize,
+}
+
+impl std::fmt::Display for Discrepancy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}: {} != {}", self.lang, self.field, self.left, self.right)
+    }
+}
+
+impl Report {
+    /// 逐语言逐指标比较两份报告，只在一侧出现的语言按另一侧为 0 处理；结果按语言名排序
+    pub fn diff(&self, other: &Report) -> Vec<Discrepancy> {
+        let empty = LangStat::default();
+        let mut langs: Vec<&LangType> = self.inner.keys().chain(other.inner.keys()).collect();
+        langs.sort_by_key(|lang| lang.to_string());
+        langs.dedup();
+
+        let mut out = Vec::new();
+        for lang in langs {
+            let left = self.inner.get(lang).unwrap_or(&empty);
+            let right = other.inner.get(lang).unwrap_or(&empty);
+            let fields = [
+                ("files", left.files, right.files),
+                ("lines", left.lines, right.lines),
+                ("code", left.code, right.code),
+                ("comments", left.comments, right.comments),
+                ("blanks", left.blanks, right.blanks),
+                ("functions", left.functions, right.functions),
+                ("classes", left.classes, right.classes),
+                ("commented_code", left.commented_code, right.commented_code),
+                ("special_comments", left.special_comments, right.special_comments),
+                ("todos", left.todos, right.todos),
+                ("header_lines", left.header_lines, right.header_lines),
+            ];
+            for (field, l, r) in fields {
+                if l != r {
+                    out.push(Discrepancy { lang: *lang, field, left: l, right: r });
+                }
+            }
+        }
+        out
+    }
+}
+
+/// 相对基线某个语言的净变化，正数为新增，负数为减少
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LangDelta {
+    pub lang: LangType,
+    pub files: i64,
+    pub lines: i64,
+    pub code: i64,
+    pub comments: i64,
+    pub blanks: i64,
+    pub functions: i64,
+    pub classes: i64,
+}
+
+impl Report {
+    /// 本报告相对 `baseline` 的逐语言净变化，基于 [`Report::diff`]；没有变化的语言不列出，按语言名排序
+    pub fn delta(&self, baseline: &Report) -> Vec<LangDelta> {
+        let mut out: Vec<LangDelta> = Vec::new();
+        for d in baseline.diff(self) {
+            if out.last().is_none_or(|last| last.lang != d.lang) {
+                out.push(LangDelta { lang: d.lang, ..LangDelta::default() });
+            }
+            let change = d.right as i64 - d.left as i64;
+            let delta = out.last_mut().unwrap();
+            match d.field {
+                "files" => delta.files = change,
+                "lines" => delta.lines = change,
+                "code" => delta.code = change,
+                "comments" => delta.comments = change,
+                "blanks" => delta.blanks = change,
+                "functions" => delta.functions = change,
+                "classes" => delta.classes = change,
+                // 其余指标不在净变化表中
+                _ => (),
+            }
+        }
+        out.retain(|d| d != &LangDelta { lang: d.lang, ..LangDelta::default() });
+        out
+    }
+}
+
+impl Report {
+    /// 按默认系数（2.4/1.05/2.5/0.38）对所有语言的代码行数做基本 COCOMO（organic）估算
+    pub fn cocomo(&self) -> CocomoEstimate {
+        self.cocomo_with(&CocomoModel::default())
+    }
+
+    /// 使用指定的模型系数估算
+    pub fn cocomo_with(&self, model: &CocomoModel) -> CocomoEstimate {
+        let code: usize = self.inner.values().map(|stat| stat.code).sum();
+        model.estimate(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(lang: LangType, path: &str) -> FileStat {
+        FileStat {
+            lang,
+            path: path.to_string(),
+            lines: 1,
+            code: 1,
+            ..FileStat::default()
+        }
+    }
+
+    #[test]
+    fn test_empty_report() {
+        let report = Report::new();
+        assert!(report.is_empty());
+        assert_eq!(report.file_count(), 0);
+        assert_eq!(report.language_count(), 0);
+    }
+
+    #[test]
+    fn test_file_and_language_count() {
+        let mut report = Report::new();
+        report.add(file(LangType::Rust, "a.rs"));
+        report.add(file(LangType::Rust, "b.rs"));
+        report.add(file(LangType::Python, "c.py"));
+
+        assert!(!report.is_empty());
+        assert_eq!(report.file_count(), 3);
+        assert_eq!(report.language_count(), 2);
+    }
+
+    #[test]
+    fn test_merge_reports() {
+        let mut a = Report::new();
+        a.add(FileStat { lang: LangType::Rust, path: "a/main.rs".to_string(), lines: 10, code: 8, blanks: 2, ..FileStat::default() });
+        a.add(FileStat { lang: LangType::Python, path: "a/tool.py".to_string(), lines: 5, code: 4, comments: 1, ..FileStat::default() });
+        let mut b = Report::new();
+        b.add(FileStat { lang: LangType::Rust, path: "b/lib.rs".to_string(), lines: 20, code: 15, comments: 5, ..FileStat::default() });
+        b.add(FileStat { lang: LangType::Go, path: "b/main.go".to_string(), lines: 7, code: 7, ..FileStat::default() });
+
+        let merged = a.clone() + b.clone();
+        assert_eq!(merged.file_count(), 4);
+        assert_eq!(merged.language_count(), 3);
+
+        let rust = merged.get_by_lang(&LangType::Rust).unwrap();
+        assert_eq!((rust.files, rust.lines, rust.code, rust.comments, rust.blanks), (2, 30, 23, 5, 2));
+        let paths: Vec<&str> = rust.stats.iter().map(|s| s.path.as_str()).collect();
+        assert_eq!(paths, ["a/main.rs", "b/lib.rs"]);
+        assert_eq!(merged.get_by_lang(&LangType::Python).unwrap().lines, 5);
+        assert_eq!(merged.get_by_lang(&LangType::Go).unwrap().stats.len(), 1);
+
+        a.merge(b);
+        assert!(a.diff(&merged).is_empty());
+    }
+
+    #[test]
+    fn test_cocomo() {
+        let mut report = Report::new();
+        report.add(FileStat { lang: LangType::Rust, path: "main.rs".to_string(), lines: 7000, code: 6000, ..FileStat::default() });
+        report.add(FileStat { lang: LangType::Python, path: "tool.py".to_string(), lines: 4500, code: 4000, ..FileStat::default() });
+
+        // 10 KLOC：2.4 × 10^1.05 人月，2.5 × 工作量^0.38 个月
+        let estimate = report.cocomo();
+        assert!((estimate.effort_months - 26.928).abs() < 1e-3);
+        assert!((estimate.schedule_months - 8.738).abs() < 1e-3);
+        assert!((estimate.developers - 3.082).abs() < 1e-3);
+
+        let custom = report.cocomo_with(&CocomoModel::default().with_coefficients(3.0, 1.12, 2.5, 0.35));
+        assert!((custom.effort_months - 39.548).abs() < 1e-3);
+        assert_eq!(Report::new().cocomo().effort_months, 0.0);
+    }
+
+    #[test]
+    fn test_group_by_family() {
+        let mut report = Report::new();
+        report.add(file(LangType::Javascript, "a.js"));
+        report.add(file(LangType::Typescript, "b.ts"));
+        report.add(file(LangType::Css, "c.css"));
+        report.add(file(LangType::Html, "d.html"));
+        report.add(file(LangType::Rust, "e.rs"));
+
+        let rows = report.with_group_by(GroupBy::Family).rows();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].0, "Web");
+        assert_eq!(rows[0].1.files, 4);
+        assert_eq!(rows[0].1.code, 4);
+        assert_eq!(rows[1].0, "Systems");
+        assert_eq!(rows[1].1.files, 1);
+    }
+
+    #[test]
+    fn test_sort_rows() {
+        let mut report = Report::new();
+        for (lang, path, lines, code, comments) in [
+            (LangType::Rust, "a.rs", 100, 50, 40),
+            (LangType::Python, "b.py", 80, 70, 5),
+            (LangType::Go, "c.go", 20, 15, 2),
+            (LangType::Go, "d.go", 10, 5, 1),
+        ] {
+            report.add(FileStat { lang, path: path.to_string(), lines, code, comments, ..FileStat::default() });
+        }
+
+        let names = |report: &Report| report.rows().into_iter().map(|(name, _)| name).collect::<Vec<_>>();
+        let cases = [
+            (SortKey::Lines, ["Rust", "Python", "Go"]),
+            (SortKey::Code, ["Python", "Rust", "Go"]),
+            (SortKey::Comments, ["Rust", "Python", "Go"]),
+            (SortKey::Files, ["Go", "Python", "Rust"]),
+            (SortKey::Name, ["Go", "Python", "Rust"]),
+        ];
+        for (sort, expected) in cases {
+            let report = report.clone().with_sort(sort, None);
+            assert_eq!(names(&report), expected, "{:?}", sort);
+
+            let order = match sort.default_order() {
+                SortOrder::Asc => SortOrder::Desc,
+                SortOrder::Desc => SortOrder::Asc,
+            };
+            let mut reversed = expected.to_vec();
+            reversed.reverse();
+            // 文件数相同的 Python 与 Rust 仍按名字升序
+            if sort == SortKey::Files {
+                reversed = vec!["Python", "Rust", "Go"];
+            }
+            assert_eq!(names(&report.with_sort(sort, Some(order))), reversed, "{:?}", sort);
+        }
+
+        assert_eq!("comments".parse::<SortKey>(), Ok(SortKey::Comments));
+        assert!("size".parse::<SortKey>().is_err());
+        assert_eq!("asc".parse::<SortOrder>(), Ok(SortOrder::Asc));
+    }
+
+    #[test]
+    fn test_largest_files() {
+        let mut report = Report::new();
+        for (lang, path, lines) in [
+            (LangType::Rust, "a.rs", 10),
+            (LangType::Rust, "b.rs", 40),
+            (LangType::Python, "c.py", 25),
+            (LangType::Python, "d.py", 40),
+            (LangType::Python, "e.py", 5),
+        ] {
+            report.add(FileStat { lang, path: path.to_string(), lines, ..FileStat::default() });
+        }
+
+        let paths: Vec<&str> = report.largest_files(3).iter().map(|s| s.path.as_str()).collect();
+        assert_eq!(paths, vec!["b.rs", "d.py", "c.py"]);
+        assert_eq!(report.largest_files(10).len(), 5);
+    }
+
+    #[test]
+    fn test_sort_helpers_are_stable() {
+        // 每次新建的 HashMap 迭代顺序不同，排序结果应保持一致
+        let build = || {
+            let mut report = Report::new();
+            for (lang, path, lines) in [
+                (LangType::Rust, "a.rs", 10),
+                (LangType::Python, "b.py", 10),
+                (LangType::Go, "c.go", 10),
+                (LangType::Java, "d.java", 20),
+            ] {
+                report.add(FileStat { lang, path: path.to_string(), lines, code: lines, ..FileStat::default() });
+            }
+            report
+        };
+
+        let names = |items: Vec<StatItem<'_>>| items.iter().map(|(l, _)| l.to_string()).collect::<Vec<_>>();
+        let expected = vec!["Java", "Go", "Python", "Rust"];
+        for _ in 0..10 {
+            let report = build();
+            assert_eq!(names(report.sort_by_lines_desc()), expected);
+            assert_eq!(names(report.sort_by_code_desc()), expected);
+            assert_eq!(names(report.sort_by_name()), vec!["Go", "Java", "Python", "Rust"]);
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut left = Report::new();
+        left.add(file(LangType::Rust, "a.rs"));
+        left.add(file(LangType::Python, "b.py"));
+        assert!(left.diff(&left.clone()).is_empty());
+
+        let mut right = left.clone();
+        right.inner.get_mut(&LangType::Rust).unwrap().code += 2;
+        right.add(file(LangType::Go, "c.go"));
+
+        let diff = left.diff(&right);
+        assert_eq!(diff[0], Discrepancy { lang: LangType::Go, field: "files", left: 0, right: 1 });
+        assert!(diff.contains(&Discrepancy { lang: LangType::Rust, field: "code", left: 1, right: 3 }));
+        assert_eq!(diff.last().unwrap().to_string(), "Rust code: 1 != 3");
+    }
 }
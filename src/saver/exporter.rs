@@ -1,5 +1,12 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
 use std::io::Write;
-use crate::report::Report;
+
+use serde::Serialize;
+
+use crate::report::{MultiReport, Report, SkipWarning, SortKey, SortOrder};
+use crate::stats::BucketStat;
+use crate::utils::color::Painter;
 use super::SaveError;
 
 /// 导出策略 Trait
@@ -8,18 +15,52 @@ pub trait ReportExporter {
     fn export(&self, report: &Report, writer: &mut dyn Write) -> Result<(), SaveError>;
 }
 
+/// `json-map` 格式中总计所在的键
+pub const JSON_MAP_TOTAL_KEY: &str = "Total";
+
 /// JSON 导出器
-pub struct JsonExporter;
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JsonExporter {
+    sort: Option<SortKey>,
+    order: Option<SortOrder>,
+    limit: Option<usize>,
+    keyed: bool,
+}
 
 impl JsonExporter {
     pub fn new() -> Self {
-        JsonExporter
+        JsonExporter {
+            sort: None,
+            order: None,
+            limit: None,
+            keyed: false,
+        }
     }
-}
 
-impl Default for JsonExporter {
-    fn default() -> Self {
-        Self::new()
+    /// 输出以语言名为键的对象 `{"Rust": {...}, "Total": {...}}`，代替 `languages` 数组
+    ///
+    /// 总计放在 [`JSON_MAP_TOTAL_KEY`] 键下，此格式不含 `warnings`。
+    pub fn with_keyed(mut self, keyed: bool) -> Self {
+        self.keyed = keyed;
+        self
+    }
+
+    /// `languages` 的排列顺序，默认沿用报告的排序设置（行数降序）
+    pub fn with_sort(mut self, sort: SortKey) -> Self {
+        self.sort = Some(sort);
+        self
+    }
+
+    /// 排列方向，默认沿用报告的设置，报告也未指定时使用排序依据的默认方向
+    pub fn with_order(mut self, order: SortOrder) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    /// 只输出排序后的前 N 行，`total` 仍为全部语言的合计
+    pub fn with_limit(mut self, limit: Option<usize>) -> Self {
+        self.limit = limit;
+        self
     }
 }
 
@@ -32,76 +73,438 @@ impl ReportExporter for JsonExporter {
 }
 
 impl JsonExporter {
+    /// 按路径分别输出：`{"paths": {"<path>": {...}}, "total": {...}}`
+    pub fn export_multi(&self, multi: &MultiReport, writer: &mut dyn Write) -> Result<(), SaveError> {
+        let paths = multi.reports
+            .iter()
+            .map(|(path, report)| (path.as_str(), self.json_report(report)))
+            .collect();
+        let json_data = JsonMultiReport {
+            paths,
+            total: self.json_report(&multi.total()).total,
+        };
+
+        let text = serde_json::to_string_pretty(&json_data).map_err(SaveError::Json)?;
+        writer.write_all(text.as_bytes()).map_err(SaveError::Io)?;
+        Ok(())
+    }
+
     /// 将报告格式化为 JSON 字符串
     fn format_as_json(&self, report: &Report) -> Result<String, SaveError> {
-        let mut json_data = serde_json::json!({
-            "languages": []
-        });
+        if self.keyed {
+            return serde_json::to_string_pretty(&self.json_map(report)).map_err(SaveError::Json);
+        }
+        serde_json::to_string_pretty(&self.json_report(report)).map_err(SaveError::Json)
+    }
 
-        // 使用 Report 的排序方法
-        let items = report.sort_stats(|a, b| b.1.lines.cmp(&a.1.lines));
+    fn json_map(&self, report: &Report) -> BTreeMap<String, BucketStat> {
+        let json = self.json_report(report);
+        let mut map: BTreeMap<String, BucketStat> = json.languages
+            .into_iter()
+            .filter_map(|row| Some((row.label.into_values().next()?, row.counts)))
+            .collect();
+        map.insert(JSON_MAP_TOTAL_KEY.to_string(), json.total);
+        map
+    }
 
-        let mut languages = Vec::new();
-        let mut total_files = 0;
-        let mut total_lines = 0;
-        let mut total_code = 0;
-        let mut total_comments = 0;
-        let mut total_blanks = 0;
-        let mut total_functions = 0;
-        let mut total_classes = 0;
+    fn json_report(&self, report: &Report) -> JsonReport {
+        JsonReport::from_report(report, sorted_rows(report, self.sort, self.order), self.limit)
+    }
+}
 
-        for (lang, stat) in items {
-            let lang_data = serde_json::json!({
-                "language": lang.to_string(),
-                "files": stat.files,
-                "lines": stat.lines,
-                "code": stat.code,
-                "comments": stat.comments,
-                "blanks": stat.blanks,
-                "functions": stat.functions,
-                "classes": stat.classes
-            });
-            languages.push(lang_data);
+/// `languages` 中的一行，分组名的键随 `--group-by` 变化（如 `language`、`family`），总是排在最前
+#[derive(Debug, Serialize)]
+struct JsonRow {
+    #[serde(flatten)]
+    label: BTreeMap<String, String>,
+    #[serde(flatten)]
+    counts: BucketStat,
+}
 
-            total_files += stat.files;
-            total_lines += stat.lines;
-            total_code += stat.code;
-            total_comments += stat.comments;
-            total_blanks += stat.blanks;
-            total_functions += stat.functions;
-            total_classes += stat.classes;
+#[derive(Debug, Serialize)]
+struct JsonReport {
+    languages: Vec<JsonRow>,
+    total: BucketStat,
+    /// 被跳过的文件及原因，没有时为空数组
+    warnings: Vec<SkipWarning>,
+}
+
+impl JsonReport {
+    fn from_report(report: &Report, rows: Vec<(String, BucketStat)>, limit: Option<usize>) -> Self {
+        let key = report.group_by.label().to_lowercase();
+        let mut total = BucketStat::default();
+        let mut languages: Vec<JsonRow> = rows
+            .into_iter()
+            .map(|(label, counts)| {
+                total += counts.clone();
+                JsonRow {
+                    label: BTreeMap::from([(key.clone(), label)]),
+                    counts,
+                }
+            })
+            .collect();
+        if let Some(limit) = limit {
+            languages.truncate(limit);
+        }
+
+        JsonReport { languages, total, warnings: report.warnings.clone() }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct JsonMultiReport<'a> {
+    paths: BTreeMap<&'a str, JsonReport>,
+    total: BucketStat,
+}
+
+/// JSON Lines 导出器：每种语言一行紧凑的 JSON 对象，最后一行是带 `"total": true` 的总计
+///
+/// 每写一行就刷新一次，便于用 `jq` 等工具逐行处理，不必等待整个文档。
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JsonlExporter {
+    sort: Option<SortKey>,
+    order: Option<SortOrder>,
+}
+
+impl JsonlExporter {
+    pub fn new() -> Self {
+        JsonlExporter { sort: None, order: None }
+    }
+
+    /// 各行的排列顺序，默认沿用报告的排序设置（行数降序）
+    pub fn with_sort(mut self, sort: SortKey) -> Self {
+        self.sort = Some(sort);
+        self
+    }
+
+    /// 排列方向，默认沿用报告的设置，报告也未指定时使用排序依据的默认方向
+    pub fn with_order(mut self, order: SortOrder) -> Self {
+        self.order = Some(order);
+        self
+    }
+}
+
+/// JSON Lines 输出的总计行
+#[derive(Debug, Serialize)]
+struct JsonlTotal {
+    total: bool,
+    #[serde(flatten)]
+    counts: BucketStat,
+}
+
+impl ReportExporter for JsonlExporter {
+    fn export(&self, report: &Report, writer: &mut dyn Write) -> Result<(), SaveError> {
+        let key = report.group_by.label().to_lowercase();
+        let mut total = BucketStat::default();
+        for (label, counts) in sorted_rows(report, self.sort, self.order) {
+            total += counts.clone();
+            let row = JsonRow {
+                label: BTreeMap::from([(key.clone(), label)]),
+                counts,
+            };
+            write_json_line(writer, &row)?;
         }
+        write_json_line(writer, &JsonlTotal { total: true, counts: total })
+    }
+}
+
+/// 导出器指定的排序依据与方向优先，未指定的部分沿用报告的设置
+fn sorted_rows(report: &Report, sort: Option<SortKey>, order: Option<SortOrder>) -> Vec<(String, BucketStat)> {
+    let sort = sort.unwrap_or(report.sort);
+    let order = order.or(report.order).unwrap_or(sort.default_order());
+    report.rows_sorted(sort, order)
+}
+
+fn write_json_line<T: Serialize>(writer: &mut dyn Write, value: &T) -> Result<(), SaveError> {
+    let line = serde_json::to_string(value).map_err(SaveError::Json)?;
+    writeln!(writer, "{}", line).map_err(SaveError::Io)?;
+    writer.flush().map_err(SaveError::Io)
+}
 
-        json_data["languages"] = serde_json::Value::Array(languages);
-        json_data["total"] = serde_json::json!({
-            "files": total_files,
-            "lines": total_lines,
-            "code": total_code,
-            "comments": total_comments,
-            "blanks": total_blanks,
-            "functions": total_functions,
-            "classes": total_classes
-        });
+/// 目录树导出器：输出 `{"dir", "lines", "children": [...]}` 嵌套 JSON
+pub struct TreemapExporter;
 
-        serde_json::to_string_pretty(&json_data).map_err(SaveError::Json)
+impl TreemapExporter {
+    pub fn new() -> Self {
+        TreemapExporter
     }
 }
 
-/// CSV 导出器
-pub struct CsvExporter;
+impl Default for TreemapExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-impl CsvExporter {
+impl ReportExporter for TreemapExporter {
+    fn export(&self, report: &Report, writer: &mut dyn Write) -> Result<(), SaveError> {
+        let text = serde_json::to_string_pretty(&report.dir_tree()).map_err(SaveError::Json)?;
+        writer.write_all(text.as_bytes()).map_err(SaveError::Io)?;
+        Ok(())
+    }
+}
+
+/// CI 导出器：每行一个 `language=lines`，按名字排序，没有表头和分隔线
+pub struct CiExporter;
+
+impl CiExporter {
     pub fn new() -> Self {
-        CsvExporter
+        CiExporter
+    }
+
+    /// 生成 CI 格式文本，终端输出与写入文件共用
+    pub fn render(&self, report: &Report) -> String {
+        let mut rows = report.rows();
+        rows.sort_by(|a, b| a.0.cmp(&b.0));
+        rows.iter()
+            .map(|(label, stat)| format!("{}={}\n", label, stat.lines))
+            .collect()
     }
 }
 
-impl Default for CsvExporter {
+impl Default for CiExporter {
     fn default() -> Self {
         Self::new()
     }
 }
 
+impl ReportExporter for CiExporter {
+    fn export(&self, report: &Report, writer: &mut dyn Write) -> Result<(), SaveError> {
+        writer.write_all(self.render(report).as_bytes()).map_err(SaveError::Io)?;
+        Ok(())
+    }
+}
+
+/// 文本导出器：与终端输出相同的列对齐表格，含总计行
+///
+/// 默认不着色，写入文件时不会带有 ANSI 转义序列。
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TextExporter {
+    painter: Painter,
+    /// 表格末尾增加待办标记数一列
+    todos: bool,
+}
+
+impl TextExporter {
+    pub fn new() -> Self {
+        TextExporter {
+            painter: Painter::new(false),
+            todos: false,
+        }
+    }
+
+    /// 使用指定的着色工具，终端输出时使用
+    pub fn with_painter(mut self, painter: Painter) -> Self {
+        self.painter = painter;
+        self
+    }
+
+    /// 在表格末尾增加 `TODOs` 列
+    pub fn with_todos(mut self, enable: bool) -> Self {
+        self.todos = enable;
+        self
+    }
+
+    /// 开启待办标记列时返回该列的单元格文本
+    fn todo_cell(&self, todos: impl std::fmt::Display) -> String {
+        if self.todos {
+            format!(" {:<10}", todos)
+        } else {
+            String::new()
+        }
+    }
+}
+
+impl ReportExporter for TextExporter {
+    fn export(&self, report: &Report, writer: &mut dyn Write) -> Result<(), SaveError> {
+        writer.write_all(self.render(report).as_bytes()).map_err(SaveError::Io)?;
+        Ok(())
+    }
+}
+
+impl TextExporter {
+    /// 生成统计表格文本
+    pub fn render(&self, report: &Report) -> String {
+        let paint = &self.painter;
+        let mut out = String::new();
+        self.write_divider(&mut out);
+
+        // 使用更宽的列宽和对齐方式
+        let header = format!(
+            "{:<12} {:<8} {:<10} {:<10} {:<10} {:<10} {:<10}{}",
+            report.group_by.label(), "Files", "Lines", "Code", "Comments", "Blanks", "Functions",
+            self.todo_cell("TODOs")
+        );
+        let _ = writeln!(out, "{}", paint.bold(&header));
+        self.write_divider(&mut out);
+
+        // 按语言或语言族汇总，行数降序
+        for (label, stat) in report.rows() {
+            let _ = writeln!(
+                out,
+                "{} {:<8} {:<10} {:<10} {:<10} {:<10} {:<10}{}",
+                paint.keyed(&format!("{:<12}", label), &label),
+                stat.files,
+                stat.lines,
+                stat.code,
+                stat.comments,
+                stat.blanks,
+                stat.functions,
+                self.todo_cell(stat.todos)
+            );
+        }
+
+        self.write_divider(&mut out);
+
+        // 添加总计行
+        let total_files = report.file_count();
+        let total_lines: usize = report.into_iter().map(|(_, s)| s.lines).sum();
+        let total_code: usize = report.into_iter().map(|(_, s)| s.code).sum();
+        let total_comments: usize = report.into_iter().map(|(_, s)| s.comments).sum();
+        let total_blanks: usize = report.into_iter().map(|(_, s)| s.blanks).sum();
+        let total_functions: usize = report.into_iter().map(|(_, s)| s.functions).sum();
+        let total_todos: usize = report.into_iter().map(|(_, s)| s.todos).sum();
+
+        let total = format!(
+            "{:<12} {:<8} {:<10} {:<10} {:<10} {:<10} {:<10}{}",
+            "Total", total_files, total_lines, total_code, total_comments, total_blanks, total_functions,
+            self.todo_cell(total_todos)
+        );
+        let _ = writeln!(out, "{}", paint.bold(&total));
+        self.write_divider(&mut out);
+
+        if report.into_iter().any(|(_, s)| s.test_split.is_some()) {
+            self.write_test_split(&mut out, report);
+        }
+        out
+    }
+
+    /// 测试代码与生产代码的对比
+    fn write_test_split(&self, out: &mut String, report: &Report) {
+        let header = format!(
+            "{:<12} {:<10} {:<10} {:<10} {:<10} {:<10}",
+            "Language", "Src Files", "Src Code", "Test Files", "Test Code", "Test/Src"
+        );
+        let _ = writeln!(out, "{}", self.painter.bold(&header));
+        self.write_divider(out);
+
+        for (lang, stat) in report.sort_by_lines_desc() {
+            let Some(split) = &stat.test_split else {
+                continue;
+            };
+            let ratio = if split.code.code == 0 {
+                "-".to_string()
+            } else {
+                format!("{:.2}", split.test.code as f64 / split.code.code as f64)
+            };
+            let name = lang.to_string();
+            let _ = writeln!(
+                out,
+                "{} {:<10} {:<10} {:<10} {:<10} {:<10}",
+                self.painter.keyed(&format!("{:<12}", name), &name),
+                split.code.files,
+                split.code.code,
+                split.test.files,
+                split.test.code,
+                ratio
+            );
+        }
+        self.write_divider(out);
+    }
+
+    fn write_divider(&self, out: &mut String) {
+        let _ = writeln!(out, "{}", self.painter.dim(&"-".repeat(80)));
+    }
+
+}
+
+/// Markdown 导出器：GitHub 风格的表格，按报告的汇总维度与排序输出，最后一行为加粗的总计
+pub struct MarkdownExporter;
+
+impl MarkdownExporter {
+    pub fn new() -> Self {
+        MarkdownExporter
+    }
+}
+
+impl Default for MarkdownExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReportExporter for MarkdownExporter {
+    fn export(&self, report: &Report, writer: &mut dyn Write) -> Result<(), SaveError> {
+        writer.write_all(self.format_as_markdown(report).as_bytes()).map_err(SaveError::Io)?;
+        Ok(())
+    }
+}
+
+impl MarkdownExporter {
+    fn format_as_markdown(&self, report: &Report) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "| {} | Files | Lines | Code | Comments | Blanks | Functions | Classes |",
+            report.group_by.label()
+        );
+        out.push_str("|:---|---:|---:|---:|---:|---:|---:|---:|\n");
+
+        let mut total = BucketStat::default();
+        for (label, stat) in report.rows() {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {} | {} | {} | {} |",
+                label, stat.files, stat.lines, stat.code, stat.comments, stat.blanks, stat.functions, stat.classes
+            );
+            total += stat;
+        }
+
+        let _ = writeln!(
+            out,
+            "| **Total** | **{}** | **{}** | **{}** | **{}** | **{}** | **{}** | **{}** |",
+            total.files, total.lines, total.code, total.comments, total.blanks, total.functions, total.classes
+        );
+        out
+    }
+}
+
+/// CSV 导出器
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CsvExporter {
+    sort: Option<SortKey>,
+    order: Option<SortOrder>,
+    limit: Option<usize>,
+}
+
+impl CsvExporter {
+    pub fn new() -> Self {
+        CsvExporter {
+            sort: None,
+            order: None,
+            limit: None,
+        }
+    }
+
+    /// 数据行的排列顺序，默认沿用报告的排序设置（行数降序）
+    pub fn with_sort(mut self, sort: SortKey) -> Self {
+        self.sort = Some(sort);
+        self
+    }
+
+    /// 排列方向，默认沿用报告的设置，报告也未指定时使用排序依据的默认方向
+    pub fn with_order(mut self, order: SortOrder) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    /// 只输出排序后的前 N 行，总计行仍为全部语言的合计
+    pub fn with_limit(mut self, limit: Option<usize>) -> Self {
+        self.limit = limit;
+        self
+    }
+}
+
 impl ReportExporter for CsvExporter {
     fn export(&self, report: &Report, writer: &mut dyn Write) -> Result<(), SaveError> {
         let csv_data = self.format_as_csv(report)?;
@@ -116,10 +519,7 @@ impl CsvExporter {
         let mut csv_data = String::new();
 
         // CSV 头部
-        csv_data.push_str("Language,Files,Lines,Code,Comments,Blanks,Functions,Classes\n");
-
-        // 使用 Report 的排序方法
-        let items = report.sort_stats(|a, b| b.1.lines.cmp(&a.1.lines));
+        csv_data.push_str(&format!("{},Files,Lines,Code,Comments,Blanks,Functions,Classes\n", report.group_by.label()));
 
         let mut total_files = 0;
         let mut total_lines = 0;
@@ -127,19 +527,23 @@ impl CsvExporter {
         let mut total_comments = 0;
         let mut total_blanks = 0;
         let mut total_functions = 0;
+        let mut total_classes = 0;
 
-        // 写入每种语言的数据
-        for (lang, stat) in items {
-            csv_data.push_str(&format!(
-                "{},{},{},{},{},{},{}\n",
-                lang.to_string(),
-                stat.files,
-                stat.lines,
-                stat.code,
-                stat.comments,
-                stat.blanks,
-                stat.functions,
-            ));
+        // 写入每种语言的数据，超出 limit 的行只计入总计
+        for (i, (label, stat)) in sorted_rows(report, self.sort, self.order).into_iter().enumerate() {
+            if self.limit.is_none_or(|limit| i < limit) {
+                csv_data.push_str(&format!(
+                    "{},{},{},{},{},{},{},{}\n",
+                    label,
+                    stat.files,
+                    stat.lines,
+                    stat.code,
+                    stat.comments,
+                    stat.blanks,
+                    stat.functions,
+                    stat.classes,
+                ));
+            }
 
             total_files += stat.files;
             total_lines += stat.lines;
@@ -147,15 +551,16 @@ impl CsvExporter {
             total_comments += stat.comments;
             total_blanks += stat.blanks;
             total_functions += stat.functions;
+            total_classes += stat.classes;
         }
 
         // 添加分隔线
-        csv_data.push_str(",,,,,,,,\n");
+        csv_data.push_str(",,,,,,,\n");
 
         // 添加总计行
         csv_data.push_str(&format!(
-            "Total,{},{},{},{},{},{}\n",
-            total_files, total_lines, total_code, total_comments, total_blanks, total_functions
+            "Total,{},{},{},{},{},{},{}\n",
+            total_files, total_lines, total_code, total_comments, total_blanks, total_functions, total_classes
         ));
 
         Ok(csv_data)
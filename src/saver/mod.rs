@@ -1,10 +1,11 @@
 pub mod exporter;
 pub mod save_error;
-pub use exporter::{ReportExporter, JsonExporter, CsvExporter};
+pub use exporter::{ReportExporter, CiExporter, JsonExporter, JsonlExporter, CsvExporter, MarkdownExporter, TextExporter, TreemapExporter};
 pub use save_error::SaveError;
 
 use std::fs::File;
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use crate::report::Report;
 use crate::utils::format::OutputFormat;
 
@@ -17,6 +18,12 @@ impl FileSaver {
     }
 }
 
+impl Default for FileSaver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl FileSaver {
     /// 将报告保存到指定文件
     pub fn save_report<P: AsRef<Path>>(
@@ -25,17 +32,93 @@ impl FileSaver {
         format: OutputFormat,
     ) -> Result<(), SaveError> {
         let mut file = File::create(path).map_err(SaveError::Io)?;
+        Self::write_report(report, &mut file, format)
+    }
 
+    /// 异步保存报告：序列化在阻塞线程池中完成，文件用 `tokio::fs` 写入，不阻塞运行时
+    pub async fn save_report_async<P: AsRef<Path>>(
+        report: &Report,
+        path: P,
+        format: OutputFormat,
+    ) -> Result<(), SaveError> {
+        let report = report.clone();
+        let bytes = tokio::task::spawn_blocking(move || {
+            let mut buf = Vec::new();
+            Self::write_report(&report, &mut buf, format).map(|_| buf)
+        })
+        .await
+        .map_err(|e| SaveError::Io(std::io::Error::other(e)))??;
+
+        tokio::fs::write(path, bytes).await.map_err(SaveError::Io)
+    }
+
+    /// 按格式把报告写入 `out`
+    fn write_report(report: &Report, out: &mut dyn Write, format: OutputFormat) -> Result<(), SaveError> {
         match format {
             OutputFormat::Json => {
                 let exporter = JsonExporter::new();
-                exporter.export(report, &mut file)
+                exporter.export(report, out)
+            },
+            OutputFormat::JsonMap => {
+                let exporter = JsonExporter::new().with_keyed(true);
+                exporter.export(report, out)
+            },
+            OutputFormat::Jsonl => {
+                let exporter = JsonlExporter::new();
+                exporter.export(report, out)
             },
             OutputFormat::Csv => {
                 let exporter = CsvExporter::new();
-                exporter.export(report, &mut file)
+                exporter.export(report, out)
             },
-            OutputFormat::Text => Err(SaveError::UnsupportedFormat),
+            OutputFormat::Treemap => {
+                let exporter = TreemapExporter::new();
+                exporter.export(report, out)
+            },
+            OutputFormat::Markdown => {
+                let exporter = MarkdownExporter::new();
+                exporter.export(report, out)
+            },
+            OutputFormat::Text => {
+                let exporter = TextExporter::new();
+                exporter.export(report, out)
+            },
+            OutputFormat::Ci => {
+                let exporter = CiExporter::new();
+                exporter.export(report, out)
+            },
+        }
+    }
+
+    /// 一次性把报告保存为多种格式，文件名为 `<base>.<ext>`
+    ///
+    /// 若 `base` 已带有与格式相同的扩展名则不再重复追加，返回实际写入的路径
+    pub fn save_reports<P: AsRef<Path>>(
+        report: &Report,
+        base: P,
+        formats: &[OutputFormat],
+    ) -> Result<Vec<PathBuf>, SaveError> {
+        let base = base.as_ref();
+        let mut written = Vec::with_capacity(formats.len());
+
+        for format in formats {
+            let path = Self::output_path(base, *format);
+            Self::save_report(report, &path, *format)?;
+            written.push(path);
+        }
+
+        Ok(written)
+    }
+
+    /// 某种格式的输出文件路径：`base` 已带有该格式的扩展名时直接使用，否则追加 `.<ext>`
+    pub fn output_path<P: AsRef<Path>>(base: P, format: OutputFormat) -> PathBuf {
+        let base = base.as_ref();
+        let ext = format.extension();
+        // 扩展名可能含多段（如 `treemap.json`），按文件名后缀比较
+        if base.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.ends_with(&format!(".{}", ext))) {
+            base.to_path_buf()
+        } else {
+            PathBuf::from(format!("{}.{}", base.display(), ext))
         }
     }
 
@@ -1,8 +1,10 @@
 use std::ops::AddAssign;
 
+use serde::Serialize;
+
 use crate::langs::lang_type::LangType;
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct FileStat {
     pub lang: LangType,
     pub path: String,
@@ -13,8 +15,26 @@ pub struct FileStat {
     pub comments: usize,
     pub blanks: usize,
 
+    /// 函数定义的个数
     pub functions: usize,
+    /// 函数体内的行数（含定义行）
+    pub function_lines: usize,
     pub classes: usize,
+    /// 代码中分支关键字（`if`、`&&` 等）的个数，近似圈复杂度，仅在开启 `--complexity` 时统计
+    pub complexity: usize,
+
+    /// 看起来像被注释掉的代码的注释行，仅在开启检测时统计
+    pub commented_code: usize,
+    /// 含有特殊注释标记（如 Sphinx 的 `#:`）的行
+    pub special_comments: usize,
+    /// 注释中 `TODO`、`FIXME` 等待办标记的个数，仅在开启 `--todos` 时统计
+    pub todos: usize,
+    /// 文件开头的注释块（如许可证头）的行数，仅在开启跳过时统计，这些行不再计入注释
+    pub header_lines: usize,
+    /// 由 `// region Name` / `// endregion` 等标记划分的区域及其行数，按区域开始的顺序排列
+    pub regions: Vec<(String, usize)>,
+    /// 文件中同时出现了 CRLF 与 LF 行尾
+    pub mixed_eol: bool,
 }
 
 impl FileStat {
@@ -26,6 +46,22 @@ impl FileStat {
             ..Default::default()
         }
     }
+
+    /// 不统计空行：从总行数中扣除并清零
+    pub fn ignore_blanks(&mut self) {
+        self.lines -= self.blanks;
+        self.blanks = 0;
+    }
+
+    /// 不统计注释：注释行与文件头行从总行数中扣除并清零，注释的细分统计一并清零
+    pub fn ignore_comments(&mut self) {
+        self.lines -= self.comments + self.header_lines;
+        self.comments = 0;
+        self.header_lines = 0;
+        self.commented_code = 0;
+        self.special_comments = 0;
+        self.todos = 0;
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -38,9 +74,20 @@ pub struct LangStat {
     pub blanks: usize,
 
     pub functions: usize,
+    pub function_lines: usize,
     pub classes: usize,
+    /// 各文件分支关键字个数之和
+    pub complexity: usize,
+    pub commented_code: usize,
+    pub special_comments: usize,
+    /// 各文件待办标记个数之和
+    pub todos: usize,
+    pub header_lines: usize,
 
     pub stats: Vec<FileStat>,
+
+    /// 测试代码与生产代码的拆分结果，仅在启用拆分时存在
+    pub test_split: Option<TestSplit>,
 }
 
 impl LangStat {
@@ -50,6 +97,16 @@ impl LangStat {
             ..Default::default()
         }
     }
+
+    /// 平均每个函数的行数（函数体行数 / 函数个数），没有函数时为 `None`
+    pub fn avg_lines_per_function(&self) -> Option<f64> {
+        (self.functions > 0).then(|| self.function_lines as f64 / self.functions as f64)
+    }
+
+    /// 平均每个类的代码行数，没有类时为 `None`
+    pub fn avg_lines_per_class(&self) -> Option<f64> {
+        (self.classes > 0).then(|| self.code as f64 / self.classes as f64)
+    }
 }
 
 impl AddAssign for LangStat {
@@ -60,8 +117,87 @@ impl AddAssign for LangStat {
         self.comments += other.comments;
         self.blanks += other.blanks;
         self.functions += other.functions;
+        self.function_lines += other.function_lines;
         self.classes += other.classes;
+        self.complexity += other.complexity;
+        self.commented_code += other.commented_code;
+        self.special_comments += other.special_comments;
+        self.todos += other.todos;
+        self.header_lines += other.header_lines;
         
         self.stats.extend_from_slice(&other.stats);
+
+        self.test_split = match (self.test_split.take(), other.test_split) {
+            (Some(mut a), Some(b)) => {
+                a.code += b.code;
+                a.test += b.test;
+                Some(a)
+            }
+            (a, b) => a.or(b),
+        };
+    }
+}
+
+/// 某一类文件（测试或生产代码）的汇总统计，JSON 输出中按字段声明顺序序列化
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct BucketStat {
+    pub files: usize,
+    pub lines: usize,
+    pub code: usize,
+    pub comments: usize,
+    pub blanks: usize,
+
+    pub functions: usize,
+    pub classes: usize,
+    /// 待办标记个数，仅在开启 `--todos` 时统计；不写入 JSON，保持输出格式稳定
+    #[serde(skip)]
+    pub todos: usize,
+}
+
+impl BucketStat {
+    pub fn add(&mut self, stat: &FileStat) {
+        self.files += 1;
+        self.lines += stat.lines;
+        self.code += stat.code;
+        self.comments += stat.comments;
+        self.blanks += stat.blanks;
+        self.functions += stat.functions;
+        self.classes += stat.classes;
+        self.todos += stat.todos;
+    }
+}
+
+impl From<&LangStat> for BucketStat {
+    fn from(stat: &LangStat) -> Self {
+        BucketStat {
+            files: stat.files,
+            lines: stat.lines,
+            code: stat.code,
+            comments: stat.comments,
+            blanks: stat.blanks,
+            functions: stat.functions,
+            classes: stat.classes,
+            todos: stat.todos,
+        }
+    }
+}
+
+impl AddAssign for BucketStat {
+    fn add_assign(&mut self, other: Self) {
+        self.files += other.files;
+        self.lines += other.lines;
+        self.code += other.code;
+        self.comments += other.comments;
+        self.blanks += other.blanks;
+        self.functions += other.functions;
+        self.classes += other.classes;
+        self.todos += other.todos;
     }
+}
+
+/// 按路径模式划分的生产代码 / 测试代码统计
+#[derive(Debug, Default, Clone)]
+pub struct TestSplit {
+    pub code: BucketStat,
+    pub test: BucketStat,
 }
\ No newline at end of file
@@ -1,3 +1,11 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::{Arc, RwLock};
+
+use lazy_static::lazy_static;
+
+use crate::langs::lang_type::LangType;
+
 use super::lex_status::LineCtx;
 
 /// 把一行文本映射成“类别”
@@ -10,150 +18,728 @@ pub enum LineKind {
     Mixed,        // 代码+注释
 }
 
+/// 行内片段的类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanKind {
+    Code,
+    Comment,
+}
+
+/// 行内的一个片段，`range` 是相对于原始行文本的字节区间（已去掉首尾空白）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineSpan {
+    pub kind: SpanKind,
+    pub range: Range<usize>,
+}
+
+/// 一行的分类结果：行类别 + 按顺序排列的代码/注释片段
+///
+/// 空行没有片段；纯代码/纯注释行只有一个片段；`Mixed` 行包含代码和注释片段。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineClassification {
+    pub kind: LineKind,
+    pub spans: Vec<LineSpan>,
+}
+
+impl LineClassification {
+    pub fn blank() -> Self {
+        Self {
+            kind: LineKind::Blank,
+            spans: Vec::new(),
+        }
+    }
+
+    /// 注释内部的空行：计为注释，但没有任何片段
+    pub fn empty_comment() -> Self {
+        Self {
+            kind: LineKind::Comment,
+            spans: Vec::new(),
+        }
+    }
+
+    /// 跨行字符串内部的空行：计为代码，但没有任何片段
+    pub fn empty_code() -> Self {
+        Self {
+            kind: LineKind::Code,
+            spans: Vec::new(),
+        }
+    }
+
+    /// 按 `(类别, 起点, 终点)` 构造，区间相对于 `trimmed`，`offset` 是 `trimmed` 在原始行中的起点。
+    /// 空白片段会被丢弃。
+    fn from_parts(kind: LineKind, trimmed: &str, offset: usize, parts: &[(SpanKind, usize, usize)]) -> Self {
+        let spans = parts
+            .iter()
+            .filter_map(|&(kind, start, end)| {
+                let part = &trimmed[start..end];
+                let lead = part.len() - part.trim_start().len();
+                let body = part.trim();
+                if body.is_empty() {
+                    return None;
+                }
+                let start = offset + start + lead;
+                Some(LineSpan { kind, range: start..start + body.len() })
+            })
+            .collect();
+        Self { kind, spans }
+    }
+
+    /// 根据实际保留下来的片段确定行类别
+    fn with_kind_from_spans(mut self) -> Self {
+        let has_code = self.spans.iter().any(|span| span.kind == SpanKind::Code);
+        let has_comment = self.spans.iter().any(|span| span.kind == SpanKind::Comment);
+        self.kind = match (has_code, has_comment) {
+            (true, true) => LineKind::Mixed,
+            (true, false) => LineKind::Code,
+            (false, true) => LineKind::Comment,
+            (false, false) => LineKind::Blank,
+        };
+        self
+    }
+
+    pub fn code_spans(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.spans_of(SpanKind::Code)
+    }
+
+    pub fn comment_spans(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.spans_of(SpanKind::Comment)
+    }
+
+    fn spans_of(&self, kind: SpanKind) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.spans
+            .iter()
+            .filter(move |span| span.kind == kind)
+            .map(|span| span.range.clone())
+    }
+
+    /// 取出该行所有代码片段的文本，多个片段之间用空格连接
+    /// 是否有注释片段以 `markers` 中的某个标记开头，`raw` 须是分类时传入的原始行
+    pub fn has_comment_marker(&self, raw: &str, markers: &[&str]) -> bool {
+        !markers.is_empty() && self.comment_spans()
+            .any(|range| markers.iter().any(|m| raw[range.clone()].starts_with(m)))
+    }
+
+    /// 注释片段中出现了 `markers` 中的几个标记，同一标记在一行中只计一次；
+    /// 标记前后紧邻字母、数字或下划线时不算（如 `TODOS`）
+    pub fn count_comment_markers(&self, raw: &str, markers: &[String]) -> usize {
+        if markers.is_empty() {
+            return 0;
+        }
+        let is_word = |c: char| c.is_alphanumeric() || c == '_';
+        markers
+            .iter()
+            .filter(|marker| !marker.is_empty())
+            .filter(|marker| self.comment_spans().any(|range| {
+                let text = &raw[range];
+                text.match_indices(marker.as_str()).any(|(pos, m)| {
+                    !text[..pos].ends_with(is_word) && !text[pos + m.len()..].starts_with(is_word)
+                })
+            }))
+            .count()
+    }
+
+    pub fn code_text(&self, raw: &str) -> String {
+        self.code_spans()
+            .map(|range| &raw[range])
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
 pub trait Classifier: Send + Sync {
     /// 核心函数：根据上下文判断行类别
     /// # param
-    /// - line: 行内容，包含行号、行文本、行文本长度、行文本的起始索引
+    /// - line: 行内容，包含原始行文本、词法上下文和语言定义
     /// # return
-    /// - LineKind: 行类别
-    /// - Option<(usize, usize)>: 如果行类别是 Mixed，则返回代码的起始和结束位置
-    fn classify(&self, line: LineCtx) -> (LineKind, Option<(usize, usize)>);
+    /// - LineClassification: 行类别以及代码/注释片段在原始行中的字节区间
+    fn classify(&self, line: LineCtx) -> LineClassification;
+}
+
+impl Classifier for Arc<dyn Classifier> {
+    fn classify(&self, line: LineCtx) -> LineClassification {
+        self.as_ref().classify(line)
+    }
+}
+
+lazy_static! {
+    /// 库使用者为某种语言注册的分类器，优先于内置分类器
+    static ref CUSTOM_CLASSIFIERS: RwLock<HashMap<LangType, Arc<dyn Classifier>>> = RwLock::new(HashMap::new());
+}
+
+/// 为 `lang` 注册自定义分类器，之后该语言的统计都使用它，返回被替换的旧分类器
+///
+/// 注册后该语言使用 `DefaultLexer`，Python 的缩进函数范围统计也随之失效。
+pub fn register_classifier(lang: LangType, classifier: impl Classifier + 'static) -> Option<Arc<dyn Classifier>> {
+    CUSTOM_CLASSIFIERS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(lang, Arc::new(classifier))
+}
+
+/// 移除 `lang` 的自定义分类器，恢复内置分类器
+pub fn unregister_classifier(lang: LangType) -> Option<Arc<dyn Classifier>> {
+    CUSTOM_CLASSIFIERS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&lang)
 }
 
-pub struct DefaultClassifier;
+/// `lang` 当前注册的自定义分类器
+pub fn custom_classifier(lang: LangType) -> Option<Arc<dyn Classifier>> {
+    CUSTOM_CLASSIFIERS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&lang)
+        .cloned()
+}
+
+pub struct DefaultClassifier {
+    /// 块注释内的空行计为空行，关闭后计为注释
+    blanks_in_comments_are_blank: bool,
+    /// 跨行字符串内的空行计为代码，关闭后计为空行
+    blanks_in_strings_are_code: bool,
+}
 
 impl DefaultClassifier {
     pub fn new() -> Self {
-        DefaultClassifier {}
+        DefaultClassifier {
+            blanks_in_comments_are_blank: true,
+            blanks_in_strings_are_code: true,
+        }
+    }
+
+    pub fn with_blanks_in_comments_are_blank(mut self, enable: bool) -> Self {
+        self.blanks_in_comments_are_blank = enable;
+        self
+    }
+
+    pub fn with_blanks_in_strings_are_code(mut self, enable: bool) -> Self {
+        self.blanks_in_strings_are_code = enable;
+        self
     }
 }
 
-pub struct PythonClassifier;
+impl Default for DefaultClassifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct PythonClassifier {
+    /// 文档字符串内的空行计为空行，关闭后计为注释
+    blanks_in_comments_are_blank: bool,
+    /// 跨行字符串内的空行计为代码，关闭后计为空行
+    blanks_in_strings_are_code: bool,
+}
 
 impl PythonClassifier {
     pub fn new() -> Self {
-        PythonClassifier {}
+        PythonClassifier {
+            blanks_in_comments_are_blank: true,
+            blanks_in_strings_are_code: true,
+        }
+    }
+
+    pub fn with_blanks_in_comments_are_blank(mut self, enable: bool) -> Self {
+        self.blanks_in_comments_are_blank = enable;
+        self
+    }
+
+    pub fn with_blanks_in_strings_are_code(mut self, enable: bool) -> Self {
+        self.blanks_in_strings_are_code = enable;
+        self
+    }
+}
+
+impl Default for PythonClassifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 批处理脚本：`REM`（不区分大小写，可带 `@`）与 `::` 开头的整行是注释
+#[derive(Default)]
+pub struct BatchClassifier;
+
+impl BatchClassifier {
+    pub fn new() -> Self {
+        BatchClassifier
+    }
+
+    fn is_comment(s: &str) -> bool {
+        if s.starts_with("::") {
+            return true;
+        }
+        let s = s.strip_prefix('@').unwrap_or(s);
+        s.get(..3).is_some_and(|head| head.eq_ignore_ascii_case("rem"))
+            && s[3..].chars().next().is_none_or(|c| c.is_whitespace())
+    }
+}
+
+impl Classifier for BatchClassifier {
+    fn classify(&self, line: LineCtx) -> LineClassification {
+        let s = line.trimmed();
+        if s.is_empty() {
+            return LineClassification::blank();
+        }
+        let (kind, span) = if Self::is_comment(s) {
+            (LineKind::Comment, SpanKind::Comment)
+        } else {
+            (LineKind::Code, SpanKind::Code)
+        };
+        LineClassification::from_parts(kind, s, line.offset(), &[(span, 0, s.len())])
+    }
+}
+
+/// Fortran：`!` 之后是注释（字符串内的除外）；固定格式中第 1 列为 `C`、`c` 或 `*` 的整行也是注释
+#[derive(Default)]
+pub struct FortranClassifier;
+
+impl FortranClassifier {
+    pub fn new() -> Self {
+        FortranClassifier
+    }
+
+    /// 第 1 列为 `*`，或为 `C`/`c` 且后面不像语句（如 `call f()`、`c = 1`）
+    fn is_fixed_form_comment(raw: &str) -> bool {
+        match raw.as_bytes().first() {
+            Some(b'*') => true,
+            Some(b'C' | b'c') => {
+                let rest = &raw[1..];
+                !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_')
+                    && !rest.trim_start().starts_with(['=', '(', '%'])
+            }
+            _ => false,
+        }
+    }
+
+    /// 查找不在单/双引号字符串内的 `!`
+    fn find_comment(s: &str) -> Option<usize> {
+        let mut quote = None;
+        for (i, c) in s.char_indices() {
+            match (quote, c) {
+                (None, '!') => return Some(i),
+                (None, '\'' | '"') => quote = Some(c),
+                (Some(q), c) if c == q => quote = None,
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+impl Classifier for FortranClassifier {
+    fn classify(&self, line: LineCtx) -> LineClassification {
+        let s = line.trimmed();
+        if s.is_empty() {
+            return LineClassification::blank();
+        }
+        let off = line.offset();
+        let len = s.len();
+        if Self::is_fixed_form_comment(line.raw()) {
+            return LineClassification::from_parts(LineKind::Comment, s, off, &[(SpanKind::Comment, 0, len)]);
+        }
+        match Self::find_comment(s) {
+            Some(pos) => LineClassification::from_parts(LineKind::Mixed, s, off, &[
+                (SpanKind::Code, 0, pos),
+                (SpanKind::Comment, pos, len),
+            ]).with_kind_from_spans(),
+            None => LineClassification::from_parts(LineKind::Code, s, off, &[(SpanKind::Code, 0, len)]),
+        }
     }
 }
 
 impl Classifier for DefaultClassifier {
-    fn classify(&self, mut line: LineCtx) -> (LineKind, Option<(usize, usize)>) {
+    fn classify(&self, mut line: LineCtx) -> LineClassification {
         let s = line.trimmed().to_string();
+        let off = line.offset();
         if s.is_empty() {
-            return (LineKind::Blank, None);
+            return if line.ctx().in_block_comment && !self.blanks_in_comments_are_blank {
+                LineClassification::empty_comment()
+            } else if line.ctx().code_string.is_some() && self.blanks_in_strings_are_code {
+                LineClassification::empty_code()
+            } else {
+                LineClassification::blank()
+            };
         }
-        
-        if line.ctx().in_block_comment {
-            if let Some((_, end)) = line.lang().block_comment {
-                if let Some(pos) = s.find(end) {
+        let len = s.len();
+        let line_comments = line.lang().line_comments;
+        let block_comment = line.lang().block_comment;
+        let nested = line.lang().nested_block_comment;
+        let quotes = line.lang().quotes;
+        let multiline = line.lang().multiline_strings;
+
+        let mut parts = Vec::new();
+        let mut cursor = 0;
+
+        // 上一行留下的块注释先找结束符
+        if line.ctx().in_block_comment
+            && let Some(delims) = block_comment {
+            let mut depth = line.ctx().comment_depth.max(1);
+            match find_block_end(&s, 0, delims, nested, &mut depth) {
+                Some(close) => {
                     line.ctx().in_block_comment = false;
-                    if pos + end.len() == s.len() {
-                        return (LineKind::Comment, None);
-                    } else {
-                        return (LineKind::Mixed, Some((pos + end.len(), s.len())));
-                    }
-                } else {
-                    return (LineKind::Comment, None);
+                    line.ctx().comment_depth = 0;
+                    cursor = close;
+                    parts.push((SpanKind::Comment, 0, cursor));
+                }
+                None => {
+                    line.ctx().comment_depth = depth;
+                    return LineClassification::from_parts(LineKind::Comment, &s, off, &[(SpanKind::Comment, 0, len)]);
                 }
             }
         }
 
-        if let Some(prefix) = line.lang().line_comment {
-            if s.starts_with(prefix) {
-                return (LineKind::Comment, None);
+        // 上一行留下的跨行字符串先找结束定界符
+        if let Some(delim) = line.ctx().code_string {
+            match find_string_end(&s, delim) {
+                Some(close) => {
+                    line.ctx().code_string = None;
+                    cursor = close;
+                    parts.push((SpanKind::Code, 0, cursor));
+                }
+                None => return LineClassification::from_parts(LineKind::Code, &s, off, &[(SpanKind::Code, 0, len)]),
             }
         }
 
-        if let Some((start, end)) = line.lang().block_comment {
-            if let Some(pos) = s.find(start) {
-                let after = &s[pos + start.len()..];
-                if let Some(end_pos) = after.find(end) {
-                    if end_pos + end.len() == after.len() && pos == 0 {
-                        return (LineKind::Comment, None);
-                    } else {
-                        return (LineKind::Mixed, Some((pos + end.len(), s.len())));
-                    }
+        // 从左到右扫描，行注释与块注释以先出现的为准；有多个行注释标记时取最靠前的
+        while cursor < len {
+            let rest = &s[cursor..];
+            let line_pos = line_comments.iter()
+                .filter_map(|prefix| find_outside_strings(rest, prefix, quotes, multiline))
+                .min();
+            let block_pos = block_comment.and_then(|(start, _)| find_outside_strings(rest, start, quotes, multiline));
+
+            if let Some(pos) = line_pos
+                && block_pos.is_none_or(|block| pos < block) {
+                parts.push((SpanKind::Code, cursor, cursor + pos));
+                parts.push((SpanKind::Comment, cursor + pos, len));
+                break;
+            }
+
+            let (Some(delims), Some(pos)) = (block_comment, block_pos) else {
+                parts.push((SpanKind::Code, cursor, len));
+                line.ctx().code_string = unclosed_string(rest, quotes, multiline);
+                break;
+            };
+            let open = cursor + pos;
+            parts.push((SpanKind::Code, cursor, open));
+
+            let mut depth = 1;
+            match find_block_end(&s, open + delims.0.len(), delims, nested, &mut depth) {
+                Some(close) => {
+                    cursor = close;
+                    parts.push((SpanKind::Comment, open, cursor));
                 }
-                else {
+                None => {
                     line.ctx().in_block_comment = true;
-                    let before = &s[..pos];
-                    return if before.trim().is_empty() {
-                        (LineKind::Comment, None)
-                    } else {
-                        (LineKind::Mixed, Some((0, pos)))
-                    };
+                    line.ctx().comment_depth = depth;
+                    parts.push((SpanKind::Comment, open, len));
+                    break;
                 }
             }
         }
-        (LineKind::Code, None)
+
+        LineClassification::from_parts(LineKind::Mixed, &s, off, &parts).with_kind_from_spans()
+    }
+}
+
+/// 从 `from` 开始查找使块注释闭合的结束符，返回结束符之后的位置；`nested` 时遇到开始符深度加一。
+/// 行内没有闭合时返回 None，`depth` 为行尾处的嵌套深度
+fn find_block_end(s: &str, from: usize, (start, end): (&str, &str), nested: bool, depth: &mut usize) -> Option<usize> {
+    let mut i = from;
+    while i < s.len() {
+        let rest = &s[i..];
+        if rest.starts_with(end) {
+            i += end.len();
+            *depth = depth.saturating_sub(1);
+            if *depth == 0 {
+                return Some(i);
+            }
+        } else if nested && rest.starts_with(start) {
+            i += start.len();
+            *depth += 1;
+        } else {
+            i += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    None
+}
+
+/// 查找不在字符串内的 `pat`，避免把 `"http://"` 之类的内容当成注释
+///
+/// 字符串以 `quotes` 中的字符或 `multiline` 中的定界符开始，到同一定界符结束，中间的 `\` 转义下一个字符。
+fn find_outside_strings(s: &str, pat: &str, quotes: &[char], multiline: &[&'static str]) -> Option<usize> {
+    scan_strings(s, Some(pat), quotes, multiline, |_| ()).0
+}
+
+/// 去掉字符串字面量（含定界符）后剩下的代码，行尾未闭合的字符串一并去掉
+pub(crate) fn outside_strings(s: &str, quotes: &[char], multiline: &[&'static str]) -> String {
+    let mut code = String::with_capacity(s.len());
+    scan_strings(s, None, quotes, multiline, |c| code.push(c));
+    code
+}
+
+/// 行尾仍未闭合的跨行字符串的定界符；只在单行内的字符串未闭合时视为到行尾结束
+fn unclosed_string(s: &str, quotes: &[char], multiline: &[&'static str]) -> Option<&'static str> {
+    scan_strings(s, None, quotes, multiline, |_| ()).1
+}
+
+/// 在跨行字符串内部查找结束定界符，返回定界符之后的位置
+fn find_string_end(s: &str, delim: &str) -> Option<usize> {
+    let mut chars = s.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if s[i..].starts_with(delim) {
+            return Some(i + delim.len());
+        }
+    }
+    None
+}
+
+#[derive(Clone, Copy)]
+enum Quote {
+    Char(char),
+    Multiline(&'static str),
+}
+
+/// 跳过字符串内容从左到右扫描，返回第一个不在字符串内的 `pat` 的位置，以及行尾未闭合的跨行字符串定界符
+///
+/// 不在字符串内的字符依次传给 `on_code`。
+fn scan_strings(
+    s: &str,
+    pat: Option<&str>,
+    quotes: &[char],
+    multiline: &[&'static str],
+    mut on_code: impl FnMut(char),
+) -> (Option<usize>, Option<&'static str>) {
+    let mut quote = None;
+    let mut i = 0;
+    while let Some(c) = s[i..].chars().next() {
+        let step = match quote {
+            Some(_) if c == '\\' => c.len_utf8() + s[i + 1..].chars().next().map_or(0, char::len_utf8),
+            Some(Quote::Multiline(delim)) if s[i..].starts_with(delim) => {
+                quote = None;
+                delim.len()
+            }
+            Some(Quote::Char(q)) if c == q => {
+                quote = None;
+                1
+            }
+            Some(_) => c.len_utf8(),
+            None if pat.is_some_and(|pat| s[i..].starts_with(pat)) => return (Some(i), None),
+            None => match multiline.iter().find(|delim| s[i..].starts_with(**delim)) {
+                Some(delim) => {
+                    quote = Some(Quote::Multiline(delim));
+                    delim.len()
+                }
+                None => {
+                    if quotes.contains(&c) {
+                        quote = Some(Quote::Char(c));
+                    } else {
+                        on_code(c);
+                    }
+                    c.len_utf8()
+                }
+            },
+        };
+        i += step;
+    }
+    match quote {
+        Some(Quote::Multiline(delim)) => (None, Some(delim)),
+        _ => (None, None),
     }
 }
 
 impl Classifier for PythonClassifier {
-    fn classify(&self, mut line: LineCtx) -> (LineKind, Option<(usize, usize)>) {
+    fn classify(&self, mut line: LineCtx) -> LineClassification {
         let s = line.trimmed().to_string();
+        let off = line.offset();
         if s.is_empty() {
-            return (LineKind::Blank, None);
+            return if line.ctx().in_string && !self.blanks_in_comments_are_blank {
+                LineClassification::empty_comment()
+            } else if line.ctx().code_string.is_some() && self.blanks_in_strings_are_code {
+                LineClassification::empty_code()
+            } else {
+                LineClassification::blank()
+            };
         }
+        let len = s.len();
+        let whole = |kind: LineKind, span: SpanKind| LineClassification::from_parts(kind, &s, off, &[(span, 0, len)]);
 
         // Handle docstrings (""" or ''')
         if line.ctx().in_string {
-            // Check for docstring end
-            if s.contains("\"\"\"") || s.contains("'''") {
+            // Only the delimiter that opened the docstring can close it
+            let delim = line.ctx().string_delim.unwrap_or("\"\"\"");
+            if let Some(end_pos) = s.find(delim) {
                 line.ctx().in_string = false;
-                // If line contains only docstring end, treat as comment
-                if s.trim() == "\"\"\"" || s.trim() == "'''" {
-                    return (LineKind::Comment, None);
+                line.ctx().string_delim = None;
+                // Extract code after docstring
+                let close = end_pos + delim.len();
+                if !s[close..].trim().is_empty() {
+                    return LineClassification::from_parts(LineKind::Mixed, &s, off, &[
+                        (SpanKind::Comment, 0, close),
+                        (SpanKind::Code, close, len),
+                    ]);
                 } else {
-                    // Extract code after docstring
-                    let end_pos = s.find("\"\"\"").or_else(|| s.find("'''")).unwrap();
-                    let after = &s[end_pos + 3..];
-                    if !after.trim().is_empty() {
-                        return (LineKind::Mixed, Some((end_pos + 3, s.len())));
-                    } else {
-                        return (LineKind::Comment, None);
-                    }
+                    return whole(LineKind::Comment, SpanKind::Comment);
                 }
             } else {
-                return (LineKind::Comment, None);
+                return whole(LineKind::Comment, SpanKind::Comment);
+            }
+        }
+
+        // A multi-line string from a previous line must be closed first
+        let mut start = 0;
+        if let Some(delim) = line.ctx().code_string {
+            match find_string_end(&s, delim) {
+                Some(close) => {
+                    line.ctx().code_string = None;
+                    start = close;
+                }
+                None => return whole(LineKind::Code, SpanKind::Code),
             }
         }
 
         // Check for docstring start
-        if s.starts_with("\"\"\"") || s.starts_with("'''") {
+        if start == 0 && (s.starts_with("\"\"\"") || s.starts_with("'''")) {
             let doc_start = if s.starts_with("\"\"\"") { "\"\"\"" } else { "'''" };
             if s.len() > 3 && s[3..].trim().contains(doc_start) {
                 // Single line docstring
-                return (LineKind::Comment, None);
-            } else {
-                // Multi-line docstring starts
-                line.ctx().in_string = true;
-                let after = &s[3..];
-                if !after.trim().is_empty() {
-                    return (LineKind::Mixed, Some((3, s.len())));
-                } else {
-                    return (LineKind::Comment, None);
-                }
+                return whole(LineKind::Comment, SpanKind::Comment);
             }
+            // Multi-line docstring starts; text after the delimiter belongs to the docstring
+            line.ctx().in_string = true;
+            line.ctx().string_delim = Some(doc_start);
+            return whole(LineKind::Comment, SpanKind::Comment);
         }
 
         // Regular line comments
-        if let Some(prefix) = line.lang().line_comment {
-            if s.starts_with(prefix) {
-                return (LineKind::Comment, None);
-            }
+        if start == 0 && line.lang().line_comments.iter().any(|prefix| s.starts_with(prefix)) {
+            return whole(LineKind::Comment, SpanKind::Comment);
         }
 
         // Inline comments
-        if let Some(comment_pos) = s.find('#') {
+        let quotes = line.lang().quotes;
+        let multiline = line.lang().multiline_strings;
+        if let Some(pos) = find_outside_strings(&s[start..], "#", quotes, multiline) {
+            let comment_pos = start + pos;
             let before = &s[..comment_pos];
             if !before.trim().is_empty() {
-                return (LineKind::Mixed, Some((0, comment_pos)));
+                return LineClassification::from_parts(LineKind::Mixed, &s, off, &[
+                    (SpanKind::Code, 0, comment_pos),
+                    (SpanKind::Comment, comment_pos, len),
+                ]);
             } else {
-                return (LineKind::Comment, None);
+                return whole(LineKind::Comment, SpanKind::Comment);
             }
         }
 
-        (LineKind::Code, None)
+        // Strings such as `x = """` keep going on the next line
+        line.ctx().code_string = unclosed_string(&s[start..], quotes, multiline);
+        whole(LineKind::Code, SpanKind::Code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::langs::lang_type::LangType;
+    use crate::langs::registry::get_lang_def;
+    use crate::syntax::lex_status::LexCtx;
+
+    fn classify_all(classifier: &dyn Classifier, lang: LangType, lines: &[&str]) -> Vec<LineClassification> {
+        let def = get_lang_def(&lang).unwrap();
+        let mut ctx = LexCtx::default();
+        lines
+            .iter()
+            .map(|line| classifier.classify(LineCtx::new(line, &mut ctx, def)))
+            .collect()
+    }
+
+    #[test]
+    fn spans_are_labeled_and_relative_to_raw_line() {
+        let lines = ["  a = f(); /* x */ b();", "    /* doc", "  end */ c();"];
+        let out = classify_all(&DefaultClassifier::new(), LangType::C, &lines);
+
+        assert_eq!(out[0].kind, LineKind::Mixed);
+        let parts: Vec<(SpanKind, &str)> = out[0].spans.iter()
+            .map(|span| (span.kind, &lines[0][span.range.clone()]))
+            .collect();
+        assert_eq!(parts, [
+            (SpanKind::Code, "a = f();"),
+            (SpanKind::Comment, "/* x */"),
+            (SpanKind::Code, "b();"),
+        ]);
+        assert_eq!(out[0].code_text(lines[0]), "a = f(); b();");
+
+        assert_eq!(out[1].kind, LineKind::Comment);
+        assert_eq!(out[1].spans, [LineSpan { kind: SpanKind::Comment, range: 4..10 }]);
+
+        assert_eq!(out[2].kind, LineKind::Mixed);
+        assert_eq!(out[2].comment_spans().map(|r| &lines[2][r]).collect::<Vec<_>>(), ["end */"]);
+        assert_eq!(out[2].code_spans().map(|r| &lines[2][r]).collect::<Vec<_>>(), ["c();"]);
+    }
+
+    #[test]
+    fn python_docstring_spans() {
+        let lines = ["    \"\"\"Summary.", "    \"\"\"", "x = 1  # note", ""];
+        let out = classify_all(&PythonClassifier::new(), LangType::Python, &lines);
+
+        // 多行文档字符串起始行上定界符之后的文本也属于文档字符串
+        assert_eq!(out[0].kind, LineKind::Comment);
+        assert_eq!(out[0].comment_spans().map(|r| &lines[0][r]).collect::<Vec<_>>(), ["\"\"\"Summary."]);
+        assert_eq!(out[0].code_text(lines[0]), "");
+        assert_eq!(out[1].kind, LineKind::Comment);
+        assert_eq!(out[2].kind, LineKind::Mixed);
+        assert_eq!(out[2].code_text(lines[2]), "x = 1");
+        assert_eq!(out[3], LineClassification::blank());
+    }
+
+    #[test]
+    fn multiple_line_comment_markers() {
+        let lines = ["// slash comment", "# hash comment", "local x = 1;  # trailing", "{ url: \"http://a#b\" }  // c"];
+        let out = classify_all(&DefaultClassifier::new(), LangType::Jsonnet, &lines);
+
+        assert_eq!(out[0].kind, LineKind::Comment);
+        assert_eq!(out[1].kind, LineKind::Comment);
+        assert_eq!(out[2].kind, LineKind::Mixed);
+        assert_eq!(out[2].code_text(lines[2]), "local x = 1;");
+        // 字符串里的标记不算，取字符串外最靠前的标记
+        assert_eq!(out[3].code_text(lines[3]), "{ url: \"http://a#b\" }");
+    }
+
+    #[test]
+    fn comment_markers_inside_strings_are_code() {
+        let lines = [
+            r#"let url = "http://example.com";"#,
+            r#"let s = "a \"/* quoted */\" b"; // real"#,
+            r#"let c = '"'; // quote char"#,
+        ];
+        let out = classify_all(&DefaultClassifier::new(), LangType::Rust, &lines);
+        assert_eq!(out[0].kind, LineKind::Code);
+        assert_eq!(out[1].kind, LineKind::Mixed);
+        assert_eq!(out[1].code_text(lines[1]), r#"let s = "a \"/* quoted */\" b";"#);
+
+        // C 中 `'"'` 是字符字面量，其中的双引号不会开始字符串
+        let out = classify_all(&DefaultClassifier::new(), LangType::C, &lines[2..]);
+        assert_eq!(out[0].code_text(lines[2]), r#"let c = '"';"#);
+    }
+
+    #[test]
+    fn hash_inside_strings_is_code() {
+        let lines = [r##"color = "#fff"  # default"##, r"tag = 'issue #12'", r##"escaped = "\"#\"""##];
+        let out = classify_all(&PythonClassifier::new(), LangType::Python, &lines);
+        assert_eq!(out[0].kind, LineKind::Mixed);
+        assert_eq!(out[0].code_text(lines[0]), r##"color = "#fff""##);
+        assert_eq!(out[1].kind, LineKind::Code);
+        assert_eq!(out[2].kind, LineKind::Code);
+
+        let lines = [r##"echo "#not a comment" '#nor this'"##, r#"echo "done" # comment"#];
+        let out = classify_all(&DefaultClassifier::new(), LangType::Shell, &lines);
+        assert_eq!(out[0].kind, LineKind::Code);
+        assert_eq!(out[1].kind, LineKind::Mixed);
+        assert_eq!(out[1].code_text(lines[1]), r#"echo "done""#);
     }
 }
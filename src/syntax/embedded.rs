@@ -0,0 +1,129 @@
+use crate::langs::lang_type::LangType;
+
+/// 含有其他语言片段的复合文件：`<script>` 与 `<style>` 块中的内容按各自的语言统计
+pub fn is_composite(lang: LangType) -> bool {
+    matches!(lang, LangType::Vue | LangType::Html)
+}
+
+/// 复合文件中的一个嵌入块
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedBlock<'a> {
+    pub lang: LangType,
+    /// 块的内容，不含开始与结束标签所在行
+    pub content: &'a str,
+}
+
+/// 依次找出 `<script>`/`<style>` 块，语言由标签的 `lang` 属性决定（默认为 JavaScript/CSS）
+pub fn embedded_blocks(source: &str) -> Vec<EmbeddedBlock<'_>> {
+    let mut blocks = Vec::new();
+    let mut rest = source;
+
+    while let Some((tag, start)) = next_open_tag(rest) {
+        let after = &rest[start..];
+        let Some(open_end) = after.find('>') else {
+            break;
+        };
+        let attrs = &after[1 + tag.len()..open_end];
+        let body = &after[open_end + 1..];
+        let close = format!("</{}", tag);
+        let Some(close_start) = body.find(&close) else {
+            break;
+        };
+
+        if let Some(lang) = block_lang(tag, attrs) {
+            blocks.push(EmbeddedBlock { lang, content: trim_tag_lines(&body[..close_start]) });
+        }
+        rest = &body[close_start + close.len()..];
+    }
+
+    blocks
+}
+
+/// 只保留复合文件中属于 `target` 语言的内容，各块之间以换行连接
+pub fn extract_lang_content(source: &str, target: LangType) -> String {
+    embedded_blocks(source)
+        .into_iter()
+        .filter(|block| block.lang == target)
+        .map(|block| block.content)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn next_open_tag(source: &str) -> Option<(&'static str, usize)> {
+    ["script", "style"]
+        .into_iter()
+        .filter_map(|tag| {
+            source.match_indices(&format!("<{}", tag))
+                .map(|(pos, _)| pos)
+                // 排除 `<scripts>` 这类仅前缀相同的标签
+                .find(|&pos| source[pos + 1 + tag.len()..].starts_with(|c: char| c == '>' || c.is_whitespace()))
+                .map(|pos| (tag, pos))
+        })
+        .min_by_key(|&(_, pos)| pos)
+}
+
+fn block_lang(tag: &str, attrs: &str) -> Option<LangType> {
+    let lang = attr_value(attrs, "lang").map(|v| v.to_lowercase());
+    match (tag, lang.as_deref()) {
+        ("script", None | Some("js" | "javascript" | "jsx")) => Some(LangType::Javascript),
+        ("script", Some("ts" | "typescript" | "tsx")) => Some(LangType::Typescript),
+        ("style", None | Some("css")) => Some(LangType::Css),
+        ("style", Some("scss" | "sass")) => Some(LangType::Sass),
+        _ => None,
+    }
+}
+
+fn attr_value<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    let pattern = format!("{}=", name);
+    let pos = attrs.match_indices(&pattern)
+        .map(|(pos, _)| pos)
+        .find(|&pos| pos == 0 || attrs[..pos].ends_with(char::is_whitespace))?;
+    let value = &attrs[pos + pattern.len()..];
+    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let value = &value[1..];
+    value.find(quote).map(|end| &value[..end])
+}
+
+/// 去掉开始标签行剩余的换行和结束标签前的缩进，使内容只包含块内的完整行
+fn trim_tag_lines(body: &str) -> &str {
+    let body = body.strip_prefix("\r\n").or_else(|| body.strip_prefix('\n')).unwrap_or(body);
+    match body.rfind('\n') {
+        Some(pos) if body[pos + 1..].trim().is_empty() => body[..pos].strip_suffix('\r').unwrap_or(&body[..pos]),
+        _ => body,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VUE: &str = "\
+<template>
+  <div>{{ msg }}</div>
+</template>
+
+<script lang=\"ts\">
+// greeting
+export default {
+  data() { return { msg: 'hi' } }
+}
+</script>
+
+<style scoped>
+div { color: red; }
+</style>
+";
+
+    #[test]
+    fn test_embedded_blocks() {
+        let blocks = embedded_blocks(VUE);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].lang, LangType::Typescript);
+        assert_eq!(blocks[0].content, "// greeting\nexport default {\n  data() { return { msg: 'hi' } }\n}");
+        assert_eq!(blocks[1].lang, LangType::Css);
+        assert_eq!(blocks[1].content, "div { color: red; }");
+
+        assert_eq!(extract_lang_content(VUE, LangType::Css), "div { color: red; }");
+        assert_eq!(extract_lang_content(VUE, LangType::Javascript), "");
+    }
+}
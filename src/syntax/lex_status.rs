@@ -1,9 +1,15 @@
 use crate::langs::lang_def::LangDef;
 
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct LexCtx {
     pub in_block_comment: bool,
+    /// 未闭合块注释的嵌套深度，不允许嵌套的语言最多为 1
+    pub comment_depth: usize,
     pub in_string: bool,
+    /// 当前打开的字符串/文档字符串定界符，只有遇到相同的定界符才会闭合
+    pub string_delim: Option<&'static str>,
+    /// 跨行的普通字符串（非文档字符串）尚未闭合时的定界符
+    pub code_string: Option<&'static str>,
 }
 
 #[derive(Debug)]
@@ -30,6 +36,10 @@ impl<'a> LineCtx<'a> {
     pub fn trimmed(&self) -> &str {
         self.trimmed
     }
+    /// `trimmed` 在原始行中的字节起点
+    pub fn offset(&self) -> usize {
+        self.raw.len() - self.raw.trim_start().len()
+    }
     pub fn ctx(&mut self) -> &mut LexCtx {
         self.ctx
     }
@@ -1,191 +1,698 @@
+use std::borrow::Cow;
 use std::io::BufRead;
 
-use regex::RegexSet;
+use regex::{Regex, RegexSet};
 
+use crate::consts::DEFAULT_TAB_WIDTH;
+use crate::langs::lang_def::LangDef;
 use crate::langs::lang_type::LangType;
-use crate::langs::registry::{get_function_regex, get_lang_def};
+use crate::langs::registry::{get_branch_regex, get_class_regex, get_function_regex, get_lang_def};
 use crate::stats::FileStat;
-use crate::syntax::classifier::{Classifier, DefaultClassifier, PythonClassifier, LineKind};
+use crate::syntax::classifier::{outside_strings, Classifier, DefaultClassifier, PythonClassifier, LineKind};
 
+use super::LexOptions;
 use super::lex_status::{LineCtx, LexCtx, FnCtx, PyCtx};
 
 pub trait Lexer: Send + Sync {
     
     fn lex(&self, reader: &mut dyn BufRead) -> Result<FileStat, String>;
+
+    /// 逐行给出分类结果，用于排查统计口径；不逐行分类的词法分析器（如 Markdown）返回错误
+    fn explain(&self, _reader: &mut dyn BufRead) -> Result<Vec<LineExplanation>, String> {
+        Err("Per-line explanation is not supported for this language".to_string())
+    }
+}
+
+/// 单行的分类说明
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineExplanation {
+    /// 行号，从 1 开始
+    pub number: usize,
+    pub text: String,
+    pub kind: LineKind,
+    /// 本行的代码部分匹配到了函数定义
+    pub function_start: bool,
+    /// 本行的代码部分匹配到了类/结构体定义
+    pub class_start: bool,
+}
+
+/// 单行的分析结果
+///
+/// 分析只依赖 `LexCtx`，函数范围等跨行统计放到 `LineLexer::fold` 中按顺序完成，
+/// 因此一个文件可以切成多段并行分析后再依次折叠。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LineRecord {
+    pub kind: LineKind,
+    /// 代码部分匹配到了函数定义
+    pub fn_match: bool,
+    /// 代码部分匹配到了类/结构体定义，同时匹配函数定义的行只算函数
+    pub class_match: bool,
+    /// 代码部分 `{` 与 `}` 的数量之差，字符串与字符字面量中的括号不算
+    pub brace_delta: isize,
+    /// 逐个处理本行括号、深度不低于 0 时，行尾深度的下限：新深度 = max(原深度 + brace_delta, brace_floor)
+    pub brace_floor: isize,
+    /// 行缩进宽度，空行和 `#` 开头的行为 None（缩进敏感语言使用）
+    pub indent: Option<usize>,
+    /// 注释行的内容看起来像被注释掉的代码
+    pub commented_code: bool,
+    /// 含有语言约定的特殊注释（如 `#:`）
+    pub special_comment: bool,
+    /// 行尾仍处在块注释或文档字符串内
+    pub inside_comment: bool,
+    /// 本行是区域标记（如 `// region`、`#pragma endregion`）
+    pub region: Option<RegionMarker>,
+    /// 代码部分的分支关键字个数，仅在统计复杂度时计算
+    pub branches: usize,
+    /// 注释部分出现的待办标记个数
+    pub todos: usize,
+}
+
+/// 区域标记，用于按区域统计行数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum RegionMarker {
+    Start(String),
+    End,
+}
+
+/// 识别 `// region Name`、`#region Name`、`#pragma region Name` 及对应的 `endregion`
+///
+/// 名字前的 `:` 会被去掉（如 rust-analyzer 使用的 `// region: Name`）。
+pub(crate) fn region_marker(raw: &str) -> Option<RegionMarker> {
+    let text = raw.trim();
+    let text = text.strip_prefix("#pragma")
+        .or_else(|| text.strip_prefix("//").map(|t| t.trim_start().trim_start_matches('#')))
+        .or_else(|| text.strip_prefix('#'))?
+        .trim_start();
+
+    let is_word_end = |rest: &str| rest.is_empty() || rest.starts_with(|c: char| c.is_whitespace() || c == ':');
+    if let Some(rest) = text.strip_prefix("endregion") {
+        is_word_end(rest).then_some(RegionMarker::End)
+    } else if let Some(rest) = text.strip_prefix("region") {
+        is_word_end(rest).then(|| RegionMarker::Start(rest.trim_start_matches(':').trim().to_string()))
+    } else {
+        None
+    }
+}
+
+impl LineRecord {
+    fn new(kind: LineKind) -> Self {
+        Self {
+            kind,
+            fn_match: false,
+            class_match: false,
+            brace_delta: 0,
+            brace_floor: 0,
+            indent: None,
+            commented_code: false,
+            special_comment: false,
+            inside_comment: false,
+            region: None,
+            branches: 0,
+            todos: 0,
+        }
+    }
+}
+
+/// 拆成“逐行分析 + 顺序折叠”两步的词法分析器
+pub(crate) trait LineLexer: Send + Sync {
+    /// 折叠时在行与行之间传递的状态
+    type Fold: Default;
+
+    /// 要求语言已注册，由 `lex_lines` 和并行入口保证
+    fn analyze(&self, raw: &str, ctx: &mut LexCtx) -> LineRecord;
+
+    fn fold(&self, stat: &mut FileStat, state: &mut Self::Fold, record: &LineRecord);
+
+    /// 文件开头的注释块是否单独统计为 `header_lines`
+    fn skip_header_comments(&self) -> bool {
+        false
+    }
+}
+
+/// 文件开头注释块（如许可证头）的识别状态
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct HeaderState {
+    started: bool,
+    done: bool,
+}
+
+/// 尚未结束的区域在 `FileStat.regions` 中的下标，按嵌套顺序排列
+#[derive(Debug, Default, Clone)]
+pub(crate) struct RegionState {
+    open: Vec<usize>,
+}
+
+/// 统计区域内的行数：标记行本身不计入所在区域，但计入外层区域；未结束的区域统计到文件末尾
+fn fold_region(stat: &mut FileStat, regions: &mut RegionState, record: &LineRecord) {
+    match &record.region {
+        Some(RegionMarker::Start(name)) => {
+            for &i in &regions.open {
+                stat.regions[i].1 += 1;
+            }
+            regions.open.push(stat.regions.len());
+            stat.regions.push((name.clone(), 0));
+        }
+        Some(RegionMarker::End) => {
+            regions.open.pop();
+            for &i in &regions.open {
+                stat.regions[i].1 += 1;
+            }
+        }
+        None => {
+            for &i in &regions.open {
+                stat.regions[i].1 += 1;
+            }
+        }
+    }
+}
+
+/// 折叠一行；开启跳过文件头时，开头注释块中的行从注释（块注释内的空行从空行）移到 `header_lines`
+///
+/// 注释块之前的空行不算文件头，块注释之外的空行或任何代码行结束文件头。
+pub(crate) fn fold_line<L: LineLexer>(
+    lexer: &L,
+    stat: &mut FileStat,
+    state: &mut L::Fold,
+    header: &mut HeaderState,
+    regions: &mut RegionState,
+    record: &LineRecord,
+) {
+    lexer.fold(stat, state, record);
+    fold_region(stat, regions, record);
+    stat.complexity += record.branches;
+    stat.todos += record.todos;
+    if header.done || !lexer.skip_header_comments() {
+        return;
+    }
+    match record.kind {
+        LineKind::Comment | LineKind::DocComment => {
+            header.started = true;
+            stat.comments -= 1;
+            stat.header_lines += 1;
+        }
+        LineKind::Blank if header.started && record.inside_comment => {
+            stat.blanks -= 1;
+            stat.header_lines += 1;
+        }
+        LineKind::Blank if !header.started => {}
+        _ => header.done = true,
+    }
+}
+
+/// 判断一行注释是否像被注释掉的代码（启发式）
+///
+/// 去掉行注释前缀后用同一个分类器重新分类：结果是代码，并且匹配语言的函数/类模式
+/// 或以 `;`、`{`、`}` 结尾时视为代码。文档注释和以句号结尾的句子不算。
+fn is_commented_code<C: Classifier>(
+    classifier: &C,
+    def: &'static LangDef,
+    patterns: &[Option<&RegexSet>],
+    line: &str,
+) -> bool {
+    let trimmed = line.trim();
+    let Some(body) = def.line_comments.iter().find_map(|prefix| trimmed.strip_prefix(prefix)) else {
+        return false;
+    };
+    let body = body.trim();
+    if body.is_empty()
+        || body.ends_with('.')
+        || def.doc_comment.is_some_and(|doc| trimmed.starts_with(doc)) {
+        return false;
+    }
+
+    let mut ctx = LexCtx::default();
+    if classifier.classify(LineCtx::new(body, &mut ctx, def)).kind != LineKind::Code {
+        return false;
+    }
+
+    patterns.iter().flatten().any(|re| re.is_match(body)) || body.ends_with([';', '{', '}'])
+}
+
+/// 逐行分析并立即折叠
+pub(crate) fn lex_lines<L: LineLexer>(lexer: &L, reader: &mut dyn BufRead) -> Result<FileStat, String> {
+    let mut stat = FileStat::default();
+    let mut ctx = LexCtx::default();
+    let mut state = L::Fold::default();
+    let mut header = HeaderState::default();
+    let mut regions = RegionState::default();
+
+    let mut eol = EolState::default();
+
+    let mut buf = Vec::new();
+    while let Some(raw) = read_line_lossy(reader, &mut buf).map_err(|e| e.to_string())? {
+        eol.record(&buf);
+        // CRLF 文件的 `\r` 也不属于行内容
+        let raw = raw.strip_suffix('\r').unwrap_or(&raw);
+        let record = lexer.analyze(raw, &mut ctx);
+        fold_line(lexer, &mut stat, &mut state, &mut header, &mut regions, &record);
+    }
+    stat.mixed_eol = eol.is_mixed();
+
+    Ok(stat)
+}
+
+/// 逐行分析但不折叠，得到每行的分类说明
+fn explain_lines<L: LineLexer>(lexer: &L, reader: &mut dyn BufRead) -> Result<Vec<LineExplanation>, String> {
+    let mut ctx = LexCtx::default();
+    let mut lines = Vec::new();
+
+    let mut buf = Vec::new();
+    while let Some(raw) = read_line_lossy(reader, &mut buf).map_err(|e| e.to_string())? {
+        let raw = raw.strip_suffix('\r').unwrap_or(&raw);
+        let record = lexer.analyze(raw, &mut ctx);
+        lines.push(LineExplanation {
+            number: lines.len() + 1,
+            text: raw.to_string(),
+            kind: record.kind,
+            function_start: record.fn_match,
+            class_start: record.class_match,
+        });
+    }
+
+    Ok(lines)
+}
+
+/// 记录文件中出现过的行尾：CRLF 与单独的 LF，最后一行没有换行时不计
+#[derive(Debug, Default)]
+pub(crate) struct EolState {
+    crlf: bool,
+    lf: bool,
+}
+
+impl EolState {
+    /// `line` 是含行尾的原始字节
+    pub(crate) fn record(&mut self, line: &[u8]) {
+        if line.ends_with(b"\r\n") {
+            self.crlf = true;
+        } else if line.ends_with(b"\n") {
+            self.lf = true;
+        }
+    }
+
+    pub(crate) fn is_mixed(&self) -> bool {
+        self.crlf && self.lf
+    }
+}
+
+/// 读取一行并去掉结尾的 `\n`，非法的 UTF-8 序列替换为 U+FFFD；读到末尾时返回 `None`
+fn read_line_lossy(reader: &mut dyn BufRead, buf: &mut Vec<u8>) -> std::io::Result<Option<String>> {
+    buf.clear();
+    if reader.read_until(b'\n', buf)? == 0 {
+        return Ok(None);
+    }
+    let line = buf.strip_suffix(b"\n").unwrap_or(buf);
+    Ok(Some(String::from_utf8_lossy(line).into_owned()))
 }
 
 pub struct DefaultLexer<C: Classifier = DefaultClassifier> {
     pub lang_type: LangType,
     classifier: C,
+    def: Option<&'static LangDef>,
+    function_regexes: Option<&'static RegexSet>,
+    class_regexes: Option<&'static RegexSet>,
+    /// 统计复杂度时使用的分支关键字正则，未开启时为 None
+    branch_regex: Option<&'static Regex>,
+    /// 统计看起来像被注释掉的代码的注释行
+    detect_commented_code: bool,
+    skip_header_comments: bool,
+    /// 注释中要统计的待办标记，为空时不统计
+    todo_markers: Vec<String>,
 }
 
 impl DefaultLexer {
     pub fn new(lang: LangType) -> Self {
-        Self {
-            lang_type: lang,
-            classifier: DefaultClassifier,
-        }
+        Self::with_classifier(lang, DefaultClassifier::new())
+    }
+
+    pub fn with_blanks_in_comments_are_blank(mut self, enable: bool) -> Self {
+        self.classifier = self.classifier.with_blanks_in_comments_are_blank(enable);
+        self
+    }
+
+    pub fn with_blanks_in_strings_are_code(mut self, enable: bool) -> Self {
+        self.classifier = self.classifier.with_blanks_in_strings_are_code(enable);
+        self
     }
 }
 
 impl<C: Classifier> Lexer for DefaultLexer<C> {
     fn lex(&self, reader: &mut dyn BufRead) -> Result<FileStat, String> {
-        let def = get_lang_def(&self.lang_type).ok_or("Language not supported")?;
-        let function_regexes = get_function_regex(&self.lang_type);
+        self.def.ok_or("Language not supported")?;
+        lex_lines(self, reader)
+    }
+
+    fn explain(&self, reader: &mut dyn BufRead) -> Result<Vec<LineExplanation>, String> {
+        self.def.ok_or("Language not supported")?;
+        explain_lines(self, reader)
+    }
+}
 
-        let mut stat = FileStat::default();
-        let mut ctx = LexCtx::default();
-        let mut fn_ctx = FnCtx::default();
+impl<C: Classifier> LineLexer for DefaultLexer<C> {
+    type Fold = FnCtx;
 
-        for line in reader.lines() {
-            let raw = line.map_err(|e| e.to_string())?;
-            let trimmed = raw.trim();
+    fn analyze(&self, raw: &str, ctx: &mut LexCtx) -> LineRecord {
+        let def = self.def.expect("language checked before lexing");
+        let cls = self.classifier.classify(LineCtx::new(raw, ctx, def));
+        let mut record = LineRecord::new(cls.kind);
+        record.special_comment = cls.has_comment_marker(raw, def.special_comments);
+        record.inside_comment = ctx.in_block_comment;
+        record.region = region_marker(raw);
+        record.todos = cls.count_comment_markers(raw, &self.todo_markers);
 
-            if fn_ctx.in_function && fn_ctx.prev == 0 {
-                fn_ctx.in_function = false;
-            }
+        if self.detect_commented_code && cls.kind == LineKind::Comment && !ctx.in_block_comment {
+            let patterns = [self.function_regexes, self.class_regexes];
+            record.commented_code = is_commented_code(&self.classifier, def, &patterns, raw);
+        }
 
-            let lctx = LineCtx::new(&raw, &mut ctx, &def);
-            let (kind, pos) = self.classifier.classify(lctx);
-
-            stat.lines += 1;
-            match kind {
-                LineKind::Blank => stat.blanks += 1,
-                LineKind::Comment | LineKind::DocComment => stat.comments += 1,
-                LineKind::Code => {
-                    stat.code += 1;
-                    if let Some(regexes) = &function_regexes {
-                        self.update_fn_ctx(trimmed, regexes, &mut fn_ctx);
-                    }
+        let code = match cls.kind {
+            LineKind::Code => Cow::Borrowed(raw.trim()),
+            LineKind::Mixed => Cow::Owned(cls.code_text(raw)),
+            _ => return record,
+        };
+        if let Some(regexes) = self.function_regexes {
+            record.fn_match = regexes.is_match(&code);
+            (record.brace_delta, record.brace_floor) = brace_effect(&outside_strings(&code, def.quotes, def.multiline_strings));
+        }
+        if let Some(regexes) = self.class_regexes {
+            record.class_match = !record.fn_match && regexes.is_match(&code);
+        }
+        if let Some(re) = self.branch_regex {
+            record.branches = re.find_iter(&code).count();
+        }
+        record
+    }
+
+    fn fold(&self, stat: &mut FileStat, fn_ctx: &mut FnCtx, record: &LineRecord) {
+        if fn_ctx.in_function && fn_ctx.prev == 0 {
+            fn_ctx.in_function = false;
+        }
+
+        stat.lines += 1;
+        if record.commented_code {
+            stat.commented_code += 1;
+        }
+        if record.special_comment {
+            stat.special_comments += 1;
+        }
+        match record.kind {
+            LineKind::Blank => stat.blanks += 1,
+            LineKind::Comment | LineKind::DocComment => stat.comments += 1,
+            LineKind::Code | LineKind::Mixed => {
+                stat.code += 1;
+                if record.class_match {
+                    stat.classes += 1;
                 }
-                LineKind::Mixed => {
-                    stat.code += 1;
-                    if let Some((start, end)) = pos {
-                        let raw = &trimmed[start..end];
-                        if let Some(regexes) = &function_regexes {
-                            self.update_fn_ctx(raw, regexes, &mut fn_ctx);
-                        }
-                    }
+                if record.fn_match {
+                    stat.functions += 1;
+                }
+                if self.function_regexes.is_some() {
+                    self.update_fn_ctx(record, fn_ctx);
                 }
             }
-            if fn_ctx.in_function {
-                stat.functions += 1;
-            }
         }
+        if fn_ctx.in_function {
+            stat.function_lines += 1;
+        }
+    }
 
-        Ok(stat)
+    fn skip_header_comments(&self) -> bool {
+        self.skip_header_comments
     }
 }
 
 impl<C: Classifier> DefaultLexer<C> {
+    /// 使用自定义的行分类器，适用于注释规则无法用 `LangDef` 描述的语言
+    pub fn with_classifier(lang: LangType, classifier: C) -> Self {
+        Self {
+            lang_type: lang,
+            classifier,
+            def: get_lang_def(&lang),
+            function_regexes: get_function_regex(&lang),
+            class_regexes: get_class_regex(&lang),
+            branch_regex: None,
+            detect_commented_code: false,
+            skip_header_comments: false,
+            todo_markers: Vec::new(),
+        }
+    }
+
+    /// 使用 `classifier` 并按 `options` 设置其余选项；分类器自身的选项（如空行归属）由调用方设置
+    pub fn from_options(lang: LangType, classifier: C, options: &LexOptions) -> Self {
+        Self::with_classifier(lang, classifier)
+            .with_detect_commented_code(options.detect_commented_code)
+            .with_skip_header_comments(options.skip_header_comments)
+            .with_complexity(options.count_complexity)
+            .with_todo_markers(options.todo_markers.clone())
+    }
+
+    pub fn with_detect_commented_code(mut self, enable: bool) -> Self {
+        self.detect_commented_code = enable;
+        self
+    }
+
+    /// 统计代码中的分支关键字，结果计入 `FileStat.complexity`
+    pub fn with_complexity(mut self, enable: bool) -> Self {
+        self.branch_regex = enable.then(|| get_branch_regex(&self.lang_type)).flatten();
+        self
+    }
+
+    /// 文件开头的注释块不计入注释，单独统计为 `header_lines`
+    pub fn with_skip_header_comments(mut self, enable: bool) -> Self {
+        self.skip_header_comments = enable;
+        self
+    }
+
+    /// 统计注释中的待办标记（如 `TODO`），结果计入 `FileStat.todos`
+    pub fn with_todo_markers(mut self, markers: Vec<String>) -> Self {
+        self.todo_markers = markers;
+        self
+    }
+
     fn update_fn_ctx(
         &self, 
-        raw: &str, 
-        regexes: &RegexSet, 
+        record: &LineRecord, 
         ctx: &mut FnCtx) {
-            if !ctx.in_function {
-                if regexes.is_match(raw) {
-                    ctx.in_function = true;
-                    ctx.depth = 0;
-                }
-            }
-            for ch in raw.chars() {
-                if ch == '{' {
-                    ctx.depth += 1;
-                } else if ch == '}' {
-                    ctx.depth = ctx.depth.saturating_sub(1);
-                }
+            if !ctx.in_function && record.fn_match {
+                ctx.in_function = true;
+                ctx.depth = 0;
             }
+            ctx.depth = (ctx.depth + record.brace_delta).max(record.brace_floor);
 
             ctx.prev = ctx.depth;
         }
 }
 
-pub struct PythonLexer;
+/// 一行代码中的括号对函数深度的影响 `(delta, floor)`，`}` 不会使深度低于 0；`'{'` 这样的字符字面量跳过
+fn brace_effect(code: &str) -> (isize, isize) {
+    let chars: Vec<char> = code.chars().collect();
+    let (mut delta, mut floor) = (0, 0);
+    for (i, &ch) in chars.iter().enumerate() {
+        let in_char_literal = i > 0 && chars[i - 1] == '\'' && chars.get(i + 1) == Some(&'\'');
+        match ch {
+            '{' if !in_char_literal => {
+                delta += 1;
+                floor += 1;
+            }
+            '}' if !in_char_literal => {
+                delta -= 1;
+                floor = (floor - 1).max(0);
+            }
+            _ => (),
+        }
+    }
+    (delta, floor)
+}
+
+pub struct PythonLexer {
+    tab_width: usize,
+    classifier: PythonClassifier,
+    def: Option<&'static LangDef>,
+    function_regexes: Option<&'static RegexSet>,
+    /// 统计复杂度时使用的分支关键字正则，未开启时为 None
+    branch_regex: Option<&'static Regex>,
+    /// 统计看起来像被注释掉的代码的注释行
+    detect_commented_code: bool,
+    skip_header_comments: bool,
+    /// 注释中要统计的待办标记，为空时不统计
+    todo_markers: Vec<String>,
+}
 
 impl PythonLexer {
-    pub fn new() -> Self { PythonLexer }
+    pub fn new() -> Self {
+        PythonLexer {
+            tab_width: DEFAULT_TAB_WIDTH,
+            classifier: PythonClassifier::new(),
+            def: get_lang_def(&LangType::Python),
+            function_regexes: get_function_regex(&LangType::Python),
+            branch_regex: None,
+            detect_commented_code: false,
+            skip_header_comments: false,
+            todo_markers: Vec::new(),
+        }
+    }
+
+    /// 按 `options` 设置全部选项
+    pub fn from_options(options: &LexOptions) -> Self {
+        Self::new()
+            .with_tab_width(options.tab_width)
+            .with_blanks_in_comments_are_blank(options.blanks_in_comments_are_blank)
+            .with_blanks_in_strings_are_code(options.blanks_in_strings_are_code)
+            .with_detect_commented_code(options.detect_commented_code)
+            .with_skip_header_comments(options.skip_header_comments)
+            .with_complexity(options.count_complexity)
+            .with_todo_markers(options.todo_markers.clone())
+    }
+
+    pub fn with_detect_commented_code(mut self, enable: bool) -> Self {
+        self.detect_commented_code = enable;
+        self
+    }
+
+    /// 统计代码中的分支关键字，结果计入 `FileStat.complexity`
+    pub fn with_complexity(mut self, enable: bool) -> Self {
+        self.branch_regex = enable.then(|| get_branch_regex(&LangType::Python)).flatten();
+        self
+    }
+
+    /// 文件开头的注释块不计入注释，单独统计为 `header_lines`
+    pub fn with_skip_header_comments(mut self, enable: bool) -> Self {
+        self.skip_header_comments = enable;
+        self
+    }
+
+    /// 统计注释中的待办标记（如 `TODO`），结果计入 `FileStat.todos`
+    pub fn with_todo_markers(mut self, markers: Vec<String>) -> Self {
+        self.todo_markers = markers;
+        self
+    }
+
+    pub fn with_tab_width(mut self, tab_width: usize) -> Self {
+        self.tab_width = tab_width;
+        self
+    }
+
+    pub fn with_blanks_in_comments_are_blank(mut self, enable: bool) -> Self {
+        self.classifier = self.classifier.with_blanks_in_comments_are_blank(enable);
+        self
+    }
+
+    pub fn with_blanks_in_strings_are_code(mut self, enable: bool) -> Self {
+        self.classifier = self.classifier.with_blanks_in_strings_are_code(enable);
+        self
+    }
+}
+
+impl Default for PythonLexer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-/// 计算一行真正的缩进空格数（1 tab = 4 space）
-fn calc_indent(raw: &str) -> usize {
+/// 计算一行真正的缩进空格数（1 tab = tab_width space）
+fn calc_indent(raw: &str, tab_width: usize) -> usize {
     raw.chars()
         .take_while(|c| c.is_whitespace())
-        .map(|c| if c == '\t' { 4 } else { 1 })
+        .map(|c| if c == '\t' { tab_width } else { 1 })
         .sum()
 }
 
 impl Lexer for PythonLexer {
     fn lex(&self, reader: &mut dyn BufRead) -> Result<FileStat, String> {
-        let def = get_lang_def(&LangType::Python)
-                        .ok_or("Python language not supported")?;
-        let fn_res = get_function_regex(&LangType::Python);
-
-        let mut stat = FileStat::default();
-        let mut ctx  = LexCtx::default();
-        let classifier = PythonClassifier::new();
-        let mut py = PyCtx::default();
-
-        for line in reader.lines() {
-            let raw = line.map_err(|e| e.to_string())?;
-            let trimmed = raw.trim();
-
-            /* ---------- 0. 先处理“上一行是函数定义”的遗留标记 ---------- */
-            if py.fn_def_line {
-                py.fn_def_line = false;
-                py.in_fn       = true;
-                py.base_indent = py.cur_indent;   // 函数体起始缩进
-            }
+        self.def.ok_or("Python language not supported")?;
+        lex_lines(self, reader)
+    }
 
-            /* ---------- 1. 分类本行 ---------- */
-            let lctx = LineCtx::new(&raw, &mut ctx, &def);
-            let (kind, pos) = classifier.classify(lctx);
-
-            stat.lines += 1;
-            match kind {
-                LineKind::Blank => stat.blanks += 1,
-                LineKind::Comment | LineKind::DocComment => stat.comments += 1,
-                LineKind::Code | LineKind::Mixed => {
-                    stat.code += 1;
-
-                    // 只在代码段里找函数定义
-                    let code_slice = match kind {
-                        LineKind::Mixed => {
-                            let (s, e) = pos.unwrap();
-                            &trimmed[s..e]
-                        }
-                        _ => trimmed,
-                    };
-
-                    if let Some(ref re) = fn_res {
-                        if re.is_match(code_slice) {
-                            py.fn_def_line  = true; // 延迟到下一行才真正进入函数体
-                            stat.functions += 1;
-                        }
-
-                    }
-                }
-            }
-        
-            /* ---------- 2. 维护缩进 & 函数体范围 ---------- */
-            if trimmed.is_empty() || trimmed.starts_with('#') {
-                continue;          // 空行或纯注释不影响缩进逻辑
+    fn explain(&self, reader: &mut dyn BufRead) -> Result<Vec<LineExplanation>, String> {
+        self.def.ok_or("Python language not supported")?;
+        explain_lines(self, reader)
+    }
+}
+
+impl LineLexer for PythonLexer {
+    type Fold = PyCtx;
+
+    fn analyze(&self, raw: &str, ctx: &mut LexCtx) -> LineRecord {
+        let def = self.def.expect("language checked before lexing");
+        let trimmed = raw.trim();
+
+        /* ---------- 1. 分类本行 ---------- */
+        let cls = self.classifier.classify(LineCtx::new(raw, ctx, def));
+        let mut record = LineRecord::new(cls.kind);
+        record.special_comment = cls.has_comment_marker(raw, def.special_comments);
+        record.inside_comment = ctx.in_string;
+        record.region = region_marker(raw);
+        record.todos = cls.count_comment_markers(raw, &self.todo_markers);
+
+        if self.detect_commented_code && cls.kind == LineKind::Comment && !ctx.in_string {
+            let patterns = [self.function_regexes, get_class_regex(&LangType::Python)];
+            record.commented_code = is_commented_code(&self.classifier, def, &patterns, raw);
+        }
+
+        // 只在代码段里找函数定义
+        if let Some(re) = self.function_regexes {
+            let code_slice = match cls.kind {
+                LineKind::Code => Cow::Borrowed(trimmed),
+                LineKind::Mixed => Cow::Owned(cls.code_text(raw)),
+                _ => Cow::Borrowed(""),
+            };
+            record.fn_match = !code_slice.is_empty() && re.is_match(&code_slice);
+            if let Some(branches) = self.branch_regex {
+                record.branches = branches.find_iter(&code_slice).count();
             }
+        }
+
+        // 空行或纯注释不影响缩进逻辑
+        if !trimmed.is_empty() && !trimmed.starts_with('#') {
+            record.indent = Some(calc_indent(raw, self.tab_width));
+        }
+        record
+    }
 
-            let indent = calc_indent(&raw);
-            py.cur_indent = indent;
+    fn fold(&self, stat: &mut FileStat, py: &mut PyCtx, record: &LineRecord) {
+        /* ---------- 0. 先处理“上一行是函数定义”的遗留标记 ---------- */
+        if py.fn_def_line {
+            py.fn_def_line = false;
+            py.in_fn       = true;
+            py.base_indent = py.cur_indent;   // 函数体起始缩进
+        }
 
-            if py.in_fn {
-                // 当前行缩进 ≤ 函数基准缩进  →  退出函数体
-                if indent <= py.base_indent {
-                    py.in_fn = false;
+        stat.lines += 1;
+        if record.commented_code {
+            stat.commented_code += 1;
+        }
+        if record.special_comment {
+            stat.special_comments += 1;
+        }
+        match record.kind {
+            LineKind::Blank => stat.blanks += 1,
+            LineKind::Comment | LineKind::DocComment => stat.comments += 1,
+            LineKind::Code | LineKind::Mixed => {
+                stat.code += 1;
+                if record.fn_match {
+                    py.fn_def_line  = true; // 延迟到下一行才真正进入函数体
+                    stat.functions += 1;
+                    stat.function_lines += 1;
                 }
             }
-            if py.in_fn {
-                stat.functions += 1;
-            }
         }
-        Ok(stat)
+
+        /* ---------- 2. 维护缩进 & 函数体范围 ---------- */
+        let Some(indent) = record.indent else {
+            return;
+        };
+        py.cur_indent = indent;
+
+        // 当前行缩进 ≤ 函数基准缩进  →  退出函数体
+        if py.in_fn && indent <= py.base_indent {
+            py.in_fn = false;
+        }
+        if py.in_fn {
+            stat.function_lines += 1;
+        }
+    }
+
+    fn skip_header_comments(&self) -> bool {
+        self.skip_header_comments
     }
 }
 
@@ -197,11 +704,27 @@ impl MdLexer {
     }
 }
 
+impl Default for MdLexer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Lexer for MdLexer {
     fn lex(&self, reader: &mut dyn BufRead) -> Result<FileStat, String> {
-        let mut stat = FileStat::default();
-        stat.lines = reader.lines().count();
-        Ok(stat)
+        let mut lines = 0;
+        let mut eol = EolState::default();
+        let mut buf = Vec::new();
+        while reader.read_until(b'\n', &mut buf).map_err(|e| e.to_string())? > 0 {
+            lines += 1;
+            eol.record(&buf);
+            buf.clear();
+        }
+        Ok(FileStat {
+            lines,
+            mixed_eol: eol.is_mixed(),
+            ..Default::default()
+        })
     }
 }
 
@@ -239,8 +762,207 @@ int main() {
         assert_eq!(stat.comments, 4);
         // 纯代码行
         assert_eq!(stat.code, 6);
-        // 函数数
-        assert_eq!(stat.functions, 5);
+        // 函数数（add、main）与函数体行数
+        assert_eq!(stat.functions, 2);
+        assert_eq!(stat.function_lines, 5);
+    }
+
+    #[test]
+    fn braces_inside_literals_do_not_extend_functions() {
+        let code = "fn a() {\n    println!(\"}}\");\n}\nconst A: i32 = 1;\nconst B: i32 = 2;\nconst C: i32 = 3;\nconst D: i32 = 4;\n";
+        let stat = DefaultLexer::new(LangType::Rust).lex(&mut Cursor::new(code)).unwrap();
+        assert_eq!((stat.functions, stat.function_lines), (1, 3));
+
+        let code = "fn b() {\n    let open = '{';\n}\nconst A: i32 = 1;\n";
+        let stat = DefaultLexer::new(LangType::Rust).lex(&mut Cursor::new(code)).unwrap();
+        assert_eq!((stat.functions, stat.function_lines), (1, 3));
+    }
+
+    #[test]
+    fn allman_style_definitions_are_functions() {
+        let code = "public int Sign(int x)\n{\n    if (x > 0)\n    {\n        return 1;\n    }\n    else if (x < 0)\n    {\n        return -1;\n    }\n    return 0;\n}\n";
+        let stat = DefaultLexer::new(LangType::Csharp).lex(&mut Cursor::new(code)).unwrap();
+        assert_eq!(stat.functions, 1);
+
+        let stat = DefaultLexer::new(LangType::D).lex(&mut Cursor::new("void main()\n{\n}\n")).unwrap();
+        assert_eq!(stat.functions, 1);
+    }
+
+    #[test]
+    fn brace_effect_clamps_at_zero() {
+        assert_eq!(brace_effect("{"), (1, 1));
+        assert_eq!(brace_effect("}}{"), (-1, 1));
+        assert_eq!(brace_effect("} else {"), (0, 1));
+        assert_eq!(brace_effect("'{' '}'"), (0, 0));
+    }
+
+    #[test]
+    fn count_rust_structs() {
+        let code = r#"
+/// 点
+pub struct Point {
+    x: i32,
+}
+
+enum Shape { Circle, Square }
+
+// struct Commented;
+impl Point {
+    fn new() -> Self { Point { x: 0 } }
+}
+
+fn make_struct() {}
+"#;
+        let stat = DefaultLexer::new(LangType::Rust)
+            .lex(&mut Cursor::new(code))
+            .unwrap();
+
+        // struct、enum、impl 各一个；注释中的与函数定义行不算
+        assert_eq!(stat.classes, 3);
+    }
+
+    #[test]
+    fn count_rust_nested_block_comments() {
+        let code = r#"/* outer
+   /* inner */
+   still a comment
+*/
+let x = 1; /* a /* b */ c */ let y = 2;
+/* /* unclosed inner */
+let z = 3;
+*/
+fn f() {}
+"#;
+        let stat = DefaultLexer::new(LangType::Rust)
+            .lex(&mut Cursor::new(code))
+            .unwrap();
+
+        // 内层的 `*/` 不会结束外层注释
+        assert_eq!(stat.lines, 9);
+        assert_eq!(stat.comments, 7);
+        assert_eq!(stat.code, 2);
+
+        // 不允许嵌套的语言在第一个 `*/` 处结束注释
+        let stat = DefaultLexer::new(LangType::C)
+            .lex(&mut Cursor::new(code))
+            .unwrap();
+        assert_eq!(stat.comments, 3);
+        assert_eq!(stat.code, 6);
+    }
+
+    #[test]
+    fn count_c_complexity() {
+        let code = r#"int classify(int x, int y) {
+    // if this were counted, the total would be off
+    if (x > 0 && y > 0) {
+        return 1;
+    } else if (x < 0 || y < 0) {
+        return -1;
+    }
+    for (int i = 0; i < x; i++) {
+        while (y-- > 0) {}
+    }
+    switch (x) {
+    case 1: return 2;
+    case 2: return 3;
+    }
+    int diff = x > y ? x - y : y - x;
+    return verify(diff);
+}
+"#;
+        let stat = DefaultLexer::new(LangType::C)
+            .with_complexity(true)
+            .lex(&mut Cursor::new(code))
+            .unwrap();
+        // if ×2、&&、||、for、while、case ×2、? ；注释中的 if 与 `verify` 中的 if 不计入
+        assert_eq!(stat.complexity, 9);
+
+        let stat = DefaultLexer::new(LangType::C)
+            .lex(&mut Cursor::new(code))
+            .unwrap();
+        assert_eq!(stat.complexity, 0);
+    }
+
+    #[test]
+    fn count_rust_complexity() {
+        let code = r#"fn parse(input: &str) -> Result<u32, Error> {
+    let n = input.trim().parse::<u32>()?;
+    if n == 0 || n > 100 {
+        return Err(Error::Range);
+    }
+    for _ in 0..n {
+        while ready() && !done() {}
+    }
+    Ok(n)
+}
+"#;
+        let stat = DefaultLexer::new(LangType::Rust)
+            .with_complexity(true)
+            .lex(&mut Cursor::new(code))
+            .unwrap();
+        // ?、if、||、for、while、&&
+        assert_eq!(stat.complexity, 6);
+    }
+
+    #[test]
+    fn count_java_classes() {
+        let code = r#"
+public class Greeter {
+    interface Listener {
+        void onGreet(String name);
+    }
+
+    static class Inner {}
+
+    public void greet() {
+        /* class Hidden {} */
+        System.out.println("hi");
+    }
+}
+"#;
+        let stat = DefaultLexer::new(LangType::Java)
+            .lex(&mut Cursor::new(code))
+            .unwrap();
+
+        assert_eq!(stat.classes, 3);
+    }
+
+    #[test]
+    fn count_named_regions() {
+        let code = r#"use std::io;
+
+// region: Parsing
+fn parse() {
+    // #region Helpers
+    let x = 1;
+    // #endregion
+}
+// endregion
+
+// region Output
+fn print() {}
+
+// endregion
+fn main() {}
+"#;
+        let stat = DefaultLexer::new(LangType::Rust)
+            .lex(&mut Cursor::new(code))
+            .unwrap();
+
+        assert_eq!(stat.regions, vec![
+            ("Parsing".to_string(), 5),
+            ("Helpers".to_string(), 1),
+            ("Output".to_string(), 2),
+        ]);
+
+        let code = "#region Imports\nimport os\n#endregion\n#pragma region\nx = 1\n";
+        let stat = PythonLexer::new().lex(&mut Cursor::new(code)).unwrap();
+        // 未结束的区域统计到文件末尾
+        assert_eq!(stat.regions, vec![("Imports".to_string(), 1), (String::new(), 1)]);
+
+        assert_eq!(region_marker("#pragma region Setup"), Some(RegionMarker::Start("Setup".to_string())));
+        assert_eq!(region_marker("#pragma endregion"), Some(RegionMarker::End));
+        assert_eq!(region_marker("// regional settings"), None);
     }
 
     #[test]
@@ -302,6 +1024,218 @@ if __name__ == "__main__":
         // 纯代码行
         assert_eq!(stat.code, 16);
         // 函数数 (hello_world, __init__, greet, async_function)
-        assert_eq!(stat.functions, 14);
+        assert_eq!(stat.functions, 4);
+        assert_eq!(stat.function_lines, 10);
+    }
+
+    #[test]
+    fn python_docstring_mismatched_quotes() {
+        let code = r#"def f():
+    """Summary.
+
+    Example: ''' does not close this docstring
+    """
+    return 1
+    x = 2
+
+def g():
+    '''Another one with """ inside
+    '''
+    return 2
+"#;
+        let mut cursor = Cursor::new(code);
+        let stat = PythonLexer::new()
+            .lex(&mut cursor)
+            .unwrap();
+
+        assert_eq!(stat.lines, 12);
+        assert_eq!(stat.blanks, 2);
+        // 两个文档字符串的全部行（起始行、Example 行与结束定界符行）
+        assert_eq!(stat.comments, 5);
+        assert_eq!(stat.code, 5);
+    }
+
+    #[test]
+    fn blank_lines_inside_block_comments() {
+        let code = "/* start\n\n   end */\nint x;\n\n";
+
+        let stat = DefaultLexer::new(LangType::C).lex(&mut Cursor::new(code)).unwrap();
+        assert_eq!((stat.blanks, stat.comments, stat.code), (2, 2, 1));
+
+        let stat = DefaultLexer::new(LangType::C)
+            .with_blanks_in_comments_are_blank(false)
+            .lex(&mut Cursor::new(code))
+            .unwrap();
+        // 注释内的空行改计为注释，注释外的空行不受影响
+        assert_eq!((stat.blanks, stat.comments, stat.code), (1, 3, 1));
+    }
+
+    #[test]
+    fn blank_lines_inside_python_docstrings() {
+        let code = "def f():\n    \"\"\"Doc.\n\n    More.\n    \"\"\"\n    return 1\n";
+
+        let stat = PythonLexer::new().lex(&mut Cursor::new(code)).unwrap();
+        assert_eq!((stat.blanks, stat.comments), (1, 3));
+
+        let stat = PythonLexer::new()
+            .with_blanks_in_comments_are_blank(false)
+            .lex(&mut Cursor::new(code))
+            .unwrap();
+        assert_eq!((stat.blanks, stat.comments), (0, 4));
+    }
+
+    #[test]
+    fn blank_lines_inside_multiline_strings() {
+        let js = "const t = `\nline one\n\n// not a comment\n`;\n\n// c\n";
+        let stat = DefaultLexer::new(LangType::Javascript).lex(&mut Cursor::new(js)).unwrap();
+        // 模板字符串内的空行与 `//` 都是代码，字符串闭合后恢复正常分类
+        assert_eq!((stat.blanks, stat.comments, stat.code), (1, 1, 5));
+
+        let py = "query = \"\"\"\nSELECT 1\n\n# not a comment\n\"\"\"\n\n# c\n";
+        let stat = PythonLexer::new().lex(&mut Cursor::new(py)).unwrap();
+        assert_eq!((stat.blanks, stat.comments, stat.code), (1, 1, 5));
+
+        let rust = "let s = \"a\n\nb\";\n";
+        let stat = DefaultLexer::new(LangType::Rust).lex(&mut Cursor::new(rust)).unwrap();
+        assert_eq!((stat.blanks, stat.code), (0, 3));
+
+        let stat = DefaultLexer::new(LangType::Rust)
+            .with_blanks_in_strings_are_code(false)
+            .lex(&mut Cursor::new(rust))
+            .unwrap();
+        assert_eq!((stat.blanks, stat.code), (1, 2));
+    }
+
+    fn lex_both_endings(lexer: &dyn Lexer, lf: &str) -> (FileStat, FileStat) {
+        let crlf = lf.replace('\n', "\r\n");
+        let lf_stat = lexer.lex(&mut Cursor::new(lf)).unwrap();
+        let crlf_stat = lexer.lex(&mut Cursor::new(crlf)).unwrap();
+        (lf_stat, crlf_stat)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn crlf_rust_matches_lf() {
+        let code = "\
+// header
+/* block
+
+   end */
+fn main() {
+    let s = \"a\"; // trailing
+}
+
+";
+        let (lf, crlf) = lex_both_endings(&DefaultLexer::new(LangType::Rust), code);
+        assert_eq!(crlf, lf);
+        assert_eq!((lf.lines, lf.code, lf.comments, lf.blanks), (8, 3, 3, 2));
+    }
+
+    #[test]
+    fn crlf_python_matches_lf() {
+        let code = "\
+\"\"\"Module doc.
+
+More text.
+\"\"\"
+
+def f(x):
+    # comment
+\tif x:
+\t\treturn x
+    return 0
+";
+        let (lf, crlf) = lex_both_endings(&PythonLexer::new(), code);
+        assert_eq!(crlf, lf);
+        assert_eq!(lf.lines, 10);
+    }
+
+    #[test]
+    fn python_special_comments() {
+        let code = "\
+# plain comment
+#: Sphinx doc for the constant below
+MAX = 10
+limit = 5  #: trailing Sphinx doc
+retries = 3  # plain trailing comment
+";
+        let stat = PythonLexer::new().lex(&mut Cursor::new(code)).unwrap();
+        assert_eq!(stat.comments, 2);
+        assert_eq!(stat.special_comments, 2);
+    }
+
+    fn todo_markers() -> Vec<String> {
+        ["TODO", "FIXME", "HACK", "XXX"].iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn rust_todo_markers() {
+        let code = "\
+// TODO: split this up
+fn main() {
+    let todo = \"TODO in a string\";
+    run(); // FIXME TODO handle errors, TODO retry
+    /* HACK:
+       XXX and TODOS */
+}
+";
+        let stat = DefaultLexer::new(LangType::Rust)
+            .with_todo_markers(todo_markers())
+            .lex(&mut Cursor::new(code))
+            .unwrap();
+        assert_eq!(stat.todos, 5);
+
+        let stat = DefaultLexer::new(LangType::Rust).lex(&mut Cursor::new(code)).unwrap();
+        assert_eq!(stat.todos, 0);
+    }
+
+    #[test]
+    fn python_todo_markers() {
+        let code = "\
+# TODO: add typing
+def run():
+    \"\"\"FIXME: document the return value\"\"\"
+    return 1  # HACK
+print('XXX')
+";
+        let stat = PythonLexer::new()
+            .with_todo_markers(todo_markers())
+            .lex(&mut Cursor::new(code))
+            .unwrap();
+        assert_eq!(stat.todos, 3);
+    }
+
+    #[test]
+    fn skip_license_header_comments() {
+        let code = "\n\
+/*
+ * Copyright (c) toukei authors
+
+ * SPDX-License-Identifier: MIT
+ */
+// not part of the header
+
+/// Adds one.
+fn add_one(x: i32) -> i32 {
+    // inside
+    x + 1
+}
+";
+        let stat = DefaultLexer::new(LangType::Rust).lex(&mut Cursor::new(code)).unwrap();
+        assert_eq!((stat.comments, stat.blanks, stat.header_lines), (7, 3, 0));
+
+        let stat = DefaultLexer::new(LangType::Rust)
+            .with_skip_header_comments(true)
+            .lex(&mut Cursor::new(code))
+            .unwrap();
+        // 开头的空行不算文件头，块注释内的空行算；紧跟的行注释也属于同一个注释块
+        assert_eq!((stat.comments, stat.blanks, stat.header_lines), (2, 2, 6));
+        assert_eq!(stat.lines, stat.code + stat.comments + stat.blanks + stat.header_lines);
+
+        let code = "# Copyright (c) toukei authors\n# MIT License\n\nimport os  # stdlib\n# trailing\n";
+        let stat = PythonLexer::new()
+            .with_skip_header_comments(true)
+            .lex(&mut Cursor::new(code))
+            .unwrap();
+        assert_eq!((stat.comments, stat.header_lines, stat.code), (1, 2, 1));
+    }
+}
@@ -1,23 +1,118 @@
+use crate::consts::DEFAULT_TAB_WIDTH;
 use crate::langs::lang_type::LangType;
 
 pub mod lex_status;
 pub mod lexer;
 pub mod classifier;
+pub mod embedded;
+pub mod parallel;
+pub mod stream;
+
+pub use classifier::{custom_classifier, register_classifier, unregister_classifier, Classifier, LineClassification, LineKind, LineSpan, SpanKind};
+pub use lexer::LineExplanation;
+pub use stream::LineClassifier;
+
+/// 影响统计结果的词法分析选项
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LexOptions {
+    /// 缩进敏感语言使用的制表符宽度
+    pub tab_width: usize,
+    /// 块注释/文档字符串内的空行计为空行，关闭后计为注释
+    pub blanks_in_comments_are_blank: bool,
+    /// 跨行字符串内的空行计为代码，关闭后计为空行
+    pub blanks_in_strings_are_code: bool,
+    /// 统计看起来像被注释掉的代码的注释行
+    pub detect_commented_code: bool,
+    /// 文件开头的注释块不计入注释，单独统计为 `header_lines`
+    pub skip_header_comments: bool,
+    /// 统计分支关键字，近似圈复杂度
+    pub count_complexity: bool,
+    /// 注释中要统计的待办标记，为空时不统计
+    pub todo_markers: Vec<String>,
+}
+
+impl Default for LexOptions {
+    fn default() -> Self {
+        Self {
+            tab_width: DEFAULT_TAB_WIDTH,
+            blanks_in_comments_are_blank: true,
+            blanks_in_strings_are_code: true,
+            detect_commented_code: false,
+            skip_header_comments: false,
+            count_complexity: false,
+            todo_markers: Vec::new(),
+        }
+    }
+}
 
 pub struct LexerFactory;
 
+impl Default for LexerFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl LexerFactory {
     pub fn new() -> Self {
         LexerFactory {}
     }
 
     pub fn get_lexer(lang_type: LangType) -> Option<Box<dyn lexer::Lexer>> {
+        Self::get_lexer_with_tab_width(lang_type, DEFAULT_TAB_WIDTH)
+    }
+
+    /// 获取词法分析器，缩进敏感的语言使用指定的制表符宽度
+    pub fn get_lexer_with_tab_width(lang_type: LangType, tab_width: usize) -> Option<Box<dyn lexer::Lexer>> {
+        Self::get_lexer_with_options(lang_type, LexOptions { tab_width, ..LexOptions::default() })
+    }
+
+    /// 该语言的词法分析器是否使用 `LexOptions::tab_width`（缩进敏感的语言）
+    pub fn uses_tab_width(lang_type: LangType) -> bool {
+        lang_type == LangType::Python && classifier::custom_classifier(lang_type).is_none()
+    }
+
+    pub fn get_lexer_with_options(lang_type: LangType, options: LexOptions) -> Option<Box<dyn lexer::Lexer>> {
+        if lang_type != LangType::Unknown
+            && let Some(custom) = classifier::custom_classifier(lang_type) {
+            return Some(Box::new(lexer::DefaultLexer::from_options(lang_type, custom, &options)));
+        }
         match lang_type {
-            LangType::Python => Some(Box::new(lexer::PythonLexer::new())),
+            LangType::Python => Some(Box::new(lexer::PythonLexer::from_options(&options))),
             LangType::Markdown => Some(Box::new(lexer::MdLexer::new())),
+            LangType::Batch => Some(Box::new(lexer::DefaultLexer::from_options(lang_type, classifier::BatchClassifier::new(), &options))),
+            LangType::Fortran => Some(Box::new(lexer::DefaultLexer::from_options(lang_type, classifier::FortranClassifier::new(), &options))),
             LangType::Unknown => None,
-            _ => Some(Box::new(lexer::DefaultLexer::new(lang_type))),
+            _ => Some(Box::new(lexer::DefaultLexer::from_options(lang_type, default_classifier(&options), &options))),
         }
     }
+
+    /// 获取语言对应的行分类器
+    pub fn get_classifier(lang_type: LangType) -> Box<dyn classifier::Classifier> {
+        Self::get_classifier_with_options(lang_type, LexOptions::default())
+    }
+
+    pub fn get_classifier_with_options(lang_type: LangType, options: LexOptions) -> Box<dyn classifier::Classifier> {
+        if let Some(custom) = classifier::custom_classifier(lang_type) {
+            return Box::new(custom);
+        }
+        let blanks = options.blanks_in_comments_are_blank;
+        let string_blanks = options.blanks_in_strings_are_code;
+        match lang_type {
+            LangType::Python => Box::new(classifier::PythonClassifier::new()
+                .with_blanks_in_comments_are_blank(blanks)
+                .with_blanks_in_strings_are_code(string_blanks)),
+            LangType::Batch => Box::new(classifier::BatchClassifier::new()),
+            LangType::Fortran => Box::new(classifier::FortranClassifier::new()),
+            _ => Box::new(default_classifier(&options)),
+        }
+    }
+}
+
+/// 按 `options` 设置空行归属的默认分类器
+pub(crate) fn default_classifier(options: &LexOptions) -> classifier::DefaultClassifier {
+    classifier::DefaultClassifier::new()
+        .with_blanks_in_comments_are_blank(options.blanks_in_comments_are_blank)
+        .with_blanks_in_strings_are_code(options.blanks_in_strings_are_code)
 }
 
@@ -0,0 +1,211 @@
+use rayon::prelude::*;
+
+use crate::langs::lang_type::LangType;
+use crate::langs::registry::get_lang_def;
+use crate::stats::FileStat;
+
+use super::{default_classifier, LexOptions};
+use super::lex_status::LexCtx;
+use super::classifier::{custom_classifier, BatchClassifier, FortranClassifier};
+use super::lexer::{fold_line, DefaultLexer, EolState, HeaderState, LineLexer, LineRecord, PythonLexer, RegionState};
+
+/// 一段文本在给定起始状态下的分析结果
+struct Chunk {
+    start: LexCtx,
+    end: LexCtx,
+    records: Vec<LineRecord>,
+}
+
+/// 把一个大文件按行边界切成约 `chunk_bytes` 字节的若干段并行分析
+///
+/// 每段先假设从默认状态开始；随后顺序检查，起始状态与上一段的结束状态不一致
+/// （例如块注释跨越了分段边界）的段会用正确的状态重新分析。
+/// 最后按顺序折叠所有行，函数范围等跨行统计与串行分析完全一致。
+///
+/// 不支持逐行分析的语言（如 Markdown）返回 None，调用方应回退到串行分析。
+pub fn lex_parallel(lang_type: LangType, options: LexOptions, source: &str, chunk_bytes: usize) -> Option<FileStat> {
+    get_lang_def(&lang_type)?;
+    if let Some(custom) = custom_classifier(lang_type) {
+        let lexer = DefaultLexer::from_options(lang_type, custom, &options);
+        return Some(lex_chunks(&lexer, source, chunk_bytes));
+    }
+    match lang_type {
+        LangType::Python => Some(lex_chunks(&PythonLexer::from_options(&options), source, chunk_bytes)),
+        LangType::Batch => {
+            let lexer = DefaultLexer::from_options(lang_type, BatchClassifier::new(), &options);
+            Some(lex_chunks(&lexer, source, chunk_bytes))
+        }
+        LangType::Fortran => {
+            let lexer = DefaultLexer::from_options(lang_type, FortranClassifier::new(), &options);
+            Some(lex_chunks(&lexer, source, chunk_bytes))
+        }
+        LangType::Markdown | LangType::Unknown => None,
+        _ => {
+            let lexer = DefaultLexer::from_options(lang_type, default_classifier(&options), &options);
+            Some(lex_chunks(&lexer, source, chunk_bytes))
+        }
+    }
+}
+
+fn lex_chunks<L: LineLexer>(lexer: &L, source: &str, chunk_bytes: usize) -> FileStat {
+    let pieces = split_lines(source, chunk_bytes);
+
+    let mut chunks: Vec<Chunk> = pieces
+        .par_iter()
+        .map(|piece| analyze_chunk(lexer, piece, LexCtx::default()))
+        .collect();
+
+    // 串行修正：起始状态猜错的段用真实状态重新分析
+    let mut ctx = LexCtx::default();
+    for (chunk, piece) in chunks.iter_mut().zip(&pieces) {
+        if chunk.start != ctx {
+            *chunk = analyze_chunk(lexer, piece, ctx);
+        }
+        ctx = chunk.end;
+    }
+
+    let mut stat = FileStat::default();
+    let mut state = L::Fold::default();
+    let mut header = HeaderState::default();
+    let mut regions = RegionState::default();
+    for record in chunks.iter().flat_map(|chunk| &chunk.records) {
+        fold_line(lexer, &mut stat, &mut state, &mut header, &mut regions, record);
+    }
+    let mut eol = EolState::default();
+    for line in source.split_inclusive('\n') {
+        eol.record(line.as_bytes());
+    }
+    stat.mixed_eol = eol.is_mixed();
+    stat
+}
+
+fn analyze_chunk<L: LineLexer>(lexer: &L, piece: &str, start: LexCtx) -> Chunk {
+    let mut ctx = start;
+    let records = piece
+        .lines()
+        .map(|line| lexer.analyze(line, &mut ctx))
+        .collect();
+    Chunk { start, end: ctx, records }
+}
+
+/// 在 `chunk_bytes` 之后的第一个换行处切分，保证每段都由完整的行组成
+fn split_lines(source: &str, chunk_bytes: usize) -> Vec<&str> {
+    let chunk_bytes = chunk_bytes.max(1);
+    let mut pieces = Vec::new();
+    let mut rest = source;
+
+    while !rest.is_empty() {
+        if rest.len() <= chunk_bytes {
+            pieces.push(rest);
+            break;
+        }
+        let cut = rest.as_bytes()[chunk_bytes..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(rest.len(), |pos| chunk_bytes + pos + 1);
+        let (piece, tail) = rest.split_at(cut);
+        pieces.push(piece);
+        rest = tail;
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::syntax::LexerFactory;
+
+    fn serial(lang: LangType, source: &str) -> FileStat {
+        LexerFactory::get_lexer(lang)
+            .unwrap()
+            .lex(&mut Cursor::new(source))
+            .unwrap()
+    }
+
+    #[test]
+    fn split_lines_keeps_whole_lines() {
+        let pieces = split_lines("ab\ncd\nef", 1);
+        assert_eq!(pieces, ["ab\n", "cd\n", "ef"]);
+        assert_eq!(split_lines("", 4), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn parallel_matches_serial_across_chunk_boundaries() {
+        let unit = "\
+/* block comment
+   spanning several lines
+   so chunk boundaries fall inside it */
+fn add(a: i32, b: i32) -> i32 {
+    // inside
+    a + b /* trailing */
+}
+
+let x = 1; /* opens
+closes */ let y = 2;
+";
+        let source = unit.repeat(2_000);
+
+        let expected = serial(LangType::Rust, &source);
+        for chunk_bytes in [1, 37, 512, 64 * 1024] {
+            let stat = lex_parallel(LangType::Rust, LexOptions::default(), &source, chunk_bytes).unwrap();
+            assert_eq!(stat, expected, "chunk size {}", chunk_bytes);
+        }
+        assert_eq!(expected.lines, 10 * 2_000);
+    }
+
+    #[test]
+    fn parallel_matches_serial_for_python_docstrings() {
+        let unit = "\
+def f(x):
+    \"\"\"Docstring
+    that spans lines
+    \"\"\"
+    return x
+
+class A:
+    def g(self):
+        return 1
+";
+        let source = unit.repeat(1_000);
+
+        let expected = serial(LangType::Python, &source);
+        for chunk_bytes in [1, 29, 4096] {
+            let stat = lex_parallel(LangType::Python, LexOptions::default(), &source, chunk_bytes).unwrap();
+            assert_eq!(stat, expected, "chunk size {}", chunk_bytes);
+        }
+    }
+
+    #[test]
+    fn parallel_matches_serial_with_options() {
+        let options = LexOptions {
+            blanks_in_comments_are_blank: false,
+            blanks_in_strings_are_code: false,
+            detect_commented_code: true,
+            skip_header_comments: true,
+            count_complexity: true,
+            todo_markers: vec!["TODO".to_string()],
+            ..LexOptions::default()
+        };
+        let sources = [
+            (LangType::Rust, "// header\n/* a\n\n b */\nfn f() {\n    // let x = 1;\n    if a && b {} // TODO\n}\nlet s = \"x\n\ny\";\n"),
+            (LangType::Batch, "REM header\n@echo off\nREM set X=1;\n:: TODO\nif exist a.txt echo yes\n"),
+            (LangType::Fortran, "! header\nprogram p\n  ! x = 1;\n  if (x > 0) print *, x ! TODO\nend program\n"),
+        ];
+        for (lang, unit) in sources {
+            let source = unit.repeat(200);
+            let expected = LexerFactory::get_lexer_with_options(lang, options.clone())
+                .unwrap()
+                .lex(&mut Cursor::new(&source))
+                .unwrap();
+            let stat = lex_parallel(lang, options.clone(), &source, 64).unwrap();
+            assert_eq!(stat, expected, "{:?}", lang);
+        }
+    }
+
+    #[test]
+    fn markdown_falls_back_to_serial() {
+        assert!(lex_parallel(LangType::Markdown, LexOptions::default(), "# title\n", 1).is_none());
+    }
+}
@@ -0,0 +1,133 @@
+use crate::langs::lang_def::LangDef;
+use crate::langs::lang_type::LangType;
+use crate::langs::registry::get_lang_def;
+
+use super::{LexOptions, LexerFactory};
+use super::classifier::{Classifier, LineClassification, LineKind};
+use super::lex_status::{LexCtx, LineCtx};
+
+/// 逐行分类器：按顺序喂入一个文件的各行，跨行的块注释/文档字符串状态由内部维护。
+///
+/// 适合编辑器高亮等只需要行级结果、不需要整份统计的场景。
+///
+/// ```
+/// use toukei::langs::lang_type::LangType;
+/// use toukei::syntax::{LineClassifier, LineKind};
+///
+/// let mut classifier = LineClassifier::new(LangType::C).unwrap();
+///
+/// let line = "int x = 1; /* start";
+/// let cls = classifier.classify(line);
+/// assert_eq!(cls.kind, LineKind::Mixed);
+/// assert_eq!(cls.code_spans().map(|r| &line[r]).collect::<Vec<_>>(), ["int x = 1;"]);
+/// assert_eq!(cls.comment_spans().map(|r| &line[r]).collect::<Vec<_>>(), ["/* start"]);
+///
+/// // 块注释跨行延续
+/// assert_eq!(classifier.classify("   still comment").kind, LineKind::Comment);
+/// assert_eq!(classifier.classify("*/").kind, LineKind::Comment);
+/// assert_eq!(classifier.classify("").kind, LineKind::Blank);
+/// ```
+pub struct LineClassifier {
+    def: &'static LangDef,
+    classifier: Box<dyn Classifier>,
+    ctx: LexCtx,
+}
+
+impl LineClassifier {
+    /// 语言未注册时返回 None
+    pub fn new(lang: LangType) -> Option<Self> {
+        Self::with_options(lang, LexOptions::default())
+    }
+
+    pub fn with_options(lang: LangType, options: LexOptions) -> Option<Self> {
+        let def = get_lang_def(&lang)?;
+        Some(Self {
+            def,
+            classifier: LexerFactory::get_classifier_with_options(lang, options),
+            ctx: LexCtx::default(),
+        })
+    }
+
+    /// 分类下一行（不含换行符），返回的区间相对于传入的 `line`
+    pub fn classify(&mut self, line: &str) -> LineClassification {
+        let lctx = LineCtx::new(line, &mut self.ctx, self.def);
+        self.classifier.classify(lctx)
+    }
+
+    /// 清空跨行状态，开始处理新的文件
+    pub fn reset(&mut self) {
+        self.ctx = LexCtx::default();
+    }
+}
+
+/// 去掉源码中的注释（整行注释、块注释、行尾注释）和空行，只保留代码
+///
+/// 保留每行原有的缩进；未注册的语言原样返回。
+///
+/// ```
+/// use toukei::langs::lang_type::LangType;
+///
+/// let source = "// header\nfn main() { /* inline */ run(); } // tail\n\n";
+/// assert_eq!(toukei::strip_comments(LangType::Rust, source), "fn main() { run(); }\n");
+/// ```
+pub fn strip_comments(lang: LangType, source: &str) -> String {
+    let Some(mut classifier) = LineClassifier::new(lang) else {
+        return source.to_string();
+    };
+
+    let mut out = String::with_capacity(source.len());
+    for line in source.lines() {
+        let cls = classifier.classify(line);
+        if !matches!(cls.kind, LineKind::Code | LineKind::Mixed) {
+            continue;
+        }
+        let indent = &line[..line.len() - line.trim_start().len()];
+        out.push_str(indent);
+        out.push_str(&cls.code_text(line));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_rust_comments() {
+        let source = r#"//! crate docs
+/// Adds numbers.
+fn add(a: i32, b: i32) -> i32 {
+    /* multi
+       line */
+    let url = "http://example.com"; // not a comment inside the string
+
+    a + b /* trailing */
+}
+"#;
+        let expected = r#"fn add(a: i32, b: i32) -> i32 {
+    let url = "http://example.com";
+    a + b
+}
+"#;
+        assert_eq!(strip_comments(LangType::Rust, source), expected);
+    }
+
+    #[test]
+    fn strip_python_comments() {
+        let source = r#"# module comment
+def f(x):
+    """Docstring
+    more docs
+    """
+    return x  # inline
+
+class A:
+    """Single-line docstring."""
+    pass
+"#;
+        // 文档字符串与计数时一样整行按注释处理，起始行上的文本也一并去掉
+        let expected = "def f(x):\n    return x\nclass A:\n    pass\n";
+        assert_eq!(strip_comments(LangType::Python, source), expected);
+    }
+}
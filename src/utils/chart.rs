@@ -1,14 +1,64 @@
 use std::path::Path;
+use std::str::FromStr;
 
 use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
 
 use crate::report::Report;
+use crate::stats::LangStat;
+
+/// 图表中用于比较各语言的统计项
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ChartMetric {
+    #[default]
+    Lines,
+    Code,
+    Comments,
+    Blanks,
+    Files,
+}
+
+impl ChartMetric {
+    fn value(&self, stat: &LangStat) -> usize {
+        match self {
+            ChartMetric::Lines => stat.lines,
+            ChartMetric::Code => stat.code,
+            ChartMetric::Comments => stat.comments,
+            ChartMetric::Blanks => stat.blanks,
+            ChartMetric::Files => stat.files,
+        }
+    }
+}
+
+/// 命令行 `--chart-type` 选择的图表类型
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChartType {
+    #[default]
+    Pie,
+    Bar,
+}
+
+impl FromStr for ChartType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pie" => Ok(ChartType::Pie),
+            "bar" => Ok(ChartType::Bar),
+            _ => Err(format!("Invalid chart type: {}", s)),
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct ChartConfig {
     pub width: u32,
     pub height: u32,
     pub top_n: u32,
+    pub metric: ChartMetric,
+    pub title: String,
+    /// 饼图标签中百分比保留的小数位数
+    pub percent_precision: usize,
 }
 
 impl Default for ChartConfig {
@@ -17,6 +67,9 @@ impl Default for ChartConfig {
             width: 1024,
             height: 798,
             top_n: 10,
+            metric: ChartMetric::Lines,
+            title: "Project Code Distribution (Lines of Code)".to_string(),
+            percent_precision: 1,
         }
     }
 }
@@ -36,15 +89,47 @@ impl<'a> ChartDrawer<'a> {
         }
     }
 
-    fn get_sorted(&self) -> Vec<(String, usize)> {
+    pub fn with_top_n(mut self, top_n: u32) -> Self {
+        self.config.top_n = top_n;
+        self
+    }
 
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.config.width = width;
+        self.config.height = height;
+        self
+    }
+
+    pub fn with_metric(mut self, metric: ChartMetric) -> Self {
+        self.config.metric = metric;
+        self
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.config.title = title.into();
+        self
+    }
+
+    pub fn with_percent_precision(mut self, precision: usize) -> Self {
+        self.config.percent_precision = precision;
+        self
+    }
+
+    pub fn config(&self) -> &ChartConfig {
+        &self.config
+    }
+
+    /// 按 `metric` 降序取前 `top_n` 项，其余合并为 "Other"
+    fn get_sorted(&self) -> Vec<(String, usize)> {
+        let metric = self.config.metric;
         let sorted = self.report.sort_stats(|&a, &b| {
-            a.1.lines.cmp(&b.1.lines)
+            metric.value(b.1).cmp(&metric.value(a.1))
+                .then_with(|| a.0.to_string().cmp(&b.0.to_string()))
         });
 
         let mut total = sorted.iter()
             .map(|(a, b)| {
-            (a.to_string(), b.lines)
+            (a.to_string(), metric.value(b))
             })
             .collect::<Vec<_>>();
 
@@ -65,6 +150,28 @@ impl<'a> ChartDrawer<'a> {
         total
     }
 
+    /// 饼图的扇区：`(标签, 占比)`，占比与扇区使用同一统计项，分母包含 "Other"，为 0 的项不绘制
+    fn pie_slices(&self) -> Vec<(String, f64)> {
+        let stat = self.get_sorted();
+        let total: f64 = stat.iter().map(|(_, value)| *value as f64).sum();
+        stat.into_iter()
+            .filter(|(_, value)| *value > 0)
+            .map(|(name, value)| {
+                let ratio = value as f64 / total;
+                let label = format!("{} {:.*}%", name, self.config.percent_precision, ratio * 100.0);
+                (label, ratio)
+            })
+            .collect()
+    }
+
+    /// 按类型绘制图表
+    pub fn draw<P: AsRef<Path>>(&self, chart_type: ChartType, path: P) -> Result<(), Box<dyn std::error::Error>> {
+        match chart_type {
+            ChartType::Pie => self.draw_pie(path),
+            ChartType::Bar => self.draw_bar(path),
+        }
+    }
+
     pub fn draw_pie<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn std::error::Error>> { 
         // 1. 创建位图后端和绘图区域
         let root = BitMapBackend::new(&path, (self.config.width, self.config.height))
@@ -74,15 +181,14 @@ impl<'a> ChartDrawer<'a> {
         // 2. 绘制标题
         let title_style = TextStyle::from(("sans-serif", 30)).color(&BLACK);
         root.draw_text(
-            "Project Code Distribution (Lines of Code)",
+            &self.config.title,
             &title_style,
             (20, 20),
         )?;
 
-        // 3. 获取排序后的统计数据
-        let stat = self.get_sorted();
-        let total_lines: f64 = stat.iter().map(|(_, lines)| *lines as f64).sum();
-        if total_lines == 0.0 {
+        // 3. 获取各扇区的标签与占比
+        let slices = self.pie_slices();
+        if slices.is_empty() {
             return Err("Total lines of code is zero, cannot draw pie chart".into());
         }
 
@@ -104,6 +210,166 @@ impl<'a> ChartDrawer<'a> {
             &RGBColor(231, 233, 237),
         ];
 
-        unimplemented!()
+        // 6. 从正上方开始顺时针绘制每个扇区，并在扇区外侧标注语言和占比
+        let point_at = |angle: f64, radius: f64| {
+            (
+                center.0 + (radius * angle.cos()).round() as i32,
+                center.1 + (radius * angle.sin()).round() as i32,
+            )
+        };
+        let label_style = TextStyle::from(("sans-serif", 16)).color(&BLACK);
+        let mut start = -std::f64::consts::FRAC_PI_2;
+
+        for (i, (label, ratio)) in slices.iter().enumerate() {
+            let sweep = ratio * std::f64::consts::TAU;
+            let color = color_sequence[i % color_sequence.len()];
+
+            // 每度至少一个点，使弧线足够平滑
+            let steps = (sweep.to_degrees().ceil() as usize).max(2);
+            let mut points = vec![center];
+            points.extend((0..=steps).map(|step| {
+                point_at(start + sweep * step as f64 / steps as f64, pie_radius)
+            }));
+            root.draw(&Polygon::new(points, color.filled()))?;
+
+            let (x, y) = point_at(start + sweep / 2.0, pie_radius + 20.0);
+            let anchor = if x < center.0 { HPos::Right } else { HPos::Left };
+            root.draw_text(label, &label_style.pos(Pos::new(anchor, VPos::Center)), (x, y))?;
+
+            start += sweep;
+        }
+
+        root.present()?;
+        Ok(())
+    }
+
+    /// 横向条形图：左侧为语言名，右侧为数值，条长按最大一项缩放
+    pub fn draw_bar<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn std::error::Error>> {
+        let root = BitMapBackend::new(&path, (self.config.width, self.config.height))
+            .into_drawing_area();
+        root.fill(&WHITE)?;
+
+        let title_style = TextStyle::from(("sans-serif", 30)).color(&BLACK);
+        root.draw_text(&self.config.title, &title_style, (20, 20))?;
+
+        let stat = self.get_sorted();
+        let total_lines: usize = stat.iter().map(|(_, lines)| *lines).sum();
+        if total_lines == 0 {
+            return Err("Total lines of code is zero, cannot draw bar chart".into());
+        }
+        let max = stat.iter().map(|(_, lines)| *lines).max().unwrap_or(0);
+
+        // 左侧留给语言名，右侧留给数值，标题下方开始逐行绘制
+        let width = self.config.width as i32;
+        let height = self.config.height as i32;
+        let (label_width, value_width, top, margin) = (140, 90, 70, 20);
+        let bar_area = (width - label_width - value_width - 2 * margin).max(1);
+        let row_height = ((height - top - margin) / stat.len() as i32).max(1);
+        let bar_height = (row_height * 7 / 10).max(1);
+
+        let color = RGBColor(54, 162, 235);
+        let label_style = TextStyle::from(("sans-serif", 16))
+            .color(&BLACK)
+            .pos(Pos::new(HPos::Right, VPos::Center));
+        let value_style = TextStyle::from(("sans-serif", 16))
+            .color(&BLACK)
+            .pos(Pos::new(HPos::Left, VPos::Center));
+
+        for (i, (name, lines)) in stat.iter().enumerate() {
+            let y = top + i as i32 * row_height;
+            let middle = y + bar_height / 2;
+            let x0 = margin + label_width;
+            let x1 = x0 + (*lines as f64 / max as f64 * bar_area as f64).round() as i32;
+
+            root.draw_text(name, &label_style, (x0 - 8, middle))?;
+            root.draw(&Rectangle::new([(x0, y), (x1.max(x0 + 1), y + bar_height)], color.filled()))?;
+            root.draw_text(&lines.to_string(), &value_style, (x1 + 8, middle))?;
+        }
+
+        root.present()?;
+        Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::langs::lang_type::LangType;
+    use crate::stats::FileStat;
+
+    fn report() -> Report {
+        let mut report = Report::new();
+        for (lang, path, code) in [
+            (LangType::Rust, "a.rs", 30),
+            (LangType::Python, "b.py", 20),
+            (LangType::Go, "c.go", 10),
+        ] {
+            report.add(FileStat { lang, path: path.to_string(), lines: code, code, ..FileStat::default() });
+        }
+        report
+    }
+
+    #[test]
+    fn test_builder() {
+        let report = report();
+        let drawer = ChartDrawer::new(&report, None)
+            .with_top_n(1)
+            .with_size(640, 480)
+            .with_metric(ChartMetric::Code)
+            .with_title("Code");
+
+        let config = drawer.config();
+        assert_eq!((config.width, config.height, config.top_n), (640, 480, 1));
+        assert_eq!(config.metric, ChartMetric::Code);
+        assert_eq!(config.title, "Code");
+
+        assert_eq!(drawer.get_sorted(), vec![("Rust".to_string(), 30), ("Other".to_string(), 30)]);
+    }
+
+    #[test]
+    fn test_pie_labels_precision() {
+        let report = report();
+        let labels = |precision| -> Vec<String> {
+            ChartDrawer::new(&report, None)
+                .with_percent_precision(precision)
+                .pie_slices()
+                .into_iter()
+                .map(|(label, _)| label)
+                .collect()
+        };
+
+        assert_eq!(labels(0), ["Rust 50%", "Python 33%", "Go 17%"]);
+        assert_eq!(labels(2), ["Rust 50.00%", "Python 33.33%", "Go 16.67%"]);
+
+        // "Other" 也计入分母，各项占比之和为 100%
+        let drawer = ChartDrawer::new(&report, None).with_top_n(1);
+        let slices = drawer.pie_slices();
+        assert_eq!(slices.iter().map(|(label, _)| label.as_str()).collect::<Vec<_>>(), ["Rust 50.0%", "Other 50.0%"]);
+        assert!((slices.iter().map(|(_, ratio)| ratio).sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_draw_pie() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let path = dir.path().join("pie.png");
+
+        let report = report();
+        ChartDrawer::new(&report, None).with_size(400, 300).draw_pie(&path).unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() > 0);
+
+        // 空报告无法绘制
+        assert!(ChartDrawer::new(&Report::new(), None).draw_pie(dir.path().join("empty.png")).is_err());
+    }
+
+    #[test]
+    fn test_draw_bar() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let path = dir.path().join("bar.png");
+
+        let report = report();
+        ChartDrawer::new(&report, None).with_top_n(2).with_size(400, 300).draw_bar(&path).unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() > 0);
+
+        assert!(ChartDrawer::new(&Report::new(), None).draw_bar(dir.path().join("empty.png")).is_err());
+    }
+}
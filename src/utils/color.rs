@@ -0,0 +1,104 @@
+use std::io::IsTerminal;
+use std::str::FromStr;
+
+/// 终端输出是否着色
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorChoice {
+    /// 标准输出是终端且未设置 `NO_COLOR` 时着色
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// 根据当前环境判断是否着色
+    pub fn enabled(&self) -> bool {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        self.resolve(no_color, std::io::stdout().is_terminal())
+    }
+
+    /// `no_color` 表示设置了 `NO_COLOR`，`is_tty` 表示标准输出是终端
+    pub fn resolve(&self, no_color: bool, is_tty: bool) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => !no_color && is_tty,
+        }
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(format!("Invalid color choice: {}", s)),
+        }
+    }
+}
+
+/// 最小的 ANSI 着色工具，关闭时原样返回文本
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Painter {
+    enabled: bool,
+}
+
+/// 语言名使用的前景色，按名字哈希选取
+const PALETTE: &[u8] = &[31, 32, 33, 34, 35, 36, 91, 92, 93, 94, 95, 96];
+
+impl Painter {
+    pub fn new(enabled: bool) -> Self {
+        Painter { enabled }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn bold(&self, text: &str) -> String {
+        self.paint(text, "1")
+    }
+
+    pub fn dim(&self, text: &str) -> String {
+        self.paint(text, "2")
+    }
+
+    /// 同一个 `key` 总是得到同一种颜色
+    pub fn keyed(&self, text: &str, key: &str) -> String {
+        let hash = key.bytes().fold(0usize, |h, b| h.wrapping_mul(31).wrapping_add(b as usize));
+        self.paint(text, &PALETTE[hash % PALETTE.len()].to_string())
+    }
+
+    fn paint(&self, text: &str, code: &str) -> String {
+        if self.enabled {
+            format!("\x1b[{}m{}\x1b[0m", code, text)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        assert!(ColorChoice::Always.resolve(true, false));
+        assert!(!ColorChoice::Never.resolve(false, true));
+        assert!(ColorChoice::Auto.resolve(false, true));
+        assert!(!ColorChoice::Auto.resolve(true, true));
+        assert!(!ColorChoice::Auto.resolve(false, false));
+    }
+
+    #[test]
+    fn test_painter() {
+        assert_eq!(Painter::new(false).bold("Total"), "Total");
+        assert_eq!(Painter::new(true).bold("Total"), "\x1b[1mTotal\x1b[0m");
+        assert_eq!(Painter::new(true).keyed("Rust", "Rust"), Painter::new(true).keyed("Rust", "Rust"));
+    }
+}
@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::utils::path_match::PathMatcher;
+
+const EDITORCONFIG: &str = ".editorconfig";
+
+/// 从文件所在目录向上查找 `.editorconfig`，解析出该文件生效的制表符宽度
+///
+/// 每次调用都会重新读取配置文件，统计大量文件时使用 `EditorConfigCache`。
+pub fn tab_width_for<P: AsRef<Path>>(path: P) -> Option<usize> {
+    EditorConfigCache::new().tab_width_for(path)
+}
+
+/// 按目录缓存解析好的 `.editorconfig`，同一目录下的文件只做一次 `canonicalize` 和文件读取
+#[derive(Debug, Default)]
+pub struct EditorConfigCache {
+    /// 文件所在目录 -> 对它生效的配置，按从近到远排列
+    chains: RwLock<HashMap<PathBuf, Arc<Chain>>>,
+    /// 绝对路径目录 -> 该目录下的 `.editorconfig`，不存在时为 None
+    configs: Mutex<HashMap<PathBuf, Option<Arc<EditorConfig>>>>,
+}
+
+/// 对某个目录生效的配置，`dir` 是该目录的绝对路径
+#[derive(Debug)]
+struct Chain {
+    dir: PathBuf,
+    configs: Vec<Arc<EditorConfig>>,
+}
+
+/// 一个 `.editorconfig` 文件的解析结果
+#[derive(Debug)]
+struct EditorConfig {
+    root: bool,
+    sections: Vec<Section>,
+}
+
+/// 一个 section 中与缩进有关的设置；值无法解析为数字时记为 `Some(None)`，会覆盖较远处的设置
+#[derive(Debug)]
+struct Section {
+    /// section 的 glob 无法解析时为 None，不匹配任何文件
+    matcher: Option<PathMatcher>,
+    tab_width: Option<Option<usize>>,
+    indent_size: Option<Option<usize>>,
+}
+
+impl EditorConfigCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 文件生效的制表符宽度
+    ///
+    /// 优先使用 `tab_width`，未设置时退回数值形式的 `indent_size`；
+    /// 越靠近文件的配置优先级越高，遇到 `root = true` 停止向上查找。
+    pub fn tab_width_for<P: AsRef<Path>>(&self, path: P) -> Option<usize> {
+        let path = path.as_ref();
+        let parent = path.parent().unwrap_or(Path::new(""));
+        let chain = self.chain_for(parent);
+        let file = match path.file_name() {
+            Some(name) => chain.dir.join(name),
+            None => chain.dir.clone(),
+        };
+        let file = file.display().to_string();
+
+        let mut tab_width = None;
+        let mut indent_size = None;
+
+        // 从最远的配置开始应用，近处的配置覆盖远处的
+        for config in chain.configs.iter().rev() {
+            let matching = config.sections
+                .iter()
+                .filter(|s| s.matcher.as_ref().is_some_and(|m| m.is_match(&file)));
+            for section in matching {
+                if let Some(value) = section.tab_width {
+                    tab_width = value;
+                }
+                if let Some(value) = section.indent_size {
+                    indent_size = value;
+                }
+            }
+        }
+
+        tab_width.or(indent_size).filter(|w| *w > 0)
+    }
+
+    fn chain_for(&self, dir: &Path) -> Arc<Chain> {
+        if let Some(chain) = self.chains.read().unwrap_or_else(|e| e.into_inner()).get(dir) {
+            return Arc::clone(chain);
+        }
+
+        let lookup = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+        let abs = lookup.canonicalize().unwrap_or_else(|_| lookup.to_path_buf());
+        let mut configs = Vec::new();
+        let mut current = Some(abs.as_path());
+        while let Some(d) = current {
+            if let Some(config) = self.config_in(d) {
+                let root = config.root;
+                configs.push(config);
+                if root {
+                    break;
+                }
+            }
+            current = d.parent();
+        }
+
+        let chain = Arc::new(Chain { dir: abs, configs });
+        self.chains.write().unwrap_or_else(|e| e.into_inner()).insert(dir.to_path_buf(), Arc::clone(&chain));
+        chain
+    }
+
+    fn config_in(&self, dir: &Path) -> Option<Arc<EditorConfig>> {
+        let mut configs = self.configs.lock().unwrap_or_else(|e| e.into_inner());
+        configs
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| fs::read_to_string(dir.join(EDITORCONFIG))
+                .ok()
+                .map(|content| Arc::new(parse(dir, &content))))
+            .clone()
+    }
+}
+
+fn parse(dir: &Path, content: &str) -> EditorConfig {
+    let mut sections: Vec<Section> = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(glob) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push(Section { matcher: section_matcher(dir, glob), tab_width: None, indent_size: None });
+            continue;
+        }
+        let (Some(section), Some((key, value))) = (sections.last_mut(), line.split_once('=')) else {
+            continue;
+        };
+        let value = value.trim().to_lowercase().parse::<usize>().ok();
+        match key.trim().to_lowercase().as_str() {
+            "tab_width" => section.tab_width = Some(value),
+            "indent_size" => section.indent_size = Some(value),
+            _ => {}
+        }
+    }
+
+    EditorConfig {
+        root: is_root(content),
+        sections,
+    }
+}
+
+fn is_root(content: &str) -> bool {
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            return false;
+        }
+        if let Some((key, value)) = line.split_once('=')
+            && key.trim().eq_ignore_ascii_case("root") {
+            return value.trim().eq_ignore_ascii_case("true");
+        }
+    }
+    false
+}
+
+/// section 的 glob 对应的匹配器；含 '/' 的模式相对于 `.editorconfig` 所在目录
+fn section_matcher(dir: &Path, section: &str) -> Option<PathMatcher> {
+    let patterns: Vec<String> = expand_braces(section)
+        .into_iter()
+        .map(|p| {
+            if p.contains('/') {
+                format!("{}/{}", dir.display(), p.trim_start_matches('/'))
+            } else {
+                p
+            }
+        })
+        .collect();
+
+    PathMatcher::new(&patterns).ok()
+}
+
+/// 展开单层的 `{a,b}` 形式
+fn expand_braces(pattern: &str) -> Vec<String> {
+    if let (Some(start), Some(end)) = (pattern.find('{'), pattern.find('}'))
+        && start < end {
+        let (prefix, rest) = pattern.split_at(start);
+        let body = &rest[1..end - start];
+        let suffix = &rest[end - start + 1..];
+        return body
+            .split(',')
+            .map(|alt| format!("{}{}{}", prefix, alt.trim(), suffix))
+            .collect();
+    }
+    vec![pattern.to_string()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tab_width_from_editorconfig() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("pkg")).unwrap();
+        fs::write(root.join(EDITORCONFIG), "root = true\n\n[*]\nindent_size = 8\n\n[*.{py,pyi}]\ntab_width = 2\n").unwrap();
+        fs::write(root.join("pkg/mod.py"), "x = 1\n").unwrap();
+        fs::write(root.join("pkg/mod.rs"), "fn main() {}\n").unwrap();
+
+        assert_eq!(tab_width_for(root.join("pkg/mod.py")), Some(2));
+        assert_eq!(tab_width_for(root.join("pkg/mod.rs")), Some(8));
+    }
+
+    #[test]
+    fn test_nearest_editorconfig_wins() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join(EDITORCONFIG), "root = true\n[*.py]\ntab_width = 8\n").unwrap();
+        fs::write(root.join("sub").join(EDITORCONFIG), "[*.py]\ntab_width = 3\n").unwrap();
+        fs::write(root.join("sub/a.py"), "x = 1\n").unwrap();
+        fs::write(root.join("b.py"), "x = 1\n").unwrap();
+
+        assert_eq!(tab_width_for(root.join("sub/a.py")), Some(3));
+        assert_eq!(tab_width_for(root.join("b.py")), Some(8));
+    }
+
+    #[test]
+    fn test_cache_reads_each_directory_once() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join(EDITORCONFIG), "root = true\n[*.py]\ntab_width = 2\n[sub/*.py]\ntab_width = 6\n").unwrap();
+
+        let cache = EditorConfigCache::new();
+        assert_eq!(cache.tab_width_for(root.join("a.py")), Some(2));
+        assert_eq!(cache.tab_width_for(root.join("sub/b.py")), Some(6));
+
+        // 已解析的配置不会重新读取
+        fs::write(root.join(EDITORCONFIG), "root = true\n[*.py]\ntab_width = 8\n").unwrap();
+        assert_eq!(cache.tab_width_for(root.join("c.py")), Some(2));
+        assert_eq!(EditorConfigCache::new().tab_width_for(root.join("c.py")), Some(8));
+    }
+
+    #[test]
+    fn test_expand_braces() {
+        assert_eq!(expand_braces("*.{js,ts}"), vec!["*.js".to_string(), "*.ts".to_string()]);
+        assert_eq!(expand_braces("*.py"), vec!["*.py".to_string()]);
+    }
+}
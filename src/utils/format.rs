@@ -1,19 +1,43 @@
 use std::str::FromStr;
 use strum_macros::Display;
 
-#[derive(Debug, Clone, Copy, Display)]
+#[derive(Debug, Default, Clone, Copy, Display)]
 pub enum OutputFormat {
+    #[default]
     Text,
     Json,
+    /// 以语言名为键的 JSON 对象，总计在 `Total` 键下
+    JsonMap,
+    /// 每种语言一行 JSON，最后一行为总计，便于流式处理
+    Jsonl,
     Csv,
+    /// 每行一个 `language=lines`，按语言名排序，便于在 CI 日志中 grep 与比较
+    Ci,
+    /// 按目录逐级嵌套的行数汇总（JSON），可直接用于 treemap 可视化
+    Treemap,
+    /// GitHub 风格的 Markdown 表格，便于贴到 README 或 PR 描述中
+    Markdown,
 }
 
-impl Default for OutputFormat {
-    fn default() -> Self {
-        OutputFormat::Text
+impl OutputFormat {
+    /// 保存到文件时使用的扩展名
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Text => "txt",
+            OutputFormat::Json => "json",
+            OutputFormat::JsonMap => "map.json",
+            OutputFormat::Jsonl => "jsonl",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Ci => "txt",
+            OutputFormat::Treemap => "treemap.json",
+            OutputFormat::Markdown => "md",
+        }
     }
 }
 
+/// 可以解析为 [`OutputFormat`] 的名称（不含别名），用于帮助信息
+pub const OUTPUT_FORMAT_NAMES: &[&str] = &["text", "json", "json-map", "jsonl", "csv", "ci", "treemap", "md"];
+
 impl FromStr for OutputFormat {
     type Err = String;
 
@@ -21,7 +45,12 @@ impl FromStr for OutputFormat {
         match s {
             "text" => Ok(OutputFormat::Text),
             "json" => Ok(OutputFormat::Json),
+            "json-map" => Ok(OutputFormat::JsonMap),
+            "jsonl" | "ndjson" => Ok(OutputFormat::Jsonl),
             "csv" => Ok(OutputFormat::Csv),
+            "ci" => Ok(OutputFormat::Ci),
+            "treemap" => Ok(OutputFormat::Treemap),
+            "md" | "markdown" => Ok(OutputFormat::Markdown),
             _ => Err(format!("Invalid output format: {}", s)),
         }
     }
@@ -29,12 +58,12 @@ impl FromStr for OutputFormat {
 
 impl PartialEq<Self> for OutputFormat {
     fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Self::Text, Self::Text) => true,
-            (Self::Json, Self::Json) => true,
-            (Self::Csv, Self::Csv) => true,
-            _ => false,
-        }
+        matches!(
+            (self, other),
+            (Self::Text, Self::Text) | (Self::Json, Self::Json) | (Self::JsonMap, Self::JsonMap) | (Self::Jsonl, Self::Jsonl) | (Self::Csv, Self::Csv) | (Self::Ci, Self::Ci)
+                | (Self::Treemap, Self::Treemap)
+                | (Self::Markdown, Self::Markdown)
+        )
     }
 }
 
@@ -1,3 +1,8 @@
+pub mod color;
 pub mod format;
 pub mod save;
-pub mod chart;
\ No newline at end of file
+pub mod chart;
+pub mod path_match;
+pub mod editorconfig;
+pub mod watchdog;
+pub mod terminal;
@@ -0,0 +1,57 @@
+use glob::{MatchOptions, Pattern};
+
+/// 基于 glob 模式的路径匹配器
+#[derive(Debug, Clone, Default)]
+pub struct PathMatcher {
+    patterns: Vec<Pattern>,
+}
+
+impl PathMatcher {
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Result<Self, String> {
+        let patterns = patterns
+            .iter()
+            .map(|p| Pattern::new(p.as_ref())
+                .map_err(|e| format!("Invalid glob pattern {}: {}", p.as_ref(), e)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(PathMatcher { patterns })
+    }
+
+    /// 任意一个模式匹配即返回 true，路径分隔符统一为 '/'
+    ///
+    /// 不含 '/' 的模式只匹配文件名，含 '/' 的模式匹配完整路径
+    pub fn is_match(&self, path: &str) -> bool {
+        let path = path.replace('\\', "/");
+        let name = path.rsplit('/').next().unwrap_or(&path);
+        let options = MatchOptions::new();
+
+        self.patterns.iter().any(|p| {
+            if p.as_str().contains('/') {
+                p.matches_with(&path, options)
+            } else {
+                p.matches_with(name, options)
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_test_patterns() {
+        let matcher = PathMatcher::new(crate::consts::DEFAULT_TEST_PATTERNS).unwrap();
+
+        assert!(matcher.is_match("project/tests/it.rs"));
+        assert!(matcher.is_match("src/parser_test.go"));
+        assert!(matcher.is_match("src/test_utils.py"));
+        assert!(matcher.is_match("web/app.spec.ts"));
+        assert!(!matcher.is_match("src/lib.rs"));
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        assert!(PathMatcher::new(&["a[".to_string()]).is_err());
+    }
+}
@@ -22,7 +22,7 @@ pub fn report_to_json(report: &Report) -> Result<String, SaveError> {
     let mut buffer = Vec::new();
     let exporter = JsonExporter::new();
     exporter.export(report, &mut buffer)?;
-    Ok(String::from_utf8(buffer).map_err(|e| SaveError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))?)
+    String::from_utf8(buffer).map_err(|e| SaveError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))
 }
 
 /// 便捷函数：将报告导出为 CSV 字符串
@@ -30,7 +30,7 @@ pub fn report_to_csv(report: &Report) -> Result<String, SaveError> {
     let mut buffer = Vec::new();
     let exporter = CsvExporter::new();
     exporter.export(report, &mut buffer)?;
-    Ok(String::from_utf8(buffer).map_err(|e| SaveError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))?)
+    String::from_utf8(buffer).map_err(|e| SaveError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))
 }
 
 /// 便捷函数：将报告写入到任意实现了 Write 的目标中
@@ -46,11 +46,34 @@ pub fn export_report<W: std::io::Write>(
             let exporter = crate::saver::JsonExporter::new();
             exporter.export(report, writer)
         },
+        OutputFormat::JsonMap => {
+            let exporter = crate::saver::JsonExporter::new().with_keyed(true);
+            exporter.export(report, writer)
+        },
+        OutputFormat::Jsonl => {
+            let exporter = crate::saver::JsonlExporter::new();
+            exporter.export(report, writer)
+        },
         OutputFormat::Csv => {
             let exporter = crate::saver::CsvExporter::new();
             exporter.export(report, writer)
         },
-        OutputFormat::Text => Err(SaveError::UnsupportedFormat),
+        OutputFormat::Treemap => {
+            let exporter = crate::saver::TreemapExporter::new();
+            exporter.export(report, writer)
+        },
+        OutputFormat::Markdown => {
+            let exporter = crate::saver::MarkdownExporter::new();
+            exporter.export(report, writer)
+        },
+        OutputFormat::Text => {
+            let exporter = crate::saver::TextExporter::new();
+            exporter.export(report, writer)
+        },
+        OutputFormat::Ci => {
+            let exporter = crate::saver::CiExporter::new();
+            exporter.export(report, writer)
+        },
     }
 }
 
@@ -58,6 +81,7 @@ pub fn export_report<W: std::io::Write>(
 mod tests {
     use super::*;
     use crate::langs::lang_type::LangType;
+    use crate::report::{GroupBy, SortKey, SortOrder};
     use crate::stats::FileStat;
 
     fn create_test_report() -> Report {
@@ -72,7 +96,15 @@ mod tests {
             comments: 10,
             blanks: 10,
             functions: 5,
+            function_lines: 40,
             classes: 2,
+            commented_code: 0,
+            special_comments: 0,
+            todos: 0,
+            header_lines: 0,
+            complexity: 0,
+            regions: Vec::new(),
+            mixed_eol: false,
         };
 
         let js_stat = FileStat {
@@ -84,7 +116,15 @@ mod tests {
             comments: 5,
             blanks: 5,
             functions: 3,
+            function_lines: 20,
             classes: 1,
+            commented_code: 0,
+            special_comments: 0,
+            todos: 0,
+            header_lines: 0,
+            complexity: 0,
+            regions: Vec::new(),
+            mixed_eol: false,
         };
 
         report.add(rust_stat);
@@ -102,7 +142,104 @@ mod tests {
         assert!(json_str.contains("languages"));
         assert!(json_str.contains("total"));
         assert!(json_str.contains("Rust"));
-        assert!(json_str.contains("JavaScript"));
+        assert!(json_str.contains("Javascript"));
+    }
+
+    #[test]
+    fn test_json_is_stable() {
+        let report = create_test_report();
+        let json_str = report_to_json(&report).unwrap();
+        let expected = r#"{
+  "languages": [
+    {
+      "language": "Rust",
+      "files": 1,
+      "lines": 100,
+      "code": 80,
+      "comments": 10,
+      "blanks": 10,
+      "functions": 5,
+      "classes": 2
+    },
+    {
+      "language": "Javascript",
+      "files": 1,
+      "lines": 50,
+      "code": 40,
+      "comments": 5,
+      "blanks": 5,
+      "functions": 3,
+      "classes": 1
+    }
+  ],
+  "total": {
+    "files": 2,
+    "lines": 150,
+    "code": 120,
+    "comments": 15,
+    "blanks": 15,
+    "functions": 8,
+    "classes": 3
+  },
+  "warnings": []
+}"#;
+
+        // 字段顺序与结构体声明一致，输出逐字节稳定
+        assert_eq!(json_str, expected);
+        assert_eq!(report_to_json(&report).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_text_export() {
+        let report = create_test_report();
+        let mut buffer = Vec::new();
+        export_report(&report, &mut buffer, OutputFormat::Text).unwrap();
+        let text = String::from_utf8(buffer).unwrap();
+
+        // 与终端表格相同的布局，含总计行，不带着色
+        assert!(text.contains("Language     Files    Lines"));
+        assert!(text.lines().any(|l| l.starts_with("Rust         1        100")));
+        assert!(text.lines().any(|l| l.starts_with("Total        2        150        120")));
+        assert!(!text.contains('\x1b'));
+
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let path = dir.path().join("report.txt");
+        save_report(&report, &path, OutputFormat::Text).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), text);
+    }
+
+    #[test]
+    fn test_markdown_export() {
+        let report = create_test_report();
+        let mut buffer = Vec::new();
+        export_report(&report, &mut buffer, OutputFormat::Markdown).unwrap();
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), "\
+| Language | Files | Lines | Code | Comments | Blanks | Functions | Classes |
+|:---|---:|---:|---:|---:|---:|---:|---:|
+| Rust | 1 | 100 | 80 | 10 | 10 | 5 | 2 |
+| Javascript | 1 | 50 | 40 | 5 | 5 | 3 | 1 |
+| **Total** | **2** | **150** | **120** | **15** | **15** | **8** | **3** |
+");
+        assert_eq!("md".parse::<OutputFormat>().unwrap(), OutputFormat::Markdown);
+        assert_eq!("markdown".parse::<OutputFormat>().unwrap(), OutputFormat::Markdown);
+    }
+
+    #[test]
+    fn test_markdown_export_follows_group_by_and_sort() {
+        let report = create_test_report()
+            .with_group_by(GroupBy::Family)
+            .with_sort(SortKey::Lines, Some(SortOrder::Asc));
+        let mut buffer = Vec::new();
+        export_report(&report, &mut buffer, OutputFormat::Markdown).unwrap();
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), "\
+| Family | Files | Lines | Code | Comments | Blanks | Functions | Classes |
+|:---|---:|---:|---:|---:|---:|---:|---:|
+| Web | 1 | 50 | 40 | 5 | 5 | 3 | 1 |
+| Systems | 1 | 100 | 80 | 10 | 10 | 5 | 2 |
+| **Total** | **2** | **150** | **120** | **15** | **15** | **8** | **3** |
+");
     }
 
     #[test]
@@ -113,7 +250,59 @@ mod tests {
         // 验证 CSV 包含头部
         assert!(csv_str.contains("Language,Files"));
         assert!(csv_str.contains("Rust"));
-        assert!(csv_str.contains("JavaScript"));
+        assert!(csv_str.contains("Javascript"));
         assert!(csv_str.contains("Total,"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_csv_totals_match_rows() {
+        let report = create_test_report();
+        let csv_str = report_to_csv(&report).unwrap();
+
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_reader(csv_str.as_bytes());
+        let header = reader.headers().unwrap().clone();
+        assert_eq!(header.len(), 8);
+
+        let mut sums = vec![0usize; header.len() - 1];
+        let mut totals = None;
+        for record in reader.records() {
+            let record = record.unwrap();
+            match &record[0] {
+                "" => continue,
+                "Total" => totals = Some(record),
+                _ => {
+                    for (sum, value) in sums.iter_mut().zip(record.iter().skip(1)) {
+                        *sum += value.parse::<usize>().unwrap();
+                    }
+                }
+            }
+        }
+
+        let totals = totals.expect("missing totals row");
+        let totals: Vec<usize> = totals.iter().skip(1).map(|v| v.parse().unwrap()).collect();
+        assert_eq!(totals, sums);
+        // Functions 与 Classes 各在自己的列
+        assert_eq!(&totals[5..], &[8, 3]);
+    }
+
+    #[test]
+    fn test_csv_records_have_header_width() {
+        let report = create_test_report();
+        let csv_str = report_to_csv(&report).unwrap();
+
+        // 非 flexible 的读取器遇到列数不一致的记录会报错
+        let mut reader = csv::Reader::from_reader(csv_str.as_bytes());
+        let width = reader.headers().unwrap().len();
+        assert_eq!(width, 8);
+
+        let records: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+        assert_eq!(records.len(), 4);
+        for record in &records {
+            assert_eq!(record.len(), width, "{:?}", record);
+        }
+        assert_eq!(&records[0][7], "2");
+        assert_eq!(&records[3][0], "Total");
+    }
+}
@@ -0,0 +1,14 @@
+use std::io::IsTerminal;
+
+use terminal_size::{terminal_size, Width};
+
+/// 标准输出所在终端的列数；不是终端或无法获取时返回 None
+pub fn terminal_width() -> Option<usize> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    terminal_size()
+        .map(|(Width(w), _)| w as usize)
+        .filter(|&w| w > 0)
+        .or_else(|| std::env::var("COLUMNS").ok()?.trim().parse().ok().filter(|&w| w > 0))
+}
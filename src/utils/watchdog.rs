@@ -0,0 +1,59 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// 超时看门狗：在后台线程中等待，到期后把取消标志置为 true
+///
+/// 扫描提前结束时 drop 看门狗即可让后台线程立即退出。
+pub struct Watchdog {
+    done: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Watchdog {
+    pub fn start(timeout: Duration, flag: Arc<AtomicBool>) -> Self {
+        let (done, rx) = mpsc::channel::<()>();
+        let handle = thread::spawn(move || {
+            if let Err(RecvTimeoutError::Timeout) = rx.recv_timeout(timeout) {
+                flag.store(true, Ordering::SeqCst);
+            }
+        });
+
+        Self {
+            done: Some(done),
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        // 关闭通道唤醒后台线程
+        self.done.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watchdog_fires() {
+        let flag = Arc::new(AtomicBool::new(false));
+        let _dog = Watchdog::start(Duration::from_millis(1), Arc::clone(&flag));
+        thread::sleep(Duration::from_millis(50));
+        assert!(flag.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_watchdog_dropped_early() {
+        let flag = Arc::new(AtomicBool::new(false));
+        drop(Watchdog::start(Duration::from_secs(60), Arc::clone(&flag)));
+        assert!(!flag.load(Ordering::SeqCst));
+    }
+}
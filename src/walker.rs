@@ -1,40 +1,83 @@
-use std::path::{Path, PathBuf};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
 
 use walkdir::{DirEntry, WalkDir};
 
-use crate::{config::Config, langs::{lang_type::LangType, registry::get_type_from_ext}};
+use crate::{config::Config, langs::{detect::{alternatives_for_ext, lang_from_content, SNIFF_BYTES}, lang_type::LangType, registry::get_type_from_ext_with}};
 
 #[derive(Debug, Clone)]
 pub struct FileReader {
 
-    config: Config
+    config: Config,
+    ext_overrides: HashMap<String, LangType>,
 }
 
 impl FileReader {
     pub fn new(config: Config) -> Self {
+        let ext_overrides = config.ext_override_map();
         FileReader {
-            config
+            config,
+            ext_overrides,
         }
     }
 
     pub fn walk_dir<P>(&self, path: P) -> Result<Vec<PathBuf>, std::io::Error>
     where
         P: AsRef<Path>,
-    { 
+    {
+        self.walk_dir_until(path, || false)
+    }
+
+    /// 与 `walk_dir` 相同，但 `stop` 返回 true 后立即停止遍历，返回已收集到的文件
+    ///
+    /// `path` 含有 glob 元字符（如 `src/**/*.rs`）时按模式展开为文件列表。
+    pub fn walk_dir_until<P, F>(&self, path: P, stop: F) -> Result<Vec<PathBuf>, std::io::Error>
+    where
+        P: AsRef<Path>,
+        F: Fn() -> bool + Send,
+    {
+        Ok(self.walk_iter_until(path, stop)?.collect())
+    }
+
+    /// 惰性遍历：边遍历边产出通过过滤的文件，不预先收集整棵目录树
+    ///
+    /// 过滤规则与 `walk_dir_until` 完全相同；`stop` 返回 true 后迭代结束。
+    pub fn walk_iter_until<'a, P, F>(&'a self, path: P, stop: F) -> Result<Box<dyn Iterator<Item = PathBuf> + Send + 'a>, std::io::Error>
+    where
+        P: AsRef<Path>,
+        F: Fn() -> bool + Send + 'a,
+    {
+        if let Some(pattern) = path.as_ref().to_str().filter(|p| is_glob(p)) {
+            return self.expand_glob(pattern, stop);
+        }
+
         let root = path.as_ref().to_path_buf();
-        let files = WalkDir::new(&root)
+        let mut walker = WalkDir::new(&root).follow_links(self.config.follow_symlinks);
+        // WalkDir 的深度 0 是根目录本身，配置中的 0 指根目录下直接包含的文件
+        if let Some(depth) = self.config.max_depth {
+            walker = walker.max_depth(depth + 1);
+        }
+        // 跟随符号链接时记录已进入目录的真实路径，链接成环或多个链接指向同一目录时只进入一次
+        let mut visited: HashSet<PathBuf> = HashSet::new();
+        let files = walker
             .into_iter()
-            .filter_entry(|entry| {
+            .filter_entry(move |entry| {
                 let p = entry.path();
+                if self.config.follow_symlinks
+                    && entry.file_type().is_dir()
+                    && let Ok(real) = p.canonicalize()
+                    && !visited.insert(real) {
+                    return false;
+                }
                 if p == root.as_path() {
                     return true;
                 }
 
                 if entry.file_type().is_dir() {
-                    if let Some(name) = p.file_name().and_then(|n| n.to_str()) {
-                        if name.starts_with(".") {
-                            return false;
-                        }
+                    if let Some(name) = p.file_name().and_then(|n| n.to_str())
+                        && (name.starts_with(".") || self.is_vendor_dir(name)) {
+                        return false;
                     }
 
                     for excl in &self.config.exclude_files {
@@ -49,12 +92,30 @@ impl FileReader {
                 }
                 true
             })
+            .take_while(move |_| !stop())
             .filter_map(|e| e.ok())
             .filter(|entry| self.include_entry(entry))
-            .map(|entry| entry.path().to_path_buf())
-            .collect::<Vec<PathBuf>>();
+            .map(|entry| entry.path().to_path_buf());
 
-        Ok(files)
+        Ok(Box::new(files))
+    }
+
+    /// 展开 glob 模式，结果同样经过 `include_path` 过滤
+    fn expand_glob<'a, F>(&'a self, pattern: &str, stop: F) -> Result<Box<dyn Iterator<Item = PathBuf> + Send + 'a>, std::io::Error>
+    where
+        F: Fn() -> bool + Send + 'a,
+    {
+        let paths = glob::glob(pattern)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Invalid glob pattern {}: {}", pattern, e)))?;
+
+        Ok(Box::new(paths
+            .take_while(move |_| !stop())
+            .filter_map(|p| p.ok())
+            .filter(|p| p.is_file() && self.include_path(p))))
+    }
+
+    fn is_vendor_dir(&self, name: &str) -> bool {
+        self.config.no_vendor && self.config.vendor_dirs.iter().any(|dir| dir == name)
     }
 
     fn include_entry(&self, entry: &DirEntry) -> bool {
@@ -64,13 +125,64 @@ impl FileReader {
         if entry.file_type().is_dir() {
             return false;
         }
+        // zip 压缩包交给计数器逐个条目统计
+        if is_archive(path) {
+            return !self.is_excluded(path);
+        }
+
+        // 无扩展名的脚本按首行的 shebang 判断语言；开启 `sniff` 时扩展名无法识别的文件也按内容猜测
+        let unknown_ext = path.extension()
+            .and_then(|e| e.to_str())
+            .is_none_or(|ext| get_type_from_ext_with(&ext.to_lowercase(), &self.ext_overrides).is_none());
+        if path.extension().is_none() || (self.config.sniff && unknown_ext) {
+            return !self.is_excluded(path)
+                && read_head_lang(path, self.config.sniff).is_some_and(|lang| self.wants(lang));
+        }
+
+        self.include_path(path)
+    }
 
-        // 排除任何路径组件以 '.' 开头的（隐藏文件或位于隐藏目录下）
+    fn wants(&self, lang: LangType) -> bool {
+        self.config.wants(lang)
+    }
+
+    /// 按配置判断一个文件路径是否参与统计，不访问文件系统，也可用于 git 树中的路径
+    pub fn include_path(&self, path: &Path) -> bool {
+        if self.is_excluded(path) {
+            return false;
+        }
+        if is_archive(path) {
+            return true;
+        }
+
+        // 仅包含指定类型：根据扩展名判定语言类型，然后与配置 types 比较
+        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+            let ext_str = ext.to_lowercase();
+            let lang = get_type_from_ext_with(&ext_str, &self.ext_overrides).unwrap_or(LangType::Unknown);
+            if let LangType::Unknown = lang {
+                return false;
+            }
+
+            if self.ext_overrides.contains_key(&ext_str) {
+                return self.wants(lang);
+            }
+            // 有歧义的扩展名只要任一候选语言被选中就保留，具体语言由内容决定
+            return self.wants(lang) || alternatives_for_ext(&ext_str).any(|lang| self.wants(lang));
+        }
+
+        // 无扩展名则排除
+        false
+    }
+
+    /// 隐藏路径、第三方目录以及 `exclude_files` 中的文件或目录
+    fn is_excluded(&self, path: &Path) -> bool {
+        // 排除任何路径组件以 '.' 开头的（隐藏文件或位于隐藏目录下），以及第三方目录；
+        // `.`、`..` 本身不算隐藏
         for comp in path.components() {
-            if let Some(s) = comp.as_os_str().to_str() {
-                if s.starts_with('.') {
-                    return false;
-                }
+            if let Component::Normal(name) = comp
+                && let Some(s) = name.to_str()
+                && (s.starts_with('.') || self.is_vendor_dir(s)) {
+                return true;
             }
         }
 
@@ -83,28 +195,36 @@ impl FileReader {
             let excl_lower = excl.to_lowercase();
             let excl_path = Path::new(excl);
             if path.ends_with(excl_path) || path_str.contains(&excl_lower) {
-                return false;
-            }
-        }
-
-        // 仅包含指定类型：根据扩展名判定语言类型，然后与配置 types 比较
-        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-            let ext_str = ext.to_lowercase();
-            let lang = get_type_from_ext(&ext_str).unwrap_or(LangType::Unknown);
-            match lang {
-                LangType::Unknown => return false,
-                _ => {}
+                return true;
             }
-            let types: &Vec<String> = &self.config.types;
-            
-            return types.contains(&lang.to_string().to_lowercase());
         }
-
-        // 无扩展名则排除
         false
     }
 }
 
+/// 根据文件开头的内容判断语言，读取失败、是二进制文件或无法判断时返回 None
+///
+/// 只看 shebang 时读取 256 字节，`sniff` 时读取 `SNIFF_BYTES` 字节。
+fn read_head_lang(path: &Path, sniff: bool) -> Option<LangType> {
+    let limit = if sniff { SNIFF_BYTES } else { 256 };
+    let mut head = Vec::new();
+    std::fs::File::open(path).ok()?.take(limit as u64).read_to_end(&mut head).ok()?;
+    if head.contains(&0) {
+        return None;
+    }
+    lang_from_content(&String::from_utf8_lossy(&head), sniff)
+}
+
+/// 是否为按条目统计的 zip 压缩包
+pub fn is_archive(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+}
+
+/// 路径中是否含有 glob 元字符
+pub fn is_glob(path: &str) -> bool {
+    path.contains(['*', '?', '['])
+}
+
 #[cfg(test)]
 mod tests { 
     use super::*;
@@ -112,8 +232,145 @@ mod tests {
     #[test]
     fn test_walk_dir() {
         let reader = FileReader::new(Config::new());
-        let files = reader.walk_dir(r"G:\Documents\GitHub\toukei").unwrap();
+        let current_dir = std::env::current_dir().unwrap();
+        let files = reader.walk_dir(current_dir).unwrap();
+
+        assert!(!files.is_empty());
+    }
+
+    #[test]
+    fn test_no_vendor_excludes_vendored_dirs() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules/left-pad")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(root.join("node_modules/left-pad/index.js"), "module.exports = 1;\n").unwrap();
+
+        // 清空默认的 exclude_files，只验证 --no-vendor 的效果
+        let config = Config::new().with_exclude_files(vec![]);
+        let files = FileReader::new(config.clone()).walk_dir(root).unwrap();
+        assert_eq!(files.len(), 2);
 
-        assert!(files.len() > 0);
+        let files = FileReader::new(config.clone().enable_no_vendor(true)).walk_dir(root).unwrap();
+        assert_eq!(files, vec![root.join("src/main.rs")]);
+
+        // 覆盖目录列表后 node_modules 不再被排除
+        let config = config.enable_no_vendor(true).with_vendor_dirs(vec!["vendor".to_string()]);
+        let files = FileReader::new(config).walk_dir(root).unwrap();
+        assert_eq!(files.len(), 2);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_max_depth() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("a/b")).unwrap();
+        std::fs::write(root.join("top.rs"), "fn top() {}\n").unwrap();
+        std::fs::write(root.join("a/mid.rs"), "fn mid() {}\n").unwrap();
+        std::fs::write(root.join("a/b/deep.rs"), "fn deep() {}\n").unwrap();
+
+        let walk = |config: Config| {
+            let mut files = FileReader::new(config).walk_dir(root).unwrap();
+            files.sort();
+            files
+        };
+        assert_eq!(walk(Config::new().with_max_depth(0)), vec![root.join("top.rs")]);
+        assert_eq!(walk(Config::new().with_max_depth(1)), vec![root.join("a/mid.rs"), root.join("top.rs")]);
+        assert_eq!(walk(Config::new()).len(), 3);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_follow_symlinks_terminates_on_cycle() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("shared")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(root.join("shared/lib.rs"), "pub fn lib() {}\n").unwrap();
+        std::os::unix::fs::symlink(root.join("shared"), root.join("src/shared")).unwrap();
+        // 指回根目录的链接形成循环
+        std::os::unix::fs::symlink(root, root.join("shared/loop")).unwrap();
+
+        let config = Config::new().with_exclude_files(vec![]);
+        let mut files = FileReader::new(config.clone()).walk_dir(root).unwrap();
+        files.sort();
+        assert_eq!(files, vec![root.join("shared/lib.rs"), root.join("src/main.rs")]);
+
+        let files = FileReader::new(config.enable_follow_symlinks(true)).walk_dir(root).unwrap();
+        let mut names: Vec<_> = files.iter().map(|f| f.file_name().unwrap().to_owned()).collect();
+        names.sort();
+        assert_eq!(names, ["lib.rs", "main.rs"]);
+    }
+
+    #[test]
+    fn test_walk_recursive_glob() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src/nested")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "pub mod nested;\n").unwrap();
+        std::fs::write(root.join("src/nested/mod.rs"), "fn f() {}\n").unwrap();
+        std::fs::write(root.join("src/app.py"), "print(1)\n").unwrap();
+
+        let pattern = format!("{}/src/**/*.rs", root.display());
+        assert!(is_glob(&pattern));
+
+        let files = FileReader::new(Config::new()).walk_dir(&pattern).unwrap();
+        assert_eq!(files, vec![root.join("src/lib.rs"), root.join("src/nested/mod.rs")]);
+    }
+
+    #[test]
+    fn test_exclude_types() {
+        let config = Config::new().with_exclude_types(vec!["json".to_string(), "yaml".to_string()]);
+        let reader = FileReader::new(config);
+        assert!(reader.include_path(Path::new("src/main.rs")));
+        assert!(!reader.include_path(Path::new("package.json")));
+        assert!(!reader.include_path(Path::new("ci.yml")));
+
+        // 同时出现在两个列表中时以排除为准
+        let config = Config::new()
+            .with_types(vec!["rust".to_string(), "json".to_string()])
+            .with_exclude_types(vec!["JSON".to_string()]);
+        let reader = FileReader::new(config);
+        assert!(reader.include_path(Path::new("src/main.rs")));
+        assert!(!reader.include_path(Path::new("package.json")));
+    }
+
+    #[test]
+    fn test_walk_includes_shebang_scripts() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("deploy"), "#!/usr/bin/env python3\nprint(1)\n").unwrap();
+        std::fs::write(root.join("LICENSE"), "MIT\n").unwrap();
+
+        let files = FileReader::new(Config::new()).walk_dir(root).unwrap();
+        assert_eq!(files, vec![root.join("deploy")]);
+
+        let config = Config::new().with_exclude_types(vec!["python".to_string()]);
+        assert!(FileReader::new(config).walk_dir(root).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_walk_sniffs_unknown_files() {
+        let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+        let root = dir.path();
+        let python = "# tasks\ndef build():\n    if ready():\n        run()\n    elif stale():\n        clean()\n";
+        std::fs::write(root.join("tasks"), python).unwrap();
+        std::fs::write(root.join("deploy.task"), python).unwrap();
+        std::fs::write(root.join("LICENSE"), "MIT\n").unwrap();
+
+        assert!(FileReader::new(Config::new()).walk_dir(root).unwrap().is_empty());
+
+        let mut files = FileReader::new(Config::new().enable_sniff(true)).walk_dir(root).unwrap();
+        files.sort();
+        assert_eq!(files, vec![root.join("deploy.task"), root.join("tasks")]);
+    }
+
+    #[test]
+    fn test_current_dir_component_is_not_hidden() {
+        let reader = FileReader::new(Config::new());
+        assert!(reader.include_path(Path::new("./src/main.rs")));
+        assert!(!reader.include_path(Path::new("./.hidden/main.rs")));
+    }
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use toukei::fc::{AsyncFileCounter, FileCounter};
+use toukei::langs::lang_type::LangType;
+
+const C_HEADER: &str = "#include <stdio.h>\nint add(int a, int b);\n";
+const OBJC_HEADER: &str = "#import <Foundation/Foundation.h>\n@interface Foo : NSObject\n@end\n";
+
+fn types(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn header_resolved_to_unwanted_language_is_dropped() {
+    let (_dir, config) = common::fixture([("plain.h", C_HEADER), ("foo.h", OBJC_HEADER)]);
+
+    // 只要 Objective-C：内容是 C 的头文件不计入
+    let config_objc = config.clone().with_types(types(&["objectivec"]));
+    let report = FileCounter::new(config_objc.clone()).process().unwrap();
+    assert_eq!(report.file_count(), 1);
+    assert!(report.get_by_lang(&LangType::C).is_none());
+    assert_eq!(report.get_by_lang(&LangType::ObjectiveC).unwrap().files, 1);
+    let report = AsyncFileCounter::new(config_objc).process_blocking().unwrap();
+    assert!(report.get_by_lang(&LangType::C).is_none());
+
+    // 只要 C：带有 `@interface` 的头文件不计入
+    let report = FileCounter::new(config.clone().with_types(types(&["c"]))).process().unwrap();
+    assert_eq!(report.file_count(), 1);
+    assert!(report.get_by_lang(&LangType::ObjectiveC).is_none());
+    assert_eq!(report.get_by_lang(&LangType::C).unwrap().files, 1);
+
+    // 排除列表同样作用于按内容确定的语言
+    let report = FileCounter::new(config.with_exclude_types(types(&["objectivec"]))).process().unwrap();
+    assert_eq!(report.file_count(), 1);
+    assert!(report.get_by_lang(&LangType::ObjectiveC).is_none());
+}
+
+#[test]
+fn m_file_resolved_to_unwanted_language_is_dropped() {
+    let (_dir, config) = common::fixture([
+        ("Greeter.m", "#import \"Greeter.h\"\n@implementation Greeter\n- (void)greet {\n}\n@end\n"),
+        ("square.m", "% square\nfunction y = square(x)\n    y = x .^ 2;\nend\n"),
+    ]);
+
+    let report = FileCounter::new(config.clone().with_types(types(&["matlab"]))).process().unwrap();
+    assert_eq!(report.file_count(), 1);
+    assert!(report.get_by_lang(&LangType::ObjectiveC).is_none());
+    assert_eq!(report.get_by_lang(&LangType::Matlab).unwrap().files, 1);
+
+    let report = AsyncFileCounter::new(config.with_types(types(&["objectivec"]))).process_blocking().unwrap();
+    assert_eq!(report.file_count(), 1);
+    assert!(report.get_by_lang(&LangType::Matlab).is_none());
+}
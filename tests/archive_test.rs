@@ -0,0 +1,41 @@
+mod common;
+
+use std::io::{Cursor, Write};
+
+use toukei::fc::{AsyncFileCounter, FileCounter};
+use toukei::langs::lang_type::LangType;
+use zip::write::SimpleFileOptions;
+
+fn bundle() -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+    writer.start_file("src/lib.rs", options).unwrap();
+    writer.write_all(b"// lib\npub fn f() {}\n").unwrap();
+    writer.start_file("tools/gen.py", options).unwrap();
+    writer.write_all(b"print('gen')\n").unwrap();
+    writer.finish().unwrap().into_inner()
+}
+
+#[test]
+fn zip_entries_are_counted_during_scan() {
+    let (dir, config) = common::fixture([
+        ("main.rs", b"fn main() {}\n".to_vec()),
+        ("vendor.zip", bundle()),
+    ]);
+    let archive = dir.path().join("vendor.zip").display().to_string();
+
+    let report = FileCounter::new(config.clone()).process().unwrap();
+    assert_eq!(report.file_count(), 3);
+    let rust = report.get_by_lang(&LangType::Rust).unwrap();
+    assert_eq!((rust.files, rust.code), (2, 2));
+    assert!(rust.stats.iter().any(|s| s.path == format!("{}!src/lib.rs", archive)));
+    assert_eq!(report.get_by_lang(&LangType::Python).unwrap().code, 1);
+
+    let async_report = AsyncFileCounter::new(config.clone()).process_blocking().unwrap();
+    assert!(report.diff(&async_report).is_empty());
+
+    // 语言过滤同样作用于压缩包中的条目
+    let report = FileCounter::new(config.with_types(vec!["python".to_string()])).process().unwrap();
+    assert_eq!(report.file_count(), 1);
+    assert!(report.get_by_lang(&LangType::Rust).is_none());
+}
@@ -1,3 +1,5 @@
+mod common;
+
 use toukei::config::Config;
 use toukei::fc::{FileCounter, AsyncFileCounter};
 
@@ -92,4 +94,23 @@ async fn test_async_vs_sync_performance() {
 
     println!("Performance ratio (async/sync): {:.2}",
              async_duration.as_millis() as f64 / sync_duration.as_millis() as f64);
-}
\ No newline at end of file
+}
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_async_partial_reports_match_sync() {
+    let files = (0..200).flat_map(|i| {
+        let body = "// comment\nfn f() {\n\n    1\n}\n".repeat(i % 7 + 1);
+        [(format!("f{}.rs", i), body.into_bytes()), (format!("s{}.py", i), b"# c\nx = 1\n".to_vec())]
+    });
+    let (_dir, config) = common::fixture(files.chain([("blob.rs".to_string(), b"\0\0\0".to_vec())]));
+    let sync_report = FileCounter::new(config.clone()).process().unwrap();
+    let async_report = AsyncFileCounter::new(config).with_workers(8).process().await.unwrap();
+
+    // 各工作任务的部分报告合并后，每种语言的统计与警告都与同步统计一致
+    assert_eq!(calculate_totals(&async_report), calculate_totals(&sync_report));
+    for (lang, stat) in &sync_report.inner {
+        let other = &async_report.inner[lang];
+        assert_eq!((other.files, other.lines, other.code, other.functions), (stat.files, stat.lines, stat.code, stat.functions));
+    }
+    assert_eq!(async_report.warnings, sync_report.warnings);
+    assert_eq!(async_report.warnings.len(), 1);
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use toukei::cli::Cli;
+use toukei::config::Config;
+use toukei::fc::FileCounter;
+use toukei::langs::lang_type::LangType;
+use toukei::report::LangDelta;
+
+#[test]
+fn baseline_delta_reports_only_the_added_file() {
+    let shared = [("main.rs", "fn main() {\n    run();\n}\n"), ("app.py", "print(1)\n")];
+    let (_upstream, upstream) = common::fixture(shared);
+    let (_fork, fork) = common::fixture(shared.into_iter().chain([("extra.rs", "// helper\n\nfn helper() {}\n")]));
+
+    let scan = |config: &Config| FileCounter::new(config.clone()).process().unwrap();
+    let deltas = scan(&fork).delta(&scan(&upstream));
+
+    // Python 没有变化，不出现在结果中
+    assert_eq!(deltas, vec![LangDelta {
+        lang: LangType::Rust,
+        files: 1,
+        lines: 3,
+        code: 1,
+        comments: 1,
+        blanks: 1,
+        functions: 1,
+        classes: 0,
+    }]);
+
+    // 反过来比较得到负数
+    let reverse = scan(&upstream).delta(&scan(&fork));
+    assert_eq!((reverse[0].files, reverse[0].lines), (-1, -3));
+
+    let text = Cli::new().render_delta(&deltas);
+    assert!(text.lines().any(|l| l.starts_with("Rust         +1         +3")));
+    assert!(text.lines().any(|l| l.starts_with("Total        +1         +3")));
+    assert_eq!(Cli::new().render_delta(&[]), "no change: target and baseline are identical\n");
+}
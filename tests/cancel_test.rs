@@ -0,0 +1,110 @@
+mod common;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use toukei::config::Config;
+use toukei::fc::{AsyncFileCounter, FileCounter, ProcessError};
+use toukei::langs::lang_type::LangType;
+use toukei::syntax::lex_status::LineCtx;
+use toukei::syntax::{register_classifier, Classifier, LineClassification, LineKind, LineSpan, SpanKind};
+
+fn tree(files: usize) -> (tempfile::TempDir, Config) {
+    common::fixture((0..files).map(|i| (format!("f{}.rs", i), "fn main() {}\n// c\n")))
+}
+
+#[test]
+fn test_cancel_mid_scan() {
+    let (_dir, config) = tree(3_000);
+    let cancel = Arc::new(AtomicBool::new(false));
+
+    let flag = Arc::clone(&cancel);
+    let canceller = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(1));
+        flag.store(true, Ordering::SeqCst);
+    });
+
+    let start = Instant::now();
+    let result = FileCounter::new(config).with_cancel(cancel).process();
+    canceller.join().unwrap();
+
+    assert_eq!(result.unwrap_err(), ProcessError::Cancelled);
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+/// 已分类的 Lua 行数，用于在扫描进行到一半时取消
+static CLASSIFIED: AtomicUsize = AtomicUsize::new(0);
+
+/// 每行都很慢的分类器，使扫描在取消时一定还在进行
+struct SlowClassifier;
+
+impl Classifier for SlowClassifier {
+    fn classify(&self, line: LineCtx) -> LineClassification {
+        thread::sleep(Duration::from_millis(1));
+        CLASSIFIED.fetch_add(1, Ordering::SeqCst);
+        let start = line.offset();
+        let end = start + line.trimmed().len();
+        LineClassification {
+            kind: LineKind::Code,
+            spans: vec![LineSpan { kind: SpanKind::Code, range: start..end }],
+        }
+    }
+}
+
+#[test]
+fn test_cancel_returns_partial_report() {
+    register_classifier(LangType::Lua, SlowClassifier);
+    let (_dir, config) = common::fixture((0..200).map(|i| (format!("f{}.lua", i), "x = 1\n".repeat(5))));
+    let cancel = Arc::new(AtomicBool::new(false));
+
+    // 已分类 200 行（约 40 个文件）后取消，此时扫描远未完成
+    let flag = Arc::clone(&cancel);
+    let canceller = thread::spawn(move || {
+        while CLASSIFIED.load(Ordering::SeqCst) < 200 {
+            thread::sleep(Duration::from_millis(1));
+        }
+        flag.store(true, Ordering::SeqCst);
+    });
+
+    let report = FileCounter::new(config)
+        .with_cancel(cancel)
+        .with_partial_on_cancel(true)
+        .process()
+        .unwrap();
+    canceller.join().unwrap();
+
+    assert!(report.file_count() > 0, "no file was counted before cancelling");
+    assert!(report.file_count() < 200, "scan ran to completion");
+}
+
+#[test]
+fn test_uncancelled_scan_completes() {
+    let (_dir, config) = tree(10);
+    let cancel = Arc::new(AtomicBool::new(false));
+
+    let report = FileCounter::new(config).with_cancel(cancel).process().unwrap();
+    assert_eq!(report.file_count(), 10);
+}
+
+#[tokio::test]
+async fn test_async_cancel() {
+    let (_dir, config) = tree(10);
+    let cancel = Arc::new(AtomicBool::new(true));
+
+    let err = AsyncFileCounter::new(config.clone())
+        .with_cancel(Arc::clone(&cancel))
+        .process()
+        .await
+        .unwrap_err();
+    assert_eq!(err, ProcessError::Cancelled);
+
+    let report = AsyncFileCounter::new(config)
+        .with_cancel(cancel)
+        .with_partial_on_cancel(true)
+        .process()
+        .await
+        .unwrap();
+    assert!(report.file_count() < 10);
+}
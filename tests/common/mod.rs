@@ -0,0 +1,24 @@
+use std::fs;
+use std::path::Path;
+
+use toukei::config::Config;
+
+/// 在临时目录中写入给定的 (相对路径, 内容) 文件，并返回扫描该目录的配置
+///
+/// 返回的 `TempDir` 需在测试期间保持存活，离开作用域时目录会被删除。
+pub fn fixture<N, C>(files: impl IntoIterator<Item = (N, C)>) -> (tempfile::TempDir, Config)
+where
+    N: AsRef<Path>,
+    C: AsRef<[u8]>,
+{
+    let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+    for (name, contents) in files {
+        let path = dir.path().join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(path, contents).unwrap();
+    }
+    let config = Config::new().with_paths(vec![dir.path().display().to_string()]);
+    (dir, config)
+}
@@ -0,0 +1,64 @@
+use std::fs;
+
+use toukei::config::Config;
+use toukei::parser::args_parser::ArgParser;
+use toukei::utils::format::OutputFormat;
+
+const TOUKEI_TOML: &str = r#"
+paths = ["src", "lib"]
+types = ["Rust", "python"]
+ignore_blanks = true
+ignore_comments = true
+exclude_files = ["generated"]
+output = "json,csv"
+num_workers = 3
+"#;
+
+#[test]
+fn test_from_file() {
+    let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+    let path = dir.path().join("toukei.toml");
+    fs::write(&path, TOUKEI_TOML).unwrap();
+
+    let config = Config::from_file(&path).unwrap();
+    assert_eq!(config.paths, vec!["src", "lib"]);
+    assert_eq!(config.types, vec!["rust", "python"]);
+    assert!(config.ignore_blanks);
+    assert!(config.ignore_comments);
+    assert_eq!(config.exclude_files, vec!["generated"]);
+    assert_eq!(config.output_formats, vec![OutputFormat::Json, OutputFormat::Csv]);
+    assert_eq!(config.num_workers, 3);
+
+    // 未出现的字段保持默认值
+    let config = Config::from_toml("num_workers = 2").unwrap();
+    assert_eq!(config.paths, Config::new().paths);
+    assert_eq!(config.num_workers, 2);
+
+    assert!(Config::from_toml("unknown_key = 1").is_err());
+    assert!(Config::from_toml("output = \"yaml\"").is_err());
+    assert!(Config::from_file(dir.path().join("missing.toml")).is_err());
+}
+
+#[test]
+fn test_cli_flags_override_config_file() {
+    let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+    let path = dir.path().join("toukei.toml");
+    fs::write(&path, TOUKEI_TOML).unwrap();
+    let path = path.display().to_string();
+
+    let mut parser = ArgParser::default();
+    let matches = parser.build_matches(vec!["--config", path.as_str(), "--path", "app", "--num-workers", "5"]).unwrap();
+    let config = parser.parse_matches(&matches).unwrap();
+
+    // 命令行给出的值优先
+    assert_eq!(config.paths, vec!["app"]);
+    assert_eq!(config.num_workers, 5);
+    // 命令行未给出的值来自文件
+    assert!(config.ignore_blanks);
+    assert!(config.ignore_comments);
+    assert_eq!(config.exclude_files, vec!["generated"]);
+    assert_eq!(config.output_formats, vec![OutputFormat::Json, OutputFormat::Csv]);
+
+    let matches = parser.build_matches(vec!["--config", "/nonexistent/toukei.toml"]).unwrap();
+    assert!(parser.parse_matches(&matches).is_err());
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use toukei::config::Config;
+use toukei::counter::Counter;
+use toukei::langs::lang_type::LangType;
+use toukei::syntax::lex_status::LineCtx;
+use toukei::syntax::{register_classifier, unregister_classifier, Classifier, LineClassification, LineKind, LineSpan, SpanKind};
+
+/// 只认 `//` 行注释，不识别块注释
+struct LineCommentOnly;
+
+impl Classifier for LineCommentOnly {
+    fn classify(&self, line: LineCtx) -> LineClassification {
+        let trimmed = line.trimmed();
+        if trimmed.is_empty() {
+            return LineClassification::blank();
+        }
+        let (kind, span) = if trimmed.starts_with("//") {
+            (LineKind::Comment, SpanKind::Comment)
+        } else {
+            (LineKind::Code, SpanKind::Code)
+        };
+        let start = line.offset();
+        LineClassification {
+            kind,
+            spans: vec![LineSpan { kind: span, range: start..start + trimmed.len() }],
+        }
+    }
+}
+
+#[test]
+fn registered_classifier_is_used_for_counting() {
+    let (dir, _) = common::fixture([("lib.rs", "/* block\n   comment */\n// line\nfn f() {}\n")]);
+    let file = dir.path().join("lib.rs");
+
+    let counter = Counter::new(Config::new());
+    let builtin = counter.count(&file).unwrap();
+    assert_eq!((builtin.code, builtin.comments), (1, 3));
+
+    assert!(register_classifier(LangType::Rust, LineCommentOnly).is_none());
+    let custom = counter.count(&file).unwrap();
+    assert_eq!((custom.code, custom.comments), (3, 1));
+
+    // 大文件分段分析同样使用注册的分类器
+    let parallel = Counter::new(Config::new().enable_parallel_files(true).with_parallel_threshold(0));
+    assert_eq!(parallel.count(&file).unwrap(), custom);
+
+    assert!(unregister_classifier(LangType::Rust).is_some());
+    assert_eq!(counter.count(&file).unwrap(), builtin);
+}
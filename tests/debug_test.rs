@@ -15,7 +15,7 @@ fn test_debug_file_counting() {
     match reader.walk_dir(".") {
         Ok(files) => {
             println!("Found {} files", files.len());
-            if files.len() > 0 {
+            if !files.is_empty() {
                 println!("First few files:");
                 for (i, file) in files.iter().take(5).enumerate() {
                     println!("  {}: {:?}", i, file);
@@ -18,7 +18,7 @@ fn test_debug_file_processing() {
     match reader.walk_dir(&config.paths[0]) {
         Ok(files) => {
             println!("Found {} files", files.len());
-            if files.len() > 0 {
+            if !files.is_empty() {
                 println!("First few files with extensions:");
                 for (i, file) in files.iter().take(10).enumerate() {
                     let ext = file.extension()
@@ -0,0 +1,22 @@
+mod common;
+
+use toukei::fc::FileCounter;
+
+#[test]
+fn process_detailed_returns_every_file() {
+    let (_dir, config) = common::fixture([
+        ("src/main.rs", "fn main() {}\n"),
+        ("src/lib.rs", "// lib\npub fn f() {}\n"),
+        ("app.py", "print(1)\n"),
+    ]);
+
+    let (report, files) = FileCounter::new(config).process_detailed().unwrap();
+    assert_eq!(files.len(), report.file_count());
+    assert_eq!(files.len(), 3);
+
+    let paths: Vec<_> = files.iter().map(|f| f.path.clone()).collect();
+    let mut sorted = paths.clone();
+    sorted.sort();
+    assert_eq!(paths, sorted);
+    assert_eq!(files.iter().map(|f| f.lines).sum::<usize>(), 4);
+}
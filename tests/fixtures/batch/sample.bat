@@ -0,0 +1,6 @@
+@echo off
+REM build script
+
+:build
+echo Building
+goto :eof
@@ -0,0 +1,12 @@
+#include <stdio.h>
+
+/* Adds two numbers. */
+struct point {
+    int x;
+    int y;
+};
+
+// entry point
+int add(int a, int b) {
+    return a + b;
+}
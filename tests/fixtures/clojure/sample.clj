@@ -0,0 +1,5 @@
+;; greeting helpers
+(ns sample.core)
+
+(defn greet [name]
+  (str "Hello, " name))
@@ -0,0 +1,12 @@
+#include <string>
+
+// A simple shape.
+class Shape {
+public:
+    virtual double area() const = 0;
+};
+
+/* helper */
+int square(int x) {
+    return x * x;
+}
@@ -0,0 +1,13 @@
+using System;
+
+namespace Sample
+{
+    // Greeter class
+    public class Greeter
+    {
+        public void Greet(string name)
+        {
+            Console.WriteLine(name);
+        }
+    }
+}
@@ -0,0 +1,8 @@
+// A counter.
+class Counter {
+  int value = 0;
+
+  void increment() {
+    value++;
+  }
+}
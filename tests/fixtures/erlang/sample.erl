@@ -0,0 +1,6 @@
+% sample module
+-module(sample).
+-export([add/2]).
+
+add(A, B) ->
+    A + B.
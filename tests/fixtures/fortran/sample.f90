@@ -0,0 +1,8 @@
+! compute a sum
+program sample
+    implicit none
+    integer :: total
+
+    total = 1 + 2
+    print *, total
+end program sample
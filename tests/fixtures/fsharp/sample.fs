@@ -0,0 +1,7 @@
+module Sample
+
+// adds numbers
+let add a b = a + b
+
+(* entry *)
+printfn "%d" (add 1 2)
@@ -0,0 +1,12 @@
+package main
+
+import "fmt"
+
+// Point is a 2D point.
+type Point struct {
+	X, Y int
+}
+
+func main() {
+	fmt.Println(Point{1, 2})
+}
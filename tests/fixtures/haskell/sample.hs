@@ -0,0 +1,8 @@
+module Main where
+
+-- | Entry point.
+main :: IO ()
+main = putStrLn "Hello"
+
+{- helper -}
+double x = x * 2
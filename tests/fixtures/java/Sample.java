@@ -0,0 +1,11 @@
+package sample;
+
+/**
+ * A greeter.
+ */
+public class Sample {
+    // entry point
+    public static void main(String[] args) {
+        System.out.println("Hello");
+    }
+}
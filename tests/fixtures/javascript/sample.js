@@ -0,0 +1,7 @@
+// utilities
+function add(a, b) {
+  return a + b;
+}
+
+/* exported */
+module.exports = { add };
@@ -0,0 +1,8 @@
+# arithmetic helpers
+function add(a, b)
+    a + b
+end
+
+#= block
+comment =#
+println(add(1, 2))
@@ -0,0 +1,6 @@
+// A point.
+data class Point(val x: Int, val y: Int)
+
+fun main() {
+    println(Point(1, 2))
+}
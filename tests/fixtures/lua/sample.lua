@@ -0,0 +1,8 @@
+-- utilities
+local function add(a, b)
+  return a + b
+end
+
+--[[ entry
+point ]]
+print(add(1, 2))
@@ -0,0 +1,8 @@
+% compute a square
+function y = square(x)
+    y = x .^ 2;
+end
+
+%{
+block comment
+%}
@@ -0,0 +1,7 @@
+# package set
+{ pkgs }:
+
+/* the shell */
+pkgs.mkShell {
+  buildInputs = [ pkgs.hello ];
+}
@@ -0,0 +1,12 @@
+#import <Foundation/Foundation.h>
+
+// A greeter.
+@interface Greeter : NSObject
+- (void)greet;
+@end
+
+@implementation Greeter
+- (void)greet {
+    NSLog(@"Hello");
+}
+@end
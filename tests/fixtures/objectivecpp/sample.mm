@@ -0,0 +1,9 @@
+#import <Foundation/Foundation.h>
+#include <vector>
+
+// Sums a vector.
+int sum(const std::vector<int>& v) {
+    int total = 0;
+    for (int x : v) total += x;
+    return total;
+}
@@ -0,0 +1,5 @@
+(* arithmetic *)
+let add a b = a + b
+
+let () =
+  print_int (add 1 2)
@@ -0,0 +1,7 @@
+#!/usr/bin/perl
+use strict;
+
+# greet someone
+sub greet {
+    print "Hello\n";
+}
@@ -0,0 +1,10 @@
+<?php
+
+// A greeter.
+class Greeter
+{
+    public function greet($name)
+    {
+        return "Hello " . $name;
+    }
+}
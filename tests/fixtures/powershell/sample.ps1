@@ -0,0 +1,9 @@
+# greet someone
+function Get-Greeting {
+    param($Name)
+    "Hello $Name"
+}
+
+<# entry
+point #>
+Get-Greeting -Name "World"
@@ -0,0 +1,13 @@
+# utilities
+import os
+
+
+class Greeter:
+    """Greets people."""
+
+    def greet(self, name):
+        return "Hello " + name
+
+
+def main():
+    print(Greeter().greet(os.getcwd()))
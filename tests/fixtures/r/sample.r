@@ -0,0 +1,6 @@
+# add numbers
+add <- function(a, b) {
+  a + b
+}
+
+print(add(1, 2))
@@ -0,0 +1,10 @@
+# A greeter.
+class Greeter
+  def greet(name)
+    "Hello #{name}"
+  end
+end
+
+=begin
+block comment
+=end
@@ -0,0 +1,20 @@
+//! Sample crate.
+
+use std::fmt;
+
+/// A point.
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/* entry */
+fn main() {
+    println!("{}", Point { x: 1, y: 2 });
+}
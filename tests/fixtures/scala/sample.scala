@@ -0,0 +1,7 @@
+// A greeter.
+object Sample {
+  def greet(name: String): String = s"Hello $name"
+
+  def main(args: Array[String]): Unit =
+    println(greet("World"))
+}
@@ -0,0 +1,8 @@
+#!/bin/sh
+# greet someone
+
+greet() {
+    echo "Hello $1"
+}
+
+greet World
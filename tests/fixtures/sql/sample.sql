@@ -0,0 +1,8 @@
+-- users table
+CREATE TABLE users (
+    id INTEGER PRIMARY KEY,
+    name TEXT
+);
+
+/* seed */
+INSERT INTO users VALUES (1, 'a');
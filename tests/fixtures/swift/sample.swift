@@ -0,0 +1,9 @@
+// A point.
+struct Point {
+    var x: Int
+    var y: Int
+}
+
+func describe(_ p: Point) -> String {
+    return "\(p.x), \(p.y)"
+}
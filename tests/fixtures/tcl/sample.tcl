@@ -0,0 +1,6 @@
+# greet someone
+proc greet {name} {
+    puts "Hello $name"
+}
+
+greet World
@@ -0,0 +1,11 @@
+namespace rs tutorial
+
+# A pair of numbers
+struct Pair {
+  1: i32 a,
+  2: i32 b,
+}
+
+service Calculator {
+  i32 add(1: Pair pair), // sum
+}
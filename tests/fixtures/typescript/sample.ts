@@ -0,0 +1,9 @@
+// A point.
+interface Point {
+  x: number;
+  y: number;
+}
+
+export function norm(p: Point): number {
+  return Math.hypot(p.x, p.y);
+}
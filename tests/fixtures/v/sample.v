@@ -0,0 +1,6 @@
+module main
+
+// entry point
+fn main() {
+	println('Hello')
+}
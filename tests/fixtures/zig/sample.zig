@@ -0,0 +1,6 @@
+const std = @import("std");
+
+// entry point
+pub fn main() void {
+    std.debug.print("Hello\n", .{});
+}
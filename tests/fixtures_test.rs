@@ -0,0 +1,103 @@
+use std::collections::HashSet;
+
+use toukei::config::Config;
+use toukei::counter::Counter;
+use toukei::langs::lang_type::LangType;
+use toukei::langs::registry::LANGUAGE_DEFINITIONS;
+
+/// 每种语言的样例文件及其预期统计：(路径, 语言, [lines, code, comments, blanks, functions, classes])
+const FIXTURES: &[(&str, LangType, [usize; 6])] = &[
+    ("asciidoc/sample.adoc", LangType::Asciidoc, [6, 4, 0, 2, 0, 0]),
+    ("astro/sample.astro", LangType::Astro, [6, 4, 1, 1, 0, 0]),
+    ("avro/sample.avdl", LangType::Avro, [9, 6, 1, 2, 1, 2]),
+    ("batch/sample.bat", LangType::Batch, [6, 4, 1, 1, 1, 0]),
+    ("c/sample.c", LangType::C, [12, 8, 2, 2, 1, 0]),
+    ("clojure/sample.clj", LangType::Clojure, [5, 3, 1, 1, 1, 0]),
+    ("cpp/sample.cpp", LangType::Cpp, [12, 8, 2, 2, 1, 1]),
+    ("csharp/sample.cs", LangType::Csharp, [13, 11, 1, 1, 1, 1]),
+    ("css/sample.css", LangType::Css, [6, 4, 1, 1, 0, 0]),
+    ("d/sample.d", LangType::D, [7, 5, 1, 1, 1, 0]),
+    ("dart/sample.dart", LangType::Dart, [8, 6, 1, 1, 1, 1]),
+    ("elm/sample.elm", LangType::Elm, [5, 3, 1, 1, 1, 0]),
+    ("erlang/sample.erl", LangType::Erlang, [6, 4, 1, 1, 1, 0]),
+    ("fortran/sample.f90", LangType::Fortran, [8, 6, 1, 1, 0, 0]),
+    ("fsharp/sample.fs", LangType::Fsharp, [7, 3, 2, 2, 1, 0]),
+    ("go/sample.go", LangType::Go, [12, 8, 1, 3, 1, 1]),
+    ("graphql/sample.graphql", LangType::Graphql, [6, 5, 1, 0, 1, 0]),
+    ("haskell/sample.hs", LangType::Haskell, [8, 4, 2, 2, 2, 0]),
+    ("html/sample.html", LangType::Html, [7, 6, 1, 0, 0, 0]),
+    ("java/Sample.java", LangType::Java, [11, 6, 4, 1, 1, 1]),
+    ("javascript/sample.js", LangType::Javascript, [7, 4, 2, 1, 1, 0]),
+    ("json/sample.json", LangType::Json, [5, 4, 0, 1, 0, 0]),
+    ("jsonc/sample.jsonc", LangType::Jsonc, [7, 4, 2, 1, 0, 0]),
+    ("jsonnet/sample.jsonnet", LangType::Jsonnet, [7, 4, 2, 1, 0, 0]),
+    ("julia/sample.jl", LangType::Julia, [8, 4, 3, 1, 1, 0]),
+    ("kotlin/sample.kt", LangType::Kotlin, [6, 4, 1, 1, 1, 1]),
+    ("lua/sample.lua", LangType::Lua, [8, 4, 3, 1, 1, 0]),
+    // Markdown 只统计总行数
+    ("markdown/sample.md", LangType::Markdown, [6, 0, 0, 0, 0, 0]),
+    ("matlab/sample.m", LangType::Matlab, [8, 3, 4, 1, 1, 0]),
+    ("nix/sample.nix", LangType::Nix, [7, 4, 2, 1, 1, 0]),
+    ("objectivec/sample.m", LangType::ObjectiveC, [12, 9, 1, 2, 1, 2]),
+    ("objectivecpp/sample.mm", LangType::ObjectiveCpp, [9, 7, 1, 1, 1, 0]),
+    ("ocaml/sample.ml", LangType::Ocaml, [5, 3, 1, 1, 1, 0]),
+    ("perl/sample.pl", LangType::Perl, [7, 4, 2, 1, 1, 0]),
+    ("php/sample.php", LangType::Php, [10, 8, 1, 1, 1, 1]),
+    ("powershell/sample.ps1", LangType::PowerShell, [9, 5, 3, 1, 1, 0]),
+    ("protobuf/sample.proto", LangType::Protobuf, [10, 7, 1, 2, 1, 2]),
+    ("python/sample.py", LangType::Python, [13, 6, 2, 5, 2, 0]),
+    ("qcl/sample.qcl", LangType::Qcl, [6, 3, 2, 1, 0, 0]),
+    ("qsharp/sample.qs", LangType::Qsharp, [6, 5, 1, 0, 1, 0]),
+    ("r/sample.r", LangType::R, [6, 4, 1, 1, 1, 0]),
+    ("regex/sample.regex", LangType::Regex, [3, 2, 0, 1, 0, 0]),
+    ("ruby/sample.rb", LangType::Ruby, [10, 5, 4, 1, 1, 1]),
+    ("rust/sample.rs", LangType::Rust, [20, 13, 3, 4, 2, 2]),
+    ("sass/sample.scss", LangType::Sass, [7, 4, 2, 1, 0, 0]),
+    ("scala/sample.scala", LangType::Scala, [7, 5, 1, 1, 2, 1]),
+    ("shell/sample.sh", LangType::Shell, [8, 4, 2, 2, 1, 0]),
+    ("sql/sample.sql", LangType::Sql, [8, 5, 2, 1, 0, 1]),
+    ("swift/sample.swift", LangType::Swift, [9, 7, 1, 1, 1, 1]),
+    ("tcl/sample.tcl", LangType::Tcl, [6, 4, 1, 1, 1, 0]),
+    ("tex/sample.tex", LangType::Tex, [6, 4, 1, 1, 0, 0]),
+    ("thrift/sample.thrift", LangType::Thrift, [11, 8, 1, 2, 1, 2]),
+    ("text/sample.txt", LangType::Text, [3, 2, 0, 1, 0, 0]),
+    ("toml/sample.toml", LangType::Toml, [5, 3, 1, 1, 0, 0]),
+    ("typescript/sample.ts", LangType::Typescript, [9, 7, 1, 1, 1, 1]),
+    ("v/sample.v", LangType::V, [6, 4, 1, 1, 1, 0]),
+    ("vue/Sample.vue", LangType::Vue, [8, 6, 1, 1, 1, 0]),
+    ("wenyan/sample.wy", LangType::WenYan, [4, 1, 2, 1, 1, 0]),
+    ("xml/sample.xml", LangType::Xml, [6, 4, 1, 1, 0, 0]),
+    ("yaml/sample.yaml", LangType::Yaml, [5, 3, 1, 1, 0, 0]),
+    ("zig/sample.zig", LangType::Zig, [6, 4, 1, 1, 1, 1]),
+];
+
+#[test]
+fn fixtures_match_expected_counts() {
+    let counter = Counter::new(Config::new());
+    let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+
+    let mut mismatches = Vec::new();
+    for &(file, lang, expected) in FIXTURES {
+        let stat = counter.count(root.join(file)).unwrap();
+        let actual = [stat.lines, stat.code, stat.comments, stat.blanks, stat.functions, stat.classes];
+        if stat.lang != lang || actual != expected {
+            mismatches.push(format!("{}: expected {:?} {:?}, got {:?} {:?}", file, lang, expected, stat.lang, actual));
+        }
+        if lang != LangType::Markdown {
+            assert_eq!(stat.code + stat.comments + stat.blanks, stat.lines, "{}", file);
+        }
+    }
+    assert!(mismatches.is_empty(), "fixture mismatches:\n{}", mismatches.join("\n"));
+}
+
+#[test]
+fn every_language_has_a_fixture() {
+    let covered: HashSet<LangType> = FIXTURES.iter().map(|&(_, lang, _)| lang).collect();
+    let mut missing: Vec<String> = LANGUAGE_DEFINITIONS
+        .keys()
+        .filter(|lang| !covered.contains(lang))
+        .map(|lang| lang.to_string())
+        .collect();
+    missing.sort();
+    assert!(missing.is_empty(), "languages without a fixture: {:?}", missing);
+}
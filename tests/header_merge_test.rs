@@ -0,0 +1,23 @@
+mod common;
+
+use toukei::fc::FileCounter;
+use toukei::langs::lang_type::LangType;
+use toukei::langs::registry::get_type_from_ext;
+
+#[test]
+fn test_headers_count_as_their_language() {
+    assert_eq!(get_type_from_ext("h"), Some(LangType::C));
+    assert_eq!(get_type_from_ext("hpp"), Some(LangType::Cpp));
+
+    let (_dir, config) = common::fixture([
+        ("util.c", "#include \"util.h\"\nint one(void) {\n    return 1;\n}\n"),
+        ("util.h", "int one(void);\n"),
+        ("shape.cpp", "#include \"shape.hpp\"\ndouble Shape::area() const {\n    return 0.0;\n}\n"),
+        ("shape.hpp", "class Shape {\npublic:\n    double area() const;\n};\n"),
+    ]);
+    let report = FileCounter::new(config).process().unwrap();
+
+    assert_eq!(report.language_count(), 2);
+    assert_eq!(report.get_by_lang(&LangType::C).unwrap().files, 2);
+    assert_eq!(report.get_by_lang(&LangType::Cpp).unwrap().files, 2);
+}
@@ -0,0 +1,129 @@
+use std::fs;
+use std::path::Path;
+
+use git2::{Repository, Signature, Time};
+use toukei::config::Config;
+use toukei::history::collect_history;
+use toukei::langs::lang_type::LangType;
+
+fn commit_all(repo: &Repository, message: &str, seconds: i64) {
+    let mut index = repo.index().unwrap();
+    index.add_all(["*"], git2::IndexAddOption::DEFAULT, None).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+
+    let sig = Signature::new("tester", "tester@example.com", &Time::new(seconds, 0)).unwrap();
+    let parents: Vec<_> = repo.head().ok()
+        .and_then(|h| h.peel_to_commit().ok())
+        .into_iter()
+        .collect();
+    let parent_refs: Vec<_> = parents.iter().collect();
+    repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parent_refs).unwrap();
+}
+
+fn write(root: &Path, name: &str, content: &str) {
+    fs::write(root.join(name), content).unwrap();
+}
+
+#[test]
+fn test_history_two_commits() {
+    let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+    let root = dir.path();
+    let repo = Repository::init(root).unwrap();
+
+    write(root, "main.rs", "fn main() {\n    // hi\n}\n");
+    commit_all(&repo, "first", 1_700_000_000);
+
+    write(root, "main.rs", "fn main() {\n    run();\n}\n\nfn run() {}\n");
+    write(root, "app.py", "print('hi')\n");
+    commit_all(&repo, "second", 1_700_086_400);
+
+    let points = collect_history(&[root], 10, &Config::new()).unwrap();
+
+    // 按时间从旧到新
+    assert_eq!(points.len(), 3);
+    assert_eq!(points[0].date, "2023-11-14");
+    assert_eq!((points[0].language, points[0].lines), (LangType::Rust, 2));
+
+    let second: Vec<_> = points[1..].iter().map(|p| (p.language, p.lines)).collect();
+    assert!(second.contains(&(LangType::Rust, 4)));
+    assert!(second.contains(&(LangType::Python, 1)));
+    assert_eq!(points[1].date, "2023-11-15");
+    assert_eq!(points[1].commit, points[2].commit);
+    assert_ne!(points[0].commit, points[1].commit);
+
+    // 只取最近一个提交
+    let latest = collect_history(&[root], 1, &Config::new()).unwrap();
+    assert_eq!(latest.len(), 2);
+}
+
+#[test]
+fn test_history_only_counts_given_subtree() {
+    let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+    let root = dir.path();
+    let repo = Repository::init(root).unwrap();
+
+    fs::create_dir(root.join("src")).unwrap();
+    fs::create_dir(root.join("scripts")).unwrap();
+    write(root, "main.rs", "fn main() {}\n");
+    write(&root.join("src"), "lib.rs", "pub fn a() {}\npub fn b() {}\n");
+    write(&root.join("scripts"), "run.py", "print('hi')\n");
+    commit_all(&repo, "first", 1_700_000_000);
+
+    let points = collect_history(&[root.join("src")], 10, &Config::new()).unwrap();
+    let lines: Vec<_> = points.iter().map(|p| (p.language, p.lines)).collect();
+    assert_eq!(lines, vec![(LangType::Rust, 2)]);
+
+    // 多个路径合并为同一序列，每个提交每种语言只有一行
+    let points = collect_history(&[root.join("src"), root.join("scripts")], 10, &Config::new()).unwrap();
+    let mut lines: Vec<_> = points.iter().map(|p| (p.language, p.lines)).collect();
+    lines.sort_by_key(|(lang, _)| lang.to_string());
+    assert_eq!(lines, vec![(LangType::Python, 1), (LangType::Rust, 2)]);
+
+    // 重叠的路径不会重复统计
+    let points = collect_history(&[root.to_path_buf(), root.join("src")], 10, &Config::new()).unwrap();
+    let rust: usize = points.iter().filter(|p| p.language == LangType::Rust).map(|p| p.lines).sum();
+    assert_eq!(rust, 3);
+}
+
+#[test]
+fn test_history_path_missing_in_older_commit() {
+    let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+    let root = dir.path();
+    let repo = Repository::init(root).unwrap();
+
+    write(root, "main.rs", "fn main() {}\n");
+    commit_all(&repo, "first", 1_700_000_000);
+
+    fs::create_dir(root.join("src")).unwrap();
+    write(&root.join("src"), "lib.rs", "pub fn a() {}\n");
+    commit_all(&repo, "second", 1_700_086_400);
+
+    let points = collect_history(&[root.join("src")], 10, &Config::new()).unwrap();
+    assert_eq!(points.len(), 1);
+    assert_eq!(points[0].date, "2023-11-15");
+}
+
+#[test]
+fn test_history_detects_ambiguous_extensions_and_filters_types() {
+    let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+    let root = dir.path();
+    let repo = Repository::init(root).unwrap();
+
+    write(root, "foo.h", "#import <Foundation/Foundation.h>\n@interface Foo : NSObject\n@end\n");
+    write(root, "plain.h", "int add(int a, int b);\n");
+    fs::write(root.join("data.rs"), b"fn main() {}\0\x01\x02\n").unwrap();
+    commit_all(&repo, "first", 1_700_000_000);
+
+    // 与目录扫描一致：.h 按内容识别，二进制内容被跳过
+    let points = collect_history(&[root], 10, &Config::new()).unwrap();
+    let langs: Vec<_> = points.iter().map(|p| (p.language, p.lines)).collect();
+    assert!(langs.contains(&(LangType::ObjectiveC, 3)));
+    assert!(langs.contains(&(LangType::C, 1)));
+    assert!(!langs.iter().any(|(lang, _)| *lang == LangType::Rust));
+
+    let config = Config::new().with_types(vec!["objectivec".to_string()]);
+    let points = collect_history(&[root], 10, &config).unwrap();
+    assert_eq!(points.len(), 1);
+    assert_eq!((points[0].language, points[0].lines), (LangType::ObjectiveC, 3));
+}
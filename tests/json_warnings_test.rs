@@ -0,0 +1,62 @@
+mod common;
+
+use toukei::fc::{AsyncFileCounter, FileCounter};
+use toukei::report::{SkipReason, SkipWarning};
+use toukei::utils::save::report_to_json;
+
+#[test]
+fn skipped_binary_file_is_listed_in_json_warnings() {
+    let (dir, config) = common::fixture([
+        ("main.rs", &b"fn main() {}\n"[..]),
+        ("blob.rs", &b"\x00\x01\x02binary"[..]),
+    ]);
+    let blob = dir.path().join("blob.rs").display().to_string();
+
+    let report = FileCounter::new(config.clone()).process().unwrap();
+    assert_eq!(report.file_count(), 1);
+    assert_eq!(report.warnings, vec![SkipWarning { path: blob.clone(), reason: SkipReason::Binary }]);
+
+    let json: serde_json::Value = serde_json::from_str(&report_to_json(&report).unwrap()).unwrap();
+    assert_eq!(json["warnings"], serde_json::json!([{ "path": blob, "reason": "binary" }]));
+
+    // 异步统计器记录相同的警告
+    let report = AsyncFileCounter::new(config).process_blocking().unwrap();
+    assert_eq!(report.warnings.len(), 1);
+}
+
+#[test]
+fn invalid_utf8_is_reported_as_decode_error() {
+    let (_dir, config) = common::fixture([("bad.rs", b"fn main() {}\n// \xff\xfe\n")]);
+
+    let report = FileCounter::new(config.enable_strict_utf8(true)).process().unwrap();
+
+    assert!(report.is_empty());
+    assert_eq!(report.warnings.len(), 1);
+    assert_eq!(report.warnings[0].reason, SkipReason::DecodeError);
+}
+
+#[cfg(unix)]
+#[test]
+fn async_scan_records_unreadable_files_as_warnings() {
+    let (dir, config) = common::fixture([
+        ("main.rs", &b"fn main() {}\n"[..]),
+        ("blob.rs", &b"\x00\x01\x02binary"[..]),
+    ]);
+    let root = dir.path();
+    // 指向不存在文件的符号链接，打开时出错
+    std::os::unix::fs::symlink(root.join("missing.rs"), root.join("gone.rs")).unwrap();
+    let gone = root.join("gone.rs").display().to_string();
+
+    let report = AsyncFileCounter::new(config).process_blocking().unwrap();
+    assert_eq!(report.file_count(), 1);
+
+    let reasons: Vec<_> = report.warnings.iter().map(|w| (w.path.as_str(), &w.reason)).collect();
+    assert_eq!(reasons.len(), 2);
+    assert!(reasons.contains(&(root.join("blob.rs").display().to_string().as_str(), &SkipReason::Binary)));
+    let (_, other) = reasons.iter().find(|(path, _)| *path == gone).unwrap();
+    assert!(matches!(other, SkipReason::Other(msg) if msg.starts_with("IO Error")));
+
+    let json: serde_json::Value = serde_json::from_str(&report_to_json(&report).unwrap()).unwrap();
+    let warning = json["warnings"].as_array().unwrap().iter().find(|w| w["path"] == gone.as_str()).unwrap();
+    assert!(warning["reason"]["other"].is_string());
+}
@@ -0,0 +1,36 @@
+mod common;
+
+use toukei::config::Config;
+use toukei::fc::{AsyncFileCounter, FileCounter};
+use toukei::langs::lang_type::LangType;
+
+fn fixture() -> (tempfile::TempDir, Config) {
+    common::fixture([
+        ("pkg/__init__.py", ""),
+        ("pkg/license.py", "# Copyright (c) toukei\n# MIT License\n"),
+        ("pkg/app.py", "def main():\n    return 1\n"),
+    ])
+}
+
+#[test]
+fn min_code_lines_skips_empty_and_comment_only_files() {
+    let (_dir, config) = fixture();
+
+    let report = FileCounter::new(config.clone()).process().unwrap();
+    assert_eq!(report.get_by_lang(&LangType::Python).unwrap().files, 3);
+
+    let report = FileCounter::new(config.with_min_code_lines(1)).process().unwrap();
+    let python = report.get_by_lang(&LangType::Python).unwrap();
+    assert_eq!(python.files, 1);
+    assert_eq!(python.code, 2);
+    assert_eq!(python.comments, 0);
+    assert!(python.stats[0].path.ends_with("app.py"));
+}
+
+#[tokio::test]
+async fn min_code_lines_applies_to_async_counter() {
+    let (_dir, config) = fixture();
+
+    let report = AsyncFileCounter::new(config.with_min_code_lines(1)).process().await.unwrap();
+    assert_eq!(report.get_by_lang(&LangType::Python).unwrap().files, 1);
+}
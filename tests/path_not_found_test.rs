@@ -0,0 +1,27 @@
+mod common;
+
+use toukei::config::Config;
+use toukei::fc::{AsyncFileCounter, FileCounter, ProcessError};
+
+#[test]
+fn test_missing_path_is_an_error() {
+    let (dir, mut config) = common::fixture([("main.rs", "fn main() {}\n")]);
+    let missing = dir.path().join("scr").display().to_string();
+    config.paths.push(missing.clone());
+
+    let err = FileCounter::new(config.clone()).process().unwrap_err();
+    assert_eq!(err, ProcessError::PathNotFound(missing.clone()));
+    assert_eq!(err.to_string(), format!("Path not found: {}", missing));
+
+    let err = AsyncFileCounter::new(config).process_blocking().unwrap_err();
+    assert_eq!(err, ProcessError::PathNotFound(missing));
+}
+
+#[test]
+fn test_glob_without_matches_is_not_an_error() {
+    let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+    let pattern = format!("{}/**/*.rs", dir.path().display());
+
+    let report = FileCounter::new(Config::new().with_paths(vec![pattern])).process().unwrap();
+    assert!(report.is_empty());
+}
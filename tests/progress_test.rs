@@ -0,0 +1,22 @@
+mod common;
+
+use toukei::fc::FileCounter;
+
+#[test]
+fn test_progress_reaches_file_count() {
+    let files = (0..4).flat_map(|d| (0..25).map(move |i| format!("pkg{}/f{}.rs", d, i)));
+    let (_dir, config) = common::fixture(files.map(|name| (name, "fn main() {\n    let x = 1;\n}\n")));
+
+    let mut calls = Vec::new();
+    let report = FileCounter::new(config)
+        .process_with_progress(|done, total| calls.push((done, total)))
+        .unwrap();
+
+    assert_eq!(report.file_count(), 100);
+    // 回调定期调用而非每个文件一次，最后一次为最终数目
+    assert!(!calls.is_empty() && calls.len() <= 100);
+    assert_eq!(calls.last(), Some(&(100, 100)));
+    // 已完成数单调递增且不超过已发现数
+    assert!(calls.windows(2).all(|w| w[0].0 <= w[1].0));
+    assert!(calls.iter().all(|&(done, total)| done <= total));
+}
@@ -0,0 +1,22 @@
+mod common;
+
+use std::process::Command;
+
+#[test]
+fn test_quiet_suppresses_no_files_hint() {
+    let (dir, _) = common::fixture([("data.unknownext", "nothing to count\n")]);
+    let path = dir.path().display().to_string();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_toukei"))
+        .args(["--path", &path])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No files found in:"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_toukei"))
+        .args(["--path", &path, "--quiet"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use toukei::config::Config;
+use toukei::fc::{AsyncFileCounter, FileCounter};
+
+fn fixture() -> (tempfile::TempDir, Config) {
+    common::fixture([
+        ("main.rs", "// entry\nfn main() {\n\n    println!(\"hi\");\n}\n"),
+        ("app.py", "# app\nprint(1)\n"),
+    ])
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn process_blocking_inside_multi_thread_runtime() {
+    let (_dir, config) = fixture();
+    let expected = FileCounter::new(config.clone()).process().unwrap();
+
+    let report = AsyncFileCounter::new(config).process_blocking().unwrap();
+    assert_eq!(report.file_count(), 2);
+    for (lang, stat) in &expected.inner {
+        assert_eq!(report.get_by_lang(lang).map(|s| (s.lines, s.code)), Some((stat.lines, stat.code)));
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn process_blocking_inside_current_thread_runtime() {
+    let (_dir, config) = fixture();
+
+    let report = AsyncFileCounter::new(config).process_blocking().unwrap();
+    assert_eq!(report.file_count(), 2);
+}
+
+#[test]
+fn process_blocking_without_runtime() {
+    let (_dir, config) = fixture();
+
+    let report = AsyncFileCounter::new(config).process_blocking().unwrap();
+    assert_eq!(report.file_count(), 2);
+}
+
+#[test]
+fn process_on_provided_handle() {
+    let (_dir, config) = fixture();
+    let rt = tokio::runtime::Runtime::new().unwrap();
+
+    let report = AsyncFileCounter::new(config.clone()).process_on(rt.handle()).unwrap();
+    assert_eq!(report.file_count(), 2);
+
+    // 在该运行时的任务内部同样可用
+    let handle = rt.handle().clone();
+    let report = rt.block_on(async move {
+        tokio::spawn(async move { AsyncFileCounter::new(config).process_on(&handle) })
+            .await
+            .unwrap()
+    }).unwrap();
+    assert_eq!(report.file_count(), 2);
+}
@@ -2,5 +2,224 @@
 fn test_file_saver() {
     use toukei::saver::FileSaver;
 
-    let mut saver = FileSaver::new();
-}
\ No newline at end of file
+    let _saver = FileSaver::new();
+}
+
+#[test]
+fn test_save_multiple_formats() {
+    use toukei::langs::lang_type::LangType;
+    use toukei::report::Report;
+    use toukei::saver::FileSaver;
+    use toukei::stats::FileStat;
+    use toukei::utils::format::OutputFormat;
+
+    let mut report = Report::new();
+    let mut stat = FileStat::new(LangType::Rust, "main.rs".to_string(), "main.rs".to_string());
+    stat.lines = 10;
+    stat.code = 8;
+    stat.blanks = 2;
+    report.add(stat);
+
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path().join("stats");
+    let written = FileSaver::save_reports(&report, &base, &[OutputFormat::Json, OutputFormat::Csv]).unwrap();
+
+    let json = dir.path().join("stats.json");
+    let csv = dir.path().join("stats.csv");
+    assert_eq!(written, vec![json.clone(), csv.clone()]);
+    assert!(std::fs::read_to_string(json).unwrap().contains("\"languages\""));
+    assert!(std::fs::read_to_string(csv).unwrap().starts_with("Language,"));
+}
+
+#[test]
+fn test_exporter_sort_and_limit() {
+    use toukei::langs::lang_type::LangType;
+    use toukei::report::{Report, SortKey};
+    use toukei::saver::{CsvExporter, JsonExporter, ReportExporter};
+    use toukei::stats::FileStat;
+
+    let mut report = Report::new();
+    for (lang, path, lines, code, comments) in [
+        (LangType::Rust, "a.rs", 100, 50, 40),
+        (LangType::Python, "b.py", 80, 70, 5),
+        (LangType::Go, "c.go", 20, 15, 2),
+    ] {
+        report.add(FileStat { lang, path: path.to_string(), lines, code, comments, ..FileStat::default() });
+    }
+
+    let csv_rows = |exporter: CsvExporter| {
+        let mut out = Vec::new();
+        exporter.export(&report, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+            .lines()
+            .skip(1)
+            .take_while(|line| !line.starts_with(','))
+            .map(|line| line.split(',').next().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(csv_rows(CsvExporter::new()), ["Rust", "Python", "Go"]);
+    assert_eq!(csv_rows(CsvExporter::new().with_sort(SortKey::Code)), ["Python", "Rust", "Go"]);
+    assert_eq!(csv_rows(CsvExporter::new().with_sort(SortKey::Name)), ["Go", "Python", "Rust"]);
+    assert_eq!(csv_rows(CsvExporter::new().with_sort(SortKey::Comments).with_limit(Some(2))), ["Rust", "Python"]);
+
+    let mut out = Vec::new();
+    JsonExporter::new().with_sort(SortKey::Code).with_limit(Some(1)).export(&report, &mut out).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    let languages = json["languages"].as_array().unwrap();
+    assert_eq!(languages.len(), 1);
+    assert_eq!(languages[0]["language"], "Python");
+    // 总计不受 limit 影响
+    assert_eq!(json["total"]["lines"], 200);
+}
+
+#[test]
+fn test_exporters_follow_report_sort() {
+    use toukei::langs::lang_type::LangType;
+    use toukei::report::{Report, SortKey, SortOrder};
+    use toukei::saver::{CsvExporter, JsonExporter, ReportExporter};
+    use toukei::stats::FileStat;
+
+    let mut report = Report::new();
+    for (lang, path, lines) in [
+        (LangType::Rust, "a.rs", 100),
+        (LangType::Python, "b.py", 80),
+        (LangType::Go, "c.go", 20),
+    ] {
+        report.add(FileStat { lang, path: path.to_string(), lines, ..FileStat::default() });
+    }
+    let report = report.with_sort(SortKey::Name, Some(SortOrder::Desc));
+
+    let mut out = Vec::new();
+    CsvExporter::new().export(&report, &mut out).unwrap();
+    let rows: Vec<String> = String::from_utf8(out).unwrap()
+        .lines()
+        .skip(1)
+        .take(3)
+        .map(|line| line.split(',').next().unwrap().to_string())
+        .collect();
+    assert_eq!(rows, ["Rust", "Python", "Go"]);
+
+    let mut out = Vec::new();
+    JsonExporter::new().export(&report, &mut out).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    let names: Vec<&str> = json["languages"].as_array().unwrap()
+        .iter()
+        .map(|row| row["language"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["Rust", "Python", "Go"]);
+
+    // 导出器自己指定的排序优先
+    let mut out = Vec::new();
+    JsonExporter::new().with_sort(SortKey::Lines).with_order(SortOrder::Asc).export(&report, &mut out).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(json["languages"][0]["language"], "Go");
+}
+
+#[test]
+fn test_jsonl_exporter() {
+    use toukei::langs::lang_type::LangType;
+    use toukei::report::Report;
+    use toukei::saver::{JsonlExporter, ReportExporter};
+    use toukei::stats::FileStat;
+
+    let mut report = Report::new();
+    for (lang, path, lines, code) in [
+        (LangType::Rust, "a.rs", 100, 80),
+        (LangType::Python, "b.py", 40, 30),
+    ] {
+        report.add(FileStat { lang, path: path.to_string(), lines, code, ..FileStat::default() });
+    }
+
+    let mut out = Vec::new();
+    JsonlExporter::new().export(&report, &mut out).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    let rows: Vec<serde_json::Value> = text.lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0]["language"], "Rust");
+    assert_eq!(rows[0]["lines"], 100);
+    assert_eq!(rows[1]["language"], "Python");
+    assert!(rows[0].get("total").is_none());
+    assert_eq!(rows[2]["total"], true);
+    assert_eq!(rows[2]["lines"], 140);
+    assert_eq!(rows[2]["code"], 110);
+}
+
+#[test]
+fn test_json_map_is_keyed_by_language() {
+    use toukei::langs::lang_type::LangType;
+    use toukei::report::Report;
+    use toukei::saver::exporter::JSON_MAP_TOTAL_KEY;
+    use toukei::stats::FileStat;
+    use toukei::utils::format::OutputFormat;
+    use toukei::utils::save::export_report;
+
+    let mut report = Report::new();
+    for (lang, path, lines) in [(LangType::Rust, "a.rs", 30), (LangType::Rust, "b.rs", 10), (LangType::Python, "c.py", 5)] {
+        report.add(FileStat { lang, path: path.to_string(), lines, code: lines, ..FileStat::default() });
+    }
+
+    let mut out = Vec::new();
+    export_report(&report, &mut out, "json-map".parse::<OutputFormat>().unwrap()).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+
+    let keys: Vec<&str> = json.as_object().unwrap().keys().map(String::as_str).collect();
+    assert_eq!(keys, ["Python", "Rust", JSON_MAP_TOTAL_KEY]);
+    assert_eq!(json["Rust"]["files"], 2);
+    assert_eq!(json["Rust"]["lines"], 40);
+    assert_eq!(json[JSON_MAP_TOTAL_KEY]["lines"], 45);
+}
+
+#[tokio::test]
+async fn test_save_report_async() {
+    use toukei::langs::lang_type::LangType;
+    use toukei::report::Report;
+    use toukei::saver::FileSaver;
+    use toukei::stats::FileStat;
+    use toukei::utils::format::OutputFormat;
+
+    let mut report = Report::new();
+    let mut stat = FileStat::new(LangType::Rust, "main.rs".to_string(), "main.rs".to_string());
+    stat.lines = 10;
+    stat.code = 8;
+    stat.blanks = 2;
+    report.add(stat);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("stats.json");
+    FileSaver::save_report_async(&report, &path, OutputFormat::Json).await.unwrap();
+
+    let sync_path = dir.path().join("sync.json");
+    FileSaver::save_report(&report, &sync_path, OutputFormat::Json).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), std::fs::read_to_string(&sync_path).unwrap());
+
+    let ci = dir.path().join("ci.txt");
+    FileSaver::save_report_async(&report, &ci, OutputFormat::Ci).await.unwrap();
+    assert_eq!(std::fs::read_to_string(ci).unwrap(), "Rust=10\n");
+}
+
+#[test]
+fn test_save_ci_format() {
+    use toukei::langs::lang_type::LangType;
+    use toukei::report::Report;
+    use toukei::saver::FileSaver;
+    use toukei::stats::FileStat;
+    use toukei::utils::format::OutputFormat;
+
+    let mut report = Report::new();
+    for (lang, name, lines) in [(LangType::Rust, "main.rs", 10), (LangType::Go, "main.go", 4)] {
+        let mut stat = FileStat::new(lang, name.to_string(), name.to_string());
+        stat.lines = lines;
+        stat.code = lines;
+        report.add(stat);
+    }
+
+    let dir = tempfile::Builder::new().prefix("toukei").tempdir().unwrap();
+    let base = dir.path().join("out");
+    let written = FileSaver::save_reports(&report, &base, &[OutputFormat::Ci]).unwrap();
+
+    assert_eq!(written, vec![dir.path().join("out.txt")]);
+    assert_eq!(std::fs::read_to_string(&written[0]).unwrap(), "Go=4\nRust=10\n");
+}
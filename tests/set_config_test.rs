@@ -0,0 +1,48 @@
+mod common;
+
+use toukei::config::Config;
+use toukei::counter::Counter;
+use toukei::fc::{AsyncFileCounter, FileCounter};
+use toukei::langs::lang_type::LangType;
+
+fn tree() -> (tempfile::TempDir, Config) {
+    common::fixture([
+        ("main.rs", "fn main() {}\n"),
+        ("app.py", "print(1)\nprint(2)\n"),
+        ("lib.inc", "<?php\necho 1;\n"),
+    ])
+}
+
+#[test]
+fn test_counter_set_config() {
+    let (dir, _) = tree();
+    let file = dir.path().join("lib.inc");
+
+    let mut counter = Counter::new(Config::new());
+    assert!(counter.count(&file).is_err());
+
+    counter.set_config(Config::new().with_ext_override("inc", LangType::Php));
+    let stat = counter.count(&file).unwrap();
+    assert_eq!(stat.lang, LangType::Php);
+    assert_eq!(stat.code, 2);
+}
+
+#[test]
+fn test_file_counters_set_config() {
+    let (_dir, all) = tree();
+    let rust_only = all.clone().with_types(vec!["rust".to_string()]);
+
+    let mut sync = FileCounter::new(all.clone());
+    assert_eq!(sync.process().unwrap().language_count(), 2);
+    sync.set_config(rust_only.clone());
+    let report = sync.process().unwrap();
+    assert_eq!(report.language_count(), 1);
+    assert!(report.get_by_lang(&LangType::Rust).is_some());
+
+    let mut async_counter = AsyncFileCounter::new(all);
+    assert_eq!(async_counter.process_blocking().unwrap().language_count(), 2);
+    async_counter.set_config(rust_only);
+    let report = async_counter.process_blocking().unwrap();
+    assert_eq!(report.language_count(), 1);
+    assert!(report.get_by_lang(&LangType::Rust).is_some());
+}
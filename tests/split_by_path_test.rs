@@ -0,0 +1,31 @@
+mod common;
+
+use toukei::fc::FileCounter;
+use toukei::saver::JsonExporter;
+
+#[test]
+fn test_split_by_path_json() {
+    let (dir, config) = common::fixture([
+        ("frontend/app.js", "const a = 1;\nconst b = 2;\n"),
+        ("backend/main.rs", "fn main() {\n    // entry\n    run();\n}\n"),
+        ("backend/lib.rs", "pub fn run() {}\n"),
+    ]);
+
+    let paths: Vec<String> = ["frontend", "backend"].iter().map(|d| dir.path().join(d).display().to_string()).collect();
+    let config = config.with_paths(paths.clone()).enable_split_by_path(true);
+    let multi = FileCounter::new(config).process_per_path().unwrap();
+
+    let mut out = Vec::new();
+    JsonExporter::new().export_multi(&multi, &mut out).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+
+    let front = &json["paths"][&paths[0]];
+    let back = &json["paths"][&paths[1]];
+    assert_eq!(front["total"]["files"], 1);
+    assert_eq!(front["total"]["code"], 2);
+    assert_eq!(back["total"]["files"], 2);
+    assert_eq!(back["total"]["lines"], 5);
+    assert_eq!(back["total"]["comments"], 1);
+    assert_eq!(json["total"]["files"], 3);
+    assert_eq!(json["total"]["lines"], 7);
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use toukei::config::Config;
+use toukei::counter::Counter;
+use toukei::fc::FileCounter;
+use toukei::report::Report;
+use toukei::walker::FileReader;
+
+/// 多层目录、多种语言，外加应被过滤掉的 vendor 目录与隐藏目录
+fn large_tree() -> (tempfile::TempDir, Config) {
+    let mut files = Vec::new();
+    for d in 0..20 {
+        let sub = format!("pkg{}/mod{}", d % 5, d);
+        for i in 0..50 {
+            let (name, body) = match i % 4 {
+                0 => (format!("f{}.rs", i), "fn main() {\n    // c\n\n    let x = 1;\n}\n".repeat(i % 7 + 1)),
+                1 => (format!("f{}.py", i), "def f():\n    # c\n    return 1\n".repeat(i % 5 + 1)),
+                2 => (format!("f{}.c", i), "/* c */\nint main() {\n    return 0;\n}\n".to_string()),
+                _ => (format!("f{}.txt", i), "plain text\n".to_string()),
+            };
+            files.push((format!("{}/{}", sub, name), body));
+        }
+    }
+    for skipped in ["node_modules", ".hidden"] {
+        files.push((format!("{}/skip.rs", skipped), "fn skipped() {}\n".to_string()));
+    }
+    common::fixture(files)
+}
+
+/// 先收集完整的文件列表再逐个计数的参考实现
+fn collect_then_count(config: &Config) -> Report {
+    let reader = FileReader::new(config.clone());
+    let counter = Counter::new(config.clone());
+    let mut report = Report::new();
+    for path in &config.paths {
+        for file in reader.walk_dir(path).unwrap() {
+            report.add(counter.count(&file).unwrap());
+        }
+    }
+    report
+}
+
+fn sorted_paths(report: &Report) -> Vec<String> {
+    let mut paths: Vec<String> = report.into_iter()
+        .flat_map(|(_, stat)| stat.stats.iter().map(|s| s.path.clone()))
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn streaming_count_matches_collect_then_count() {
+    let (_dir, config) = large_tree();
+
+    let streamed = FileCounter::new(config.clone()).process().unwrap();
+    let expected = collect_then_count(&config);
+
+    assert_eq!(streamed.file_count(), 20 * 50);
+    assert_eq!(streamed.file_count(), expected.file_count());
+    assert!(streamed.delta(&expected).is_empty());
+    assert_eq!(sorted_paths(&streamed), sorted_paths(&expected));
+}
@@ -0,0 +1,24 @@
+mod common;
+
+use toukei::fc::{AsyncFileCounter, FileCounter};
+use toukei::langs::lang_type::LangType;
+
+#[test]
+fn strict_utf8_skips_invalid_files() {
+    let (_dir, config) = common::fixture([
+        ("good.rs", &b"fn good() {}\n"[..]),
+        ("bad.rs", &b"// \xff\xfe\nfn bad() {}\n"[..]),
+    ]);
+
+    let lenient = FileCounter::new(config.clone()).process().unwrap();
+    assert_eq!(lenient.get_by_lang(&LangType::Rust).unwrap().files, 2);
+
+    let strict = FileCounter::new(config.clone().enable_strict_utf8(true)).process().unwrap();
+    let rust = strict.get_by_lang(&LangType::Rust).unwrap();
+    assert_eq!(rust.files, 1);
+    assert!(rust.stats[0].path.ends_with("good.rs"));
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let strict = rt.block_on(AsyncFileCounter::new(config.enable_strict_utf8(true)).process()).unwrap();
+    assert_eq!(strict.get_by_lang(&LangType::Rust).unwrap().files, 1);
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use toukei::fc::FileCounter;
+use toukei::langs::lang_type::LangType;
+
+#[test]
+fn test_split_tests_from_src() {
+    let (_dir, config) = common::fixture([
+        ("src/lib.rs", "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n"),
+        ("src/util.rs", "// helper\npub fn one() -> i32 {\n    1\n}\n"),
+        ("tests/it.rs", "#[test]\nfn it_adds() {\n    assert_eq!(2, 1 + 1);\n}\n\n"),
+    ]);
+
+    let report = FileCounter::new(config.enable_split_tests(true)).process().unwrap();
+
+    let rust = report.get_by_lang(&LangType::Rust).unwrap();
+    let split = rust.test_split.as_ref().unwrap();
+
+    assert_eq!(split.code.files, 2);
+    assert_eq!(split.code.code, 6);
+    assert_eq!(split.test.files, 1);
+    assert_eq!(split.test.code, 4);
+    assert_eq!(split.test.blanks, 1);
+    assert_eq!(split.code.files + split.test.files, rust.files);
+}
+
+#[test]
+fn test_split_disabled_by_default() {
+    let (_dir, config) = common::fixture([("main.rs", "fn main() {}\n")]);
+
+    let report = FileCounter::new(config).process().unwrap();
+
+    assert!(report.get_by_lang(&LangType::Rust).unwrap().test_split.is_none());
+}
+
+#[test]
+fn test_split_matches_paths_relative_to_scan_root() {
+    // 项目本身位于名为 tests 的目录下，只有项目内的 tests 目录算测试代码
+    let (dir, config) = common::fixture([
+        ("tests/proj/src/lib.rs", "pub fn one() -> i32 {\n    1\n}\n"),
+        ("tests/proj/tests/it.rs", "#[test]\nfn it_works() {}\n"),
+    ]);
+
+    let root = dir.path().join("tests/proj");
+    let config = config
+        .with_paths(vec![root.display().to_string()])
+        .enable_split_tests(true);
+    let report = FileCounter::new(config).process().unwrap();
+    let split = report.get_by_lang(&LangType::Rust).unwrap().test_split.clone().unwrap();
+
+    assert_eq!(split.code.files, 1);
+    assert_eq!(split.test.files, 1);
+}
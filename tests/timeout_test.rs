@@ -0,0 +1,93 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use toukei::config::Config;
+use toukei::counter::Counter;
+use toukei::fc::{AsyncFileCounter, FileCounter, ProcessError};
+use toukei::langs::lang_type::LangType;
+use toukei::syntax::lex_status::LineCtx;
+use toukei::syntax::{register_classifier, Classifier, LineClassification, LineKind, LineSpan, SpanKind};
+
+/// 远小于单个慢文件统计耗时的超时
+const SHORT_TIMEOUT: Duration = Duration::from_millis(10);
+
+/// 每个文件 100 行，用慢分类器统计至少需要 100 ms，扫描一定在 `SHORT_TIMEOUT` 到期时还在进行
+fn slow_tree() -> (tempfile::TempDir, Config) {
+    register_classifier(LangType::Tcl, SlowClassifier { classified: None });
+    common::fixture((0..50).map(|i| (format!("f{}.tcl", i), "set x 1\n".repeat(100))))
+}
+
+#[test]
+fn test_sync_scan_times_out() {
+    let (_dir, config) = slow_tree();
+    let config = config.with_timeout(SHORT_TIMEOUT);
+
+    let err = FileCounter::new(config).process().unwrap_err();
+    assert_eq!(err, ProcessError::TimedOut(SHORT_TIMEOUT));
+    assert!(err.to_string().contains("timed out"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_async_scan_times_out() {
+    let (_dir, config) = slow_tree();
+    let config = config.with_timeout(SHORT_TIMEOUT);
+
+    let err = AsyncFileCounter::new(config).process().await.unwrap_err();
+    assert_eq!(err, ProcessError::TimedOut(SHORT_TIMEOUT));
+}
+
+#[test]
+fn test_generous_timeout_completes() {
+    let (_dir, config) = common::fixture([("main.rs", "fn main() {}\n")]);
+    let config = config.with_timeout(Duration::from_secs(60));
+
+    let report = FileCounter::new(config).process().unwrap();
+    assert_eq!(report.file_count(), 1);
+}
+
+/// 已分类的 Lua 行数，用于观察超时后工作任务是否还在统计
+static CLASSIFIED: AtomicUsize = AtomicUsize::new(0);
+
+/// 每行都很慢的分类器，使扫描在超时时一定还在进行；`classified` 记录已分类的行数
+struct SlowClassifier {
+    classified: Option<&'static AtomicUsize>,
+}
+
+impl Classifier for SlowClassifier {
+    fn classify(&self, line: LineCtx) -> LineClassification {
+        std::thread::sleep(Duration::from_millis(1));
+        if let Some(classified) = self.classified {
+            classified.fetch_add(1, Ordering::SeqCst);
+        }
+        let start = line.offset();
+        let end = start + line.trimmed().len();
+        LineClassification {
+            kind: LineKind::Code,
+            spans: vec![LineSpan { kind: SpanKind::Code, range: start..end }],
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_async_timeout_stops_running_workers() {
+    register_classifier(LangType::Lua, SlowClassifier { classified: Some(&CLASSIFIED) });
+    let (dir, config) = common::fixture((0..200).map(|i| (format!("f{}.lua", i), "x = 1\n".repeat(10))));
+    // 先统计一次，正则等惰性初始化不计入超时
+    Counter::new(Config::new()).count(dir.path().join("f0.lua")).unwrap();
+    let before = CLASSIFIED.load(Ordering::SeqCst);
+
+    let config = config.with_timeout(Duration::from_millis(100));
+
+    let err = AsyncFileCounter::new(config).with_workers(2).process().await.unwrap_err();
+    assert_eq!(err, ProcessError::TimedOut(Duration::from_millis(100)));
+
+    // 等正在阻塞线程中统计的文件完成，之后不应再有新的文件被统计
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let after_timeout = CLASSIFIED.load(Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(CLASSIFIED.load(Ordering::SeqCst), after_timeout);
+    assert!(after_timeout > before, "scan had not started");
+    assert!(after_timeout - before < 200 * 10, "scan ran to completion");
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use toukei::fc::FileCounter;
+use toukei::saver::{ReportExporter, TreemapExporter};
+
+#[test]
+fn test_dir_tree_nesting_and_sums() {
+    let (dir, config) = common::fixture([
+        ("src/main.rs", "fn main() {\n    run();\n}\n"),
+        ("src/parser/lexer.rs", "pub fn lex() {}\n"),
+        ("src/parser/ast.rs", "pub struct Ast;\n\nimpl Ast {}\n"),
+        ("tests/it.rs", "#[test]\nfn it() {}\n"),
+    ]);
+    let root = dir.path();
+
+    let report = FileCounter::new(config).process().unwrap();
+    let tree = report.dir_tree();
+
+    assert_eq!(tree.dir, root.display().to_string());
+    assert_eq!(tree.lines, 9);
+    assert_eq!(tree.children.iter().map(|c| c.dir.as_str()).collect::<Vec<_>>(), vec!["src", "tests"]);
+
+    let src = tree.child("src").unwrap();
+    assert_eq!(src.lines, 7);
+    let parser = src.child("parser").unwrap();
+    assert_eq!(parser.lines, 4);
+    assert!(parser.children.is_empty());
+    assert_eq!(tree.child("tests").unwrap().lines, 2);
+
+    let mut out = Vec::new();
+    TreemapExporter::new().export(&report, &mut out).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(json["lines"], 9);
+    assert_eq!(json["children"][0]["dir"], "src");
+    assert_eq!(json["children"][0]["children"][0]["dir"], "parser");
+    assert_eq!(json["children"][0]["children"][0]["lines"], 4);
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use toukei::cli::Cli;
+use toukei::fc::{verify_parity, FileCounter};
+use toukei::langs::lang_type::LangType;
+
+#[test]
+fn verify_reports_match_on_fixture_tree() {
+    let (_dir, config) = common::fixture([
+        ("src/main.rs", "/* entry */\nfn main() {\n\n    // hi\n    println!(\"hi\");\n}\n"),
+        ("src/app.py", "\"\"\"doc\"\"\"\n\ndef f():\n    return 1  # one\n"),
+        ("index.js", "// js\nconst a = 1;\n"),
+    ]);
+
+    let discrepancies = verify_parity(&config).unwrap();
+    assert!(discrepancies.is_empty(), "{:?}", discrepancies);
+    assert!(Cli::render_verify(&discrepancies).starts_with("match"));
+}
+
+#[test]
+fn verify_detects_injected_discrepancy() {
+    let (_dir, config) = common::fixture([("lib.rs", "// lib\npub fn f() {}\n"), ("app.py", "print(1)\n")]);
+    let sync_report = FileCounter::new(config).process().unwrap();
+    assert_eq!(sync_report.get_by_lang(&LangType::Rust).unwrap().comments, 1);
+    let mut tampered = sync_report.clone();
+    tampered.inner.get_mut(&LangType::Rust).unwrap().comments += 1;
+
+    let discrepancies = sync_report.diff(&tampered);
+    assert_eq!(discrepancies.len(), 1);
+    assert_eq!(discrepancies[0].field, "comments");
+
+    let rendered = Cli::render_verify(&discrepancies);
+    assert!(rendered.starts_with("mismatch"));
+    assert!(rendered.contains("Rust comments:"));
+}
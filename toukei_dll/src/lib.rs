@@ -169,13 +169,8 @@ pub unsafe extern "C" fn analyze_code(json_config: *const c_char) -> *mut c_char
         let config = Config::from(ffi_config);
 
         let report = if config.enable_async {
-            use tokio::runtime::Runtime;
-            let rt = match Runtime::new() {
-                Ok(rt) => rt,
-                Err(e) => return create_error_response(&format!("Failed to create async runtime: {}", e)),
-            };
-
-            match rt.block_on(run_async_analysis(config)) {
+            // 调用方可能已处于 tokio 运行时内，不能再嵌套创建运行时
+            match run_async_analysis(config) {
                 Ok(report) => report,
                 Err(e) => return create_error_response(&format!("Async analysis failed: {}", e)),
             }
@@ -258,16 +253,15 @@ fn run_sync_analysis(config: Config) -> Result<Report, String> {
     counter.process().map_err(|e| format!("Processing failed: {}", e))
 }
 
-/// 运行异步模式分析
-async fn run_async_analysis(config: Config) -> Result<Report, String> {
+/// 运行异步模式分析，已处于 tokio 运行时内时复用该运行时
+fn run_async_analysis(config: Config) -> Result<Report, String> {
     let mut async_counter = AsyncFileCounter::new(config.clone());
 
     if config.num_workers > 0 {
         async_counter = async_counter.with_workers(config.num_workers);
     }
 
-    async_counter.process()
-        .await
+    async_counter.process_blocking()
         .map_err(|e| format!("Async processing failed: {}", e))
 }
 